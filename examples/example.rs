@@ -1,5 +1,3 @@
-use tex_atlas;
-
 const EXAMPLE: &str = "assets/example.atlas";
 
 
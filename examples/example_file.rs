@@ -1,4 +1,3 @@
-use tex_atlas;
 use std::fs::File;
 
 const EXAMPLE: &str = "assets/example.atlas";
@@ -0,0 +1,463 @@
+#![allow(clippy::redundant_field_names)]
+
+use tex_atlas::{
+    AlphaMode,
+    ColorSpace,
+    ColorType,
+    MultiTextureAtlas2D,
+    Origin,
+    SizingPolicy,
+    TextureAtlas2D,
+    TextureAtlas2DBuilder,
+    TextureAtlas2DWarning,
+};
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::env;
+use std::fs;
+use std::process;
+
+
+const USAGE: &str = "\
+Usage: tex-atlas <command> [options]
+
+Commands:
+    pack <directory> -o <file>    Pack a directory tree of images into an atlas file
+    unpack <file> -o <directory>  Write every texture of an atlas file out as an image file
+    info <file> [--json]          Print the pages and textures of an atlas file
+    validate <file>               Check an atlas file for problems
+    convert <file> -o <file>      Convert the pages of an atlas file
+
+Options for pack:
+    --width <pixels>              The width of each page (default: 2048)
+    --height <pixels>             The height of each page (default: 2048)
+    --sizing <policy>             fixed, exact-fit, power-of-two, square-power-of-two, or
+                                  multiple-of:<pixels>
+    --color-type <type>           The color type of each page (default: rgba8)
+    --origin <origin>             top-left or bottom-left (default: bottom-left)
+    --alpha-mode <mode>           straight or premultiplied (default: straight)
+    --color-space <space>         srgb or linear (default: srgb)
+    --padding <pixels>            The empty space between neighboring textures
+    --border-padding <pixels>     The empty space between the textures and the page edges
+    --extrude <pixels>            The number of times to repeat the edges of each texture
+    --trim                        Trim the transparent borders off of each texture
+    --rotate                      Allow rotating textures to fit them into a page
+    --alias-duplicates            Pack identical textures once
+    --keep-extensions             Keep the file extensions in the texture names
+
+Options for info:
+    --json                        Print the report as JSON
+
+Options for convert:
+    --color-type <type>           Convert the pages to a color type
+    --alpha-mode <mode>           Convert the pages to an alpha mode
+    --color-space <space>         Convert the pages to a color space
+    --mipmaps                     Generate the mip levels of each page
+
+Color types: l8, la8, rgb8, rgba8, l16, la16, rgb16, rgba16, bgr8, bgra8";
+
+const COLOR_TYPES: [ColorType; 10] = [
+    ColorType::L8,
+    ColorType::La8,
+    ColorType::Rgb8,
+    ColorType::Rgba8,
+    ColorType::L16,
+    ColorType::La16,
+    ColorType::Rgb16,
+    ColorType::Rgba16,
+    ColorType::Bgr8,
+    ColorType::Bgra8,
+];
+
+/// The ways a command can fail.
+enum CommandError {
+    /// The command line arguments are malformed.
+    Usage(String),
+    /// The command could not do its job.
+    Failure(String),
+}
+
+/// The command line arguments following a command.
+struct Arguments {
+    /// The arguments that are not options.
+    positional: Vec<String>,
+    /// The options that take a value, along with their values.
+    values: HashMap<String, String>,
+    /// The options that do not take a value.
+    flags: HashSet<String>,
+}
+
+impl Arguments {
+    /// Parse the command line arguments following a command, given the options the
+    /// command accepts. Options with values are written either as `--option value` or
+    /// as `--option=value`.
+    fn parse(arguments: &[String], value_options: &[&str], flag_options: &[&str]) -> Result<Arguments, CommandError> {
+        let mut positional = vec![];
+        let mut values = HashMap::new();
+        let mut flags = HashSet::new();
+        let mut i = 0;
+        while i < arguments.len() {
+            let argument = &arguments[i];
+            if !argument.starts_with('-') || argument == "-" {
+                positional.push(argument.clone());
+                i += 1;
+                continue;
+            }
+
+            let (option, inline_value) = match argument.find('=') {
+                Some(position) => (&argument[..position], Some(String::from(&argument[(position + 1)..]))),
+                None => (argument.as_str(), None),
+            };
+            if value_options.contains(&option) {
+                let value = match inline_value {
+                    Some(value) => value,
+                    None => {
+                        i += 1;
+                        arguments.get(i).cloned().ok_or_else(|| {
+                            CommandError::Usage(format!("The option {} needs a value.", option))
+                        })?
+                    }
+                };
+                values.insert(String::from(option), value);
+            } else if flag_options.contains(&option) && inline_value.is_none() {
+                flags.insert(String::from(option));
+            } else {
+                return Err(CommandError::Usage(format!("Unknown option {}.", argument)));
+            }
+            i += 1;
+        }
+
+        Ok(Arguments {
+            positional: positional,
+            values: values,
+            flags: flags,
+        })
+    }
+
+    /// Get the single positional argument of a command.
+    fn input(&self) -> Result<&str, CommandError> {
+        match self.positional.as_slice() {
+            [input] => Ok(input),
+            [] => Err(CommandError::Usage(String::from("Missing the input path."))),
+            _ => Err(CommandError::Usage(String::from("Too many input paths."))),
+        }
+    }
+
+    /// Get the output path of a command.
+    fn output(&self) -> Result<&str, CommandError> {
+        self.value("-o")
+            .or_else(|| self.value("--output"))
+            .ok_or_else(|| CommandError::Usage(String::from("Missing the output path.")))
+    }
+
+    fn value(&self, option: &str) -> Option<&str> {
+        self.values.get(option).map(|value| value.as_str())
+    }
+
+    fn flag(&self, option: &str) -> bool {
+        self.flags.contains(option)
+    }
+
+    /// Parse the value of an option, if it was given.
+    fn parse_value<T, F>(&self, option: &str, parse: F) -> Result<Option<T>, CommandError>
+        where F: Fn(&str) -> Option<T>
+    {
+        match self.value(option) {
+            Some(value) => parse(value).map(Some).ok_or_else(|| {
+                CommandError::Usage(format!("Invalid value {} for the option {}.", value, option))
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+fn parse_color_type(value: &str) -> Option<ColorType> {
+    COLOR_TYPES.iter()
+        .find(|color_type| format!("{:?}", color_type).eq_ignore_ascii_case(value))
+        .copied()
+}
+
+fn parse_origin(value: &str) -> Option<Origin> {
+    match value {
+        "top-left" => Some(Origin::TopLeft),
+        "bottom-left" => Some(Origin::BottomLeft),
+        _ => None,
+    }
+}
+
+fn parse_alpha_mode(value: &str) -> Option<AlphaMode> {
+    match value {
+        "straight" => Some(AlphaMode::Straight),
+        "premultiplied" => Some(AlphaMode::Premultiplied),
+        _ => None,
+    }
+}
+
+fn parse_color_space(value: &str) -> Option<ColorSpace> {
+    match value {
+        "srgb" => Some(ColorSpace::Srgb),
+        "linear" => Some(ColorSpace::Linear),
+        _ => None,
+    }
+}
+
+fn parse_sizing_policy(value: &str) -> Option<SizingPolicy> {
+    match value {
+        "fixed" => Some(SizingPolicy::Fixed),
+        "exact-fit" => Some(SizingPolicy::ExactFit),
+        "power-of-two" => Some(SizingPolicy::PowerOfTwo),
+        "square-power-of-two" => Some(SizingPolicy::SquarePowerOfTwo),
+        _ => value.strip_prefix("multiple-of:")
+            .and_then(parse_size)
+            .filter(|multiple| *multiple > 0)
+            .map(SizingPolicy::MultipleOf),
+    }
+}
+
+fn parse_size(value: &str) -> Option<usize> {
+    value.parse().ok()
+}
+
+/// Load an atlas file, along with the warnings generated while loading it.
+fn load(path: &str) -> Result<(MultiTextureAtlas2D, Vec<TextureAtlas2DWarning>), CommandError> {
+    let result = tex_atlas::load_file(path).map_err(|e| {
+        CommandError::Failure(format!("Could not load {}: {}", path, e))
+    })?;
+
+    Ok((result.multi_atlas, result.warnings))
+}
+
+/// Write an atlas file at exactly the given path. Unlike `tex_atlas::write_to_file`, 
+/// this keeps the file extension the user gave.
+fn write(path: &str, multi_atlas: &MultiTextureAtlas2D) -> Result<(), CommandError> {
+    let file = fs::File::create(path).map_err(|e| {
+        CommandError::Failure(format!("Could not write {}: {}", path, e))
+    })?;
+    tex_atlas::to_writer(file, multi_atlas).map_err(|e| {
+        CommandError::Failure(format!("Could not write {}: {}", path, e))
+    })
+}
+
+/// Get the indices of the textures in an atlas in increasing order.
+fn sorted_indices(atlas: &TextureAtlas2D) -> Vec<usize> {
+    let mut indices = atlas.indices();
+    indices.sort_unstable();
+
+    indices
+}
+
+/// Get the bounding box of a texture as the position of its top left corner in the
+/// atlas image, counting rows from the top, followed by its width and height. This
+/// returns `None` if the bounding box does not fit inside the atlas image.
+fn image_rect(atlas: &TextureAtlas2D, index: usize) -> Option<(usize, usize, usize, usize)> {
+    let bounding_box = atlas.by_index(index)?;
+    if bounding_box.top_left.v >= atlas.height {
+        return None;
+    }
+    let (x, y, width, height) = bounding_box.to_image_rect(atlas.origin(), atlas.height);
+    if x + width > atlas.width || y + height > atlas.height {
+        return None;
+    }
+
+    Some((x, y, width, height))
+}
+
+fn pack(arguments: &[String]) -> Result<(), CommandError> {
+    let arguments = Arguments::parse(
+        arguments,
+        &[
+            "-o", "--output", "--width", "--height", "--sizing", "--color-type", "--origin",
+            "--alpha-mode", "--color-space", "--padding", "--border-padding", "--extrude",
+        ],
+        &["--trim", "--rotate", "--alias-duplicates", "--keep-extensions"]
+    )?;
+    let input = arguments.input()?;
+    let output = arguments.output()?;
+    let options = tex_atlas::DirectoryOptions {
+        strip_extensions: !arguments.flag("--keep-extensions"),
+        ..tex_atlas::DirectoryOptions::default()
+    };
+    let mut builder = TextureAtlas2DBuilder::from_directory_with_options(input, &options).map_err(|e| {
+        CommandError::Failure(format!("Could not read {}: {}", input, e))
+    })?;
+
+    let width = arguments.parse_value("--width", parse_size)?.unwrap_or(2048);
+    let height = arguments.parse_value("--height", parse_size)?.unwrap_or(2048);
+    builder.dimensions(width, height);
+    if let Some(sizing_policy) = arguments.parse_value("--sizing", parse_sizing_policy)? {
+        builder.sizing_policy(sizing_policy);
+    }
+    if let Some(color_type) = arguments.parse_value("--color-type", parse_color_type)? {
+        builder.color_type(color_type);
+    }
+    if let Some(origin) = arguments.parse_value("--origin", parse_origin)? {
+        builder.origin(origin);
+    }
+    if let Some(alpha_mode) = arguments.parse_value("--alpha-mode", parse_alpha_mode)? {
+        builder.alpha_mode(alpha_mode);
+    }
+    if let Some(color_space) = arguments.parse_value("--color-space", parse_color_space)? {
+        builder.color_space(color_space);
+    }
+    if let Some(padding) = arguments.parse_value("--padding", parse_size)? {
+        builder.padding(padding);
+    }
+    if let Some(border_padding) = arguments.parse_value("--border-padding", parse_size)? {
+        builder.border_padding(border_padding);
+    }
+    if let Some(extrude) = arguments.parse_value("--extrude", parse_size)? {
+        builder.extrude(extrude);
+    }
+    builder
+        .trim(arguments.flag("--trim"))
+        .allow_rotation(arguments.flag("--rotate"))
+        .alias_duplicates(arguments.flag("--alias-duplicates"));
+
+    let multi_atlas = builder.build_multi().map_err(|e| {
+        CommandError::Failure(format!("Could not pack {}: {}", input, e))
+    })?;
+    write(output, &multi_atlas)?;
+    println!(
+        "Packed {} textures into {} pages.",
+        multi_atlas.pages().iter().map(|atlas| atlas.texture_count()).sum::<usize>(),
+        multi_atlas.page_count()
+    );
+
+    Ok(())
+}
+
+fn unpack(arguments: &[String]) -> Result<(), CommandError> {
+    let arguments = Arguments::parse(arguments, &["-o", "--output"], &[])?;
+    let input = arguments.input()?;
+    let output = arguments.output()?;
+    let (multi_atlas, _) = load(input)?;
+    tex_atlas::unpack_to_directory(&multi_atlas, output).map_err(|e| {
+        CommandError::Failure(format!("Could not unpack {}: {}", input, e))
+    })
+}
+
+fn info(arguments: &[String]) -> Result<(), CommandError> {
+    let arguments = Arguments::parse(arguments, &[], &["--json"])?;
+    let input = arguments.input()?;
+    let (multi_atlas, warnings) = load(input)?;
+    let description = tex_atlas::describe(&multi_atlas);
+    if arguments.flag("--json") {
+        println!("{}", description.to_json());
+        return Ok(());
+    }
+
+    print!("{}", description.to_table());
+    for (atlas, warning) in multi_atlas.pages().iter().zip(warnings.iter()) {
+        if *warning == TextureAtlas2DWarning::TextureDimensionsAreNotAPowerOfTwo {
+            println!("warning: the dimensions of page {} are not a power of two", atlas.atlas_name());
+        }
+    }
+
+    Ok(())
+}
+
+fn validate(arguments: &[String]) -> Result<(), CommandError> {
+    let arguments = Arguments::parse(arguments, &[], &[])?;
+    let input = arguments.input()?;
+    let (multi_atlas, warnings) = load(input)?;
+    let mut problems = vec![];
+    for (atlas, warning) in multi_atlas.pages().iter().zip(warnings.iter()) {
+        if *warning == TextureAtlas2DWarning::TextureDimensionsAreNotAPowerOfTwo {
+            println!("warning: the dimensions of page {} are not a power of two", atlas.atlas_name());
+        }
+        let mut rects = vec![];
+        for index in sorted_indices(atlas) {
+            let name = atlas.by_index_name(index).unwrap_or_default();
+            match image_rect(atlas, index) {
+                Some(rect) => rects.push((name, rect)),
+                None => problems.push(format!(
+                    "texture {} lies outside of page {}", name, atlas.atlas_name()
+                )),
+            }
+        }
+        for (i, (name_i, (x_i, y_i, width_i, height_i))) in rects.iter().enumerate() {
+            for (name_j, (x_j, y_j, width_j, height_j)) in rects[(i + 1)..].iter() {
+                let overlaps = x_i < &(x_j + width_j) && x_j < &(x_i + width_i) &&
+                    y_i < &(y_j + height_j) && y_j < &(y_i + height_i);
+                if overlaps {
+                    problems.push(format!(
+                        "textures {} and {} overlap in page {}", name_i, name_j, atlas.atlas_name()
+                    ));
+                }
+            }
+        }
+    }
+
+    for problem in problems.iter() {
+        println!("error: {}", problem);
+    }
+    if problems.is_empty() {
+        println!("{} is valid.", input);
+        Ok(())
+    } else {
+        Err(CommandError::Failure(format!("{} has {} problems.", input, problems.len())))
+    }
+}
+
+fn convert(arguments: &[String]) -> Result<(), CommandError> {
+    let arguments = Arguments::parse(
+        arguments, &["-o", "--output", "--color-type", "--alpha-mode", "--color-space"], &["--mipmaps"]
+    )?;
+    let input = arguments.input()?;
+    let output = arguments.output()?;
+    let color_type = arguments.parse_value("--color-type", parse_color_type)?;
+    let alpha_mode = arguments.parse_value("--alpha-mode", parse_alpha_mode)?;
+    let color_space = arguments.parse_value("--color-space", parse_color_space)?;
+    let (multi_atlas, _) = load(input)?;
+    let mut pages = vec![];
+    for atlas in multi_atlas.pages() {
+        let mut atlas = atlas.clone();
+        if let Some(color_type) = color_type {
+            atlas = atlas.convert_to(color_type);
+        }
+        if let Some(color_space) = color_space {
+            atlas = atlas.convert_color_space(color_space);
+        }
+        if let Some(alpha_mode) = alpha_mode {
+            atlas = atlas.convert_alpha_mode(alpha_mode);
+        }
+        if arguments.flag("--mipmaps") {
+            atlas.generate_mipmaps();
+        }
+        pages.push(atlas);
+    }
+
+    write(output, &MultiTextureAtlas2D::new(pages))
+}
+
+fn main() {
+    let arguments: Vec<String> = env::args().skip(1).collect();
+    let result = match arguments.first().map(|command| command.as_str()) {
+        Some("pack") => pack(&arguments[1..]),
+        Some("unpack") => unpack(&arguments[1..]),
+        Some("info") => info(&arguments[1..]),
+        Some("validate") => validate(&arguments[1..]),
+        Some("convert") => convert(&arguments[1..]),
+        Some("help") | Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(CommandError::Usage(format!("Unknown command {}.", command))),
+        None => Err(CommandError::Usage(String::from("Missing a command."))),
+    };
+
+    match result {
+        Ok(()) => {}
+        Err(CommandError::Usage(message)) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+        Err(CommandError::Failure(message)) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+}
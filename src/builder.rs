@@ -0,0 +1,752 @@
+use crate::packer::{
+    PackedRect,
+    PackingAlgorithm,
+};
+use crate::{
+    AlphaMode,
+    AtlasEntry,
+    BoundingBoxPixelCoords,
+    ColorSpace,
+    ColorType,
+    ErrorKind,
+    MultiTextureAtlas2D,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+    TextureImage2D,
+};
+
+use std::collections::hash_map::{
+    DefaultHasher,
+    HashMap,
+};
+use std::fs;
+use std::hash::{
+    Hash,
+    Hasher,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+
+/// A texture that is ready for packing into an atlas.
+#[derive(Clone, Debug)]
+struct PreparedImage {
+    /// The name of the texture.
+    name: String,
+    /// The names of the textures with identical images that share this texture's 
+    /// place in the atlas.
+    aliases: Vec<String>,
+    /// The texture image, converted to the color type of the atlas image.
+    image: TextureImage2D,
+    /// The dimensions of the original image of the texture, and where the texture 
+    /// sits inside it.
+    source: SourceImagePixelCoords,
+}
+
+/// The policy for choosing the dimensions of each atlas page.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum SizingPolicy {
+    /// Every page has exactly the dimensions of the builder.
+    #[default]
+    Fixed,
+    /// Each page is cropped down to the smallest size that holds its textures.
+    ExactFit,
+    /// Each page is the smallest power of two size that holds its textures. The width
+    /// and the height can differ.
+    PowerOfTwo,
+    /// Each page is the smallest square power of two size that holds its textures.
+    SquarePowerOfTwo,
+    /// Each page is cropped down to the smallest size that holds its textures, rounded 
+    /// up to a multiple of the given number of pixels in each direction. The maximum 
+    /// dimensions of the pages must be at least this multiple.
+    MultipleOf(usize),
+}
+
+impl SizingPolicy {
+    /// Determine whether a page size satisfies the sizing policy.
+    fn is_satisfied_by(self, width: usize, height: usize) -> bool {
+        match self {
+            SizingPolicy::Fixed | SizingPolicy::ExactFit => true,
+            SizingPolicy::PowerOfTwo => width.is_power_of_two() && height.is_power_of_two(),
+            SizingPolicy::SquarePowerOfTwo => width == height && width.is_power_of_two(),
+            SizingPolicy::MultipleOf(multiple) => {
+                let multiple = usize::max(multiple, 1);
+                width.is_multiple_of(multiple) && height.is_multiple_of(multiple)
+            }
+        }
+    }
+
+    /// Get the largest page size satisfying the sizing policy that fits inside the 
+    /// given maximum dimensions.
+    fn largest_size(self, max_width: usize, max_height: usize) -> (usize, usize) {
+        let previous_power_of_two = |n: usize| {
+            if n == 0 { 0 } else { 1 << (usize::BITS - 1 - n.leading_zeros()) }
+        };
+        match self {
+            SizingPolicy::Fixed | SizingPolicy::ExactFit => (max_width, max_height),
+            SizingPolicy::PowerOfTwo => (previous_power_of_two(max_width), previous_power_of_two(max_height)),
+            SizingPolicy::SquarePowerOfTwo => {
+                let side = previous_power_of_two(usize::min(max_width, max_height));
+                (side, side)
+            }
+            SizingPolicy::MultipleOf(multiple) => {
+                let multiple = usize::max(multiple, 1);
+                (max_width - max_width % multiple, max_height - max_height % multiple)
+            }
+        }
+    }
+}
+
+/// The options for loading a directory tree of texture images into an atlas builder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectoryOptions {
+    /// The string joining the components of the path of an image relative to the 
+    /// directory into the name of its texture.
+    pub separator: String,
+    /// Whether to strip the file extension off of each texture name.
+    pub strip_extensions: bool,
+    /// Whether to load every image format the `image` library supports instead of
+    /// only PNG images.
+    pub all_image_formats: bool,
+}
+
+impl Default for DirectoryOptions {
+    /// By default, texture names are the relative paths of the PNG images in the directory,
+    /// with the components joined by `/` and the file extensions stripped off.
+    fn default() -> DirectoryOptions {
+        DirectoryOptions {
+            separator: String::from("/"),
+            strip_extensions: true,
+            all_image_formats: false,
+        }
+    }
+}
+
+impl DirectoryOptions {
+    /// Determine whether a file is a texture image to load.
+    fn is_texture_image(&self, path: &Path) -> bool {
+        if self.all_image_formats {
+            image::ImageFormat::from_path(path).is_ok()
+        } else {
+            path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("png"))
+        }
+    }
+
+    /// Derive the name of a texture from the path of its image relative to the directory.
+    fn texture_name(&self, relative_path: &Path) -> String {
+        let path = if self.strip_extensions {
+            relative_path.with_extension("")
+        } else {
+            relative_path.to_path_buf()
+        };
+        let components: Vec<String> = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        components.join(&self.separator)
+    }
+}
+
+/// Collect the paths of every file in a directory tree, sorted so that the order 
+/// does not depend on the file system.
+fn collect_files(directory: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<PathBuf>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// A builder for constructing a texture atlas out of a collection of individual images.
+/// The builder packs the images into a single atlas image and computes the bounding box
+/// for each texture in the atlas. The index of each texture in the resulting atlas is the
+/// order in which the texture was added to the builder.
+#[derive(Clone, Debug)]
+pub struct TextureAtlas2DBuilder {
+    /// The name of the texture atlas.
+    atlas_name: String,
+    /// The width of the texture atlas in pixels. This is the maximum width of each page 
+    /// when the sizing policy shrinks the pages.
+    width: usize,
+    /// The height of the texture atlas in pixels. This is the maximum height of each page 
+    /// when the sizing policy shrinks the pages.
+    height: usize,
+    /// The policy for choosing the dimensions of each page.
+    sizing_policy: SizingPolicy,
+    /// The color space of the atlas image.
+    color_type: ColorType,
+    /// The origin in the atlas image for orienting the texture.
+    origin: Origin,
+    /// Whether to premultiply the color channels of the atlas image by alpha.
+    alpha_mode: AlphaMode,
+    /// The color space of the textures added to the builder.
+    color_space: ColorSpace,
+    /// The algorithm for packing the textures into the atlas image.
+    algorithm: PackingAlgorithm,
+    /// The number of pixels of empty space between neighboring textures.
+    padding: usize,
+    /// The number of pixels of empty space between the textures and the edges of 
+    /// the atlas image.
+    border_padding: usize,
+    /// The number of times to duplicate the outermost ring of pixels of each texture.
+    extrude: usize,
+    /// Whether to trim the fully transparent borders off of each texture.
+    trim: bool,
+    /// Whether the packer may rotate textures by 90 degrees to fit them into the atlas.
+    allow_rotation: bool,
+    /// Whether to pack identical textures once and register their names as aliases.
+    alias_duplicates: bool,
+    /// The textures to pack into the atlas, along with their names.
+    images: Vec<(String, TextureImage2D)>,
+}
+
+impl TextureAtlas2DBuilder {
+    /// Construct a new texture atlas builder for an atlas image with the given
+    /// dimensions. By default, the atlas image has a color type of `Rgba8`, the
+    /// origin is the bottom left corner of the atlas image, and the textures are packed
+    /// with a maximal rectangles packer.
+    pub fn new(atlas_name: &str, width: usize, height: usize) -> TextureAtlas2DBuilder {
+        TextureAtlas2DBuilder {
+            atlas_name: String::from(atlas_name),
+            width: width,
+            height: height,
+            sizing_policy: SizingPolicy::default(),
+            color_type: ColorType::Rgba8,
+            origin: Origin::BottomLeft,
+            alpha_mode: AlphaMode::Straight,
+            color_space: ColorSpace::Srgb,
+            algorithm: PackingAlgorithm::default(),
+            padding: 0,
+            border_padding: 0,
+            extrude: 0,
+            trim: false,
+            allow_rotation: false,
+            alias_duplicates: false,
+            images: vec![],
+        }
+    }
+
+    /// Construct a new texture atlas builder from the PNG images in a directory tree, using
+    /// the default directory options. The atlas is named after the directory, and has 
+    /// dimensions of 2048 x 2048 pixels until they are changed with `dimensions`.
+    pub fn from_directory<P: AsRef<Path>>(path: P) -> Result<TextureAtlas2DBuilder, TextureAtlas2DError> {
+        TextureAtlas2DBuilder::from_directory_with_options(path, &DirectoryOptions::default())
+    }
+
+    /// Construct a new texture atlas builder from the images in a directory tree. The 
+    /// atlas is named after the directory, and has dimensions of 2048 x 2048 pixels until 
+    /// they are changed with `dimensions`.
+    pub fn from_directory_with_options<P: AsRef<Path>>(
+        path: P, options: &DirectoryOptions) -> Result<TextureAtlas2DBuilder, TextureAtlas2DError> {
+        
+        let path = path.as_ref();
+        let atlas_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("atlas"));
+        let mut builder = TextureAtlas2DBuilder::new(&atlas_name, 2048, 2048);
+        builder.add_directory(path, options)?;
+
+        Ok(builder)
+    }
+
+    /// Set the dimensions of the atlas image.
+    pub fn dimensions(&mut self, width: usize, height: usize) -> &mut TextureAtlas2DBuilder {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set the policy for choosing the dimensions of each page. With any policy other 
+    /// than a fixed size, the dimensions of the builder are the maximum dimensions of 
+    /// each page, and each page shrinks to the smallest size satisfying the policy that 
+    /// holds its textures.
+    pub fn sizing_policy(&mut self, sizing_policy: SizingPolicy) -> &mut TextureAtlas2DBuilder {
+        self.sizing_policy = sizing_policy;
+        self
+    }
+
+    /// Set the color type of the atlas image. Every texture gets converted to this
+    /// color type when it is packed into the atlas.
+    pub fn color_type(&mut self, color_type: ColorType) -> &mut TextureAtlas2DBuilder {
+        self.color_type = color_type;
+        self
+    }
+
+    /// Set the origin of the atlas image.
+    pub fn origin(&mut self, origin: Origin) -> &mut TextureAtlas2DBuilder {
+        self.origin = origin;
+        self
+    }
+
+    /// Set the alpha mode of the atlas image. The textures added to the builder have 
+    /// straight alpha, and get premultiplied after packing when the alpha mode is 
+    /// premultiplied, so that extruded edges get premultiplied too.
+    pub fn alpha_mode(&mut self, alpha_mode: AlphaMode) -> &mut TextureAtlas2DBuilder {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    /// Set the color space of the atlas image. The textures added to the builder must 
+    /// already be in this color space, since packing does not convert them. Atlases of 
+    /// data textures such as normal maps and masks should be linear.
+    pub fn color_space(&mut self, color_space: ColorSpace) -> &mut TextureAtlas2DBuilder {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Set the algorithm for packing the textures into the atlas image.
+    pub fn algorithm(&mut self, algorithm: PackingAlgorithm) -> &mut TextureAtlas2DBuilder {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the number of pixels of empty space between neighboring textures in the atlas.
+    pub fn padding(&mut self, padding: usize) -> &mut TextureAtlas2DBuilder {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the number of pixels of empty space between the textures and the edges 
+    /// of the atlas image.
+    pub fn border_padding(&mut self, border_padding: usize) -> &mut TextureAtlas2DBuilder {
+        self.border_padding = border_padding;
+        self
+    }
+
+    /// Set the number of pixels to extrude the edges of each texture by. Extrusion 
+    /// duplicates the outermost ring of pixels of a texture into the space around it, so 
+    /// that texture filtering near the edges of a texture does not sample its neighbors.
+    /// The bounding box of each texture covers only the original texture, not the 
+    /// extruded pixels.
+    pub fn extrude(&mut self, extrude: usize) -> &mut TextureAtlas2DBuilder {
+        self.extrude = extrude;
+        self
+    }
+
+    /// Set whether to trim the fully transparent borders off of each texture before packing 
+    /// it. The atlas records the dimensions of the original image of each texture and 
+    /// the position of the trimmed texture inside it.
+    pub fn trim(&mut self, trim: bool) -> &mut TextureAtlas2DBuilder {
+        self.trim = trim;
+        self
+    }
+
+    /// Set whether the packer may rotate textures by 90 degrees clockwise to fit them into 
+    /// the atlas image. The atlas records which textures are stored rotated, and the 
+    /// corners of a rotated texture are labeled by where they appear when the texture 
+    /// is upright.
+    pub fn allow_rotation(&mut self, allow_rotation: bool) -> &mut TextureAtlas2DBuilder {
+        self.allow_rotation = allow_rotation;
+        self
+    }
+
+    /// Set whether to pack textures with identical images only once. The names of the 
+    /// duplicate textures become aliases of the first texture with that image, so every 
+    /// alias refers to the same bounding box. Two textures are identical when their 
+    /// images are identical after trimming and color conversion, and they were trimmed 
+    /// from the same place in original images of the same size. Aliases do not take up 
+    /// an index in the atlas, so the remaining textures are indexed in the order they 
+    /// were added to the builder with the duplicates skipped.
+    pub fn alias_duplicates(&mut self, alias_duplicates: bool) -> &mut TextureAtlas2DBuilder {
+        self.alias_duplicates = alias_duplicates;
+        self
+    }
+
+    /// Add a texture to the atlas from a raw image buffer. The rows of the image buffer
+    /// are ordered from the top of the image to the bottom of the image.
+    pub fn add_image(
+        &mut self,
+        name: &str, width: usize, height: usize, color_type: ColorType, data: &[u8]) -> &mut TextureAtlas2DBuilder {
+
+        let image = TextureImage2D::new(width, height, color_type, data.to_vec());
+        self.images.push((String::from(name), image));
+        self
+    }
+
+    /// Add a texture to the atlas from an image from the `image` library.
+    pub fn add_dynamic_image(&mut self, name: &str, image: &image::DynamicImage) -> &mut TextureAtlas2DBuilder {
+        use image::GenericImageView;
+
+        let texture = TextureImage2D::from_dynamic_image(image).unwrap_or_else(|| {
+            // Every color type in the image library other than the floating point
+            // formats has an equivalent color type, so we convert anything else to RGBA.
+            let (width, height) = image.dimensions();
+            let data = image.to_rgba8().into_raw();
+            TextureImage2D::new(width as usize, height as usize, ColorType::Rgba8, data)
+        });
+        self.images.push((String::from(name), texture));
+        self
+    }
+
+    /// Add every image in a directory tree to the atlas. Each texture is named after the 
+    /// path of its image relative to the directory, and the textures are added in 
+    /// lexicographic order of their paths. Files that are not texture images are skipped.
+    pub fn add_directory<P: AsRef<Path>>(
+        &mut self, 
+        path: P, options: &DirectoryOptions) -> Result<&mut TextureAtlas2DBuilder, TextureAtlas2DError> {
+        
+        let directory = path.as_ref();
+        let mut files = vec![];
+        collect_files(directory, &mut files).map_err(|e| {
+            TextureAtlas2DError::new(
+                ErrorKind::CouldNotReadDirectory, None, Some(self.atlas_name.clone()), Some(Box::new(e))
+            )
+        })?;
+        for file in files.iter().filter(|file| options.is_texture_image(file)) {
+            let image = image::open(file).map_err(|e| {
+                TextureAtlas2DError::new(
+                    ErrorKind::CouldNotLoadTextureImage, None, Some(self.atlas_name.clone()), Some(Box::new(e))
+                )
+            })?;
+            // Every file we collected lives inside the directory.
+            let relative_path = file.strip_prefix(directory).unwrap_or(file);
+            let name = options.texture_name(relative_path);
+            self.add_dynamic_image(&name, &image);
+        }
+
+        Ok(self)
+    }
+
+    /// Get the number of textures added to the builder.
+    #[inline]
+    pub fn texture_count(&self) -> usize {
+        self.images.len()
+    }
+
+    /// Construct an error for the atlas under construction.
+    fn error(&self, kind: ErrorKind) -> TextureAtlas2DError {
+        TextureAtlas2DError::new(kind, None, Some(self.atlas_name.clone()), None)
+    }
+
+    /// Check that every texture has a valid image buffer and a unique name, trim the 
+    /// textures if trimming is enabled, and convert each one to the color type of the 
+    /// atlas image.
+    fn prepare_images(&self) -> Result<Vec<PreparedImage>, TextureAtlas2DError> {
+        let mut names = HashMap::new();
+        let mut images = vec![];
+        for (i, (name, image)) in self.images.iter().enumerate() {
+            if names.insert(name.as_str(), i).is_some() {
+                return Err(self.error(ErrorKind::DuplicateTextureName));
+            }
+            if !image.is_valid() {
+                return Err(self.error(ErrorKind::InvalidImageData));
+            }
+
+            let (trimmed, source) = if self.trim {
+                // A fully transparent image trims down to a single transparent pixel.
+                let (x, y, width, height) = image.opaque_bounds().unwrap_or((0, 0, 1, 1));
+                let source = SourceImagePixelCoords {
+                    offset: OffsetPixelCoords::new(x, y),
+                    width: image.width,
+                    height: image.height,
+                };
+                (image.crop(x, y, width, height), source)
+            } else {
+                (image.clone(), SourceImagePixelCoords::untrimmed(image.width, image.height))
+            };
+            let converted = trimmed.convert(self.color_type).ok_or_else(|| {
+                self.error(ErrorKind::InvalidImageData)
+            })?;
+            images.push(PreparedImage { 
+                name: name.clone(), 
+                aliases: vec![], 
+                image: converted, 
+                source: source,
+            });
+        }
+
+        if self.alias_duplicates {
+            Ok(merge_duplicates(images))
+        } else {
+            Ok(images)
+        }
+    }
+
+    /// Pack the textures into as many atlas pages as it takes to fit all of them. Each page 
+    /// lists the textures placed on it by texture index, along with their positions in the 
+    /// atlas image excluding padding and extrusion, in the order the textures were added 
+    /// to the builder. The dimensions of a rotated texture are the dimensions of the 
+    /// rotated image.
+    fn pack_pages(
+        &self, 
+        images: &[PreparedImage], width: usize, height: usize) -> Result<Vec<Vec<(usize, PackedRect)>>, TextureAtlas2DError> {
+        
+        let mut remaining = self.packing_order(images, (0..images.len()).collect());
+        let mut pages = vec![];
+        while !remaining.is_empty() {
+            let (mut page, overflow) = self.pack_page(images, remaining, width, height);
+
+            // A texture that does not fit on an empty page does not fit on any page.
+            if page.is_empty() {
+                return Err(self.error(ErrorKind::TextureDoesNotFitInAtlas));
+            }
+            page.sort_by_key(|(i, _)| *i);
+            pages.push(page);
+            remaining = overflow;
+        }
+
+        Ok(pages)
+    }
+
+    /// Sort the textures into the order the packer places them in. Most packers perform 
+    /// best when the largest rectangles are placed first. Shelf packing in particular works 
+    /// best when the rectangles are sorted by decreasing height.
+    fn packing_order(&self, images: &[PreparedImage], mut indices: Vec<usize>) -> Vec<usize> {
+        match self.algorithm {
+            PackingAlgorithm::Shelf => {
+                indices.sort_by(|a, b| images[*b].image.height.cmp(&images[*a].image.height));
+            }
+            _ => {
+                indices.sort_by_key(|i| {
+                    let image = &images[*i].image;
+                    std::cmp::Reverse((usize::max(image.width, image.height), usize::min(image.width, image.height)))
+                });
+            }
+        }
+
+        indices
+    }
+
+    /// Pack as many of the given textures as will fit onto a single page of the given
+    /// dimensions, in order. This returns the textures placed on the page, and the 
+    /// textures that overflow it.
+    fn pack_page(
+        &self, 
+        images: &[PreparedImage], indices: Vec<usize>, width: usize, height: usize) -> (Vec<(usize, PackedRect)>, Vec<usize>) {
+        
+        // Each texture takes up its extruded size plus the padding on every page. Enlarging 
+        // the packing region by the padding lets the last texture in each row or column
+        // reach the border without the trailing padding.
+        let packing_width = (width + self.padding).saturating_sub(2 * self.border_padding);
+        let packing_height = (height + self.padding).saturating_sub(2 * self.border_padding);
+        let margin = 2 * self.extrude + self.padding;
+        let mut packer = self.algorithm.packer(packing_width, packing_height);
+        let mut page = vec![];
+        let mut overflow = vec![];
+        for i in indices {
+            let image = &images[i].image;
+            let packed = if self.allow_rotation {
+                packer.insert_rotatable(image.width + margin, image.height + margin)
+            } else {
+                packer.insert(image.width + margin, image.height + margin)
+            };
+            match packed {
+                Some(rect) => {
+                    let x = rect.x + self.border_padding + self.extrude;
+                    let y = rect.y + self.border_padding + self.extrude;
+                    let mut placed = if rect.rotated {
+                        PackedRect::new(x, y, image.height, image.width)
+                    } else {
+                        PackedRect::new(x, y, image.width, image.height)
+                    };
+                    placed.rotated = rect.rotated;
+                    page.push((i, placed));
+                }
+                None => overflow.push(i),
+            }
+        }
+
+        (page, overflow)
+    }
+
+    /// Find the smallest page dimensions satisfying the sizing policy that hold the 
+    /// textures on a page packed at the given dimensions. This returns the dimensions 
+    /// of the page, along with the positions of the textures on the resized page.
+    fn fit_page(
+        &self, 
+        images: &[PreparedImage], 
+        page: Vec<(usize, PackedRect)>, width: usize, height: usize) -> (usize, usize, Vec<(usize, PackedRect)>) {
+        
+        match self.sizing_policy {
+            SizingPolicy::Fixed => (width, height, page),
+            SizingPolicy::ExactFit | SizingPolicy::MultipleOf(_) => {
+                // The textures stay where they are, and the page shrinks to the extent of
+                // the textures, including their extrusion and the border padding.
+                let multiple = match self.sizing_policy {
+                    SizingPolicy::MultipleOf(multiple) => usize::max(multiple, 1),
+                    _ => 1,
+                };
+                let margin = self.extrude + self.border_padding;
+                let used_width = page.iter().map(|(_, rect)| rect.x + rect.width + margin).max().unwrap_or(1);
+                let used_height = page.iter().map(|(_, rect)| rect.y + rect.height + margin).max().unwrap_or(1);
+                let round_up = |n: usize| usize::max(n.div_ceil(multiple) * multiple, multiple);
+                let fitted_width = usize::min(round_up(used_width), width);
+                let fitted_height = usize::min(round_up(used_height), height);
+
+                (fitted_width, fitted_height, page)
+            }
+            SizingPolicy::PowerOfTwo | SizingPolicy::SquarePowerOfTwo => {
+                // Try every smaller page size satisfying the policy from the smallest area up,
+                // skipping the sizes too small to hold the area of the textures.
+                let indices: Vec<usize> = page.iter().map(|(i, _)| *i).collect();
+                let margin = 2 * self.extrude + self.padding;
+                let required_area: usize = indices.iter().map(|i| {
+                    (images[*i].image.width + margin) * (images[*i].image.height + margin)
+                }).sum();
+                let powers = |max: usize| {
+                    (0..usize::BITS).map(|k| 1_usize << k).take_while(move |side| *side <= max)
+                };
+                let mut candidates = vec![];
+                for candidate_width in powers(width) {
+                    for candidate_height in powers(height) {
+                        let is_smaller = candidate_width * candidate_height < width * height;
+                        let area = (candidate_width + self.padding) * (candidate_height + self.padding);
+                        if is_smaller && 
+                            area >= required_area && 
+                            self.sizing_policy.is_satisfied_by(candidate_width, candidate_height) {
+                            
+                            candidates.push((candidate_width, candidate_height));
+                        }
+                    }
+                }
+                candidates.sort_by_key(|(candidate_width, candidate_height)| {
+                    (candidate_width * candidate_height, usize::max(*candidate_width, *candidate_height))
+                });
+
+                let ordered = self.packing_order(images, indices);
+                for (candidate_width, candidate_height) in candidates {
+                    let (mut fitted, overflow) = self.pack_page(images, ordered.clone(), candidate_width, candidate_height);
+                    if overflow.is_empty() {
+                        fitted.sort_by_key(|(i, _)| *i);
+                        return (candidate_width, candidate_height, fitted);
+                    }
+                }
+
+                (width, height, page)
+            }
+        }
+    }
+
+    /// Copy the textures placed on a page into an atlas image of the given dimensions.
+    fn build_page(
+        &self, 
+        page_name: &str, 
+        images: &[PreparedImage], 
+        page: &[(usize, PackedRect)], width: usize, height: usize) -> TextureAtlas2D {
+        
+        // The atlas image rows are ordered from the top of the image to the bottom 
+        // of the image until we orient it at the end.
+        let mut atlas_image = TextureImage2D::new(
+            width, height, self.color_type, vec![0; width * height * self.color_type.bytes_per_pixel()]
+        );
+        let mut entries = vec![];
+        for (index, (i, rect)) in page.iter().enumerate() {
+            if rect.rotated {
+                atlas_image.copy_from(&images[*i].image.rotate_clockwise(), rect.x, rect.y);
+            } else {
+                atlas_image.copy_from(&images[*i].image, rect.x, rect.y);
+            }
+            atlas_image.extrude(rect.x, rect.y, rect.width, rect.height, self.extrude);
+            let bounding_box = BoundingBoxPixelCoords::from_image_rect(
+                self.origin, height, rect.x, rect.y, rect.width, rect.height
+            );
+            let mut entry = AtlasEntry::new(
+                images[*i].name.clone(), bounding_box, images[*i].source, width, height
+            );
+            entry.rotated = rect.rotated;
+            entry.aliases = images[*i].aliases.clone();
+            entries.push((index, entry));
+        }
+        let width_in_bytes = width * self.color_type.bytes_per_pixel();
+        crate::orient_image(&mut atlas_image.data, self.origin, height, width_in_bytes);
+
+        let mut atlas = TextureAtlas2D::from_atlas_entries(
+            width, height, self.color_type, self.origin, entries, String::from(page_name), atlas_image.data
+        );
+        atlas.set_color_space(self.color_space);
+
+        atlas.convert_alpha_mode(self.alpha_mode)
+    }
+
+    /// Get the largest page size satisfying the sizing policy that fits inside the 
+    /// dimensions of the builder. A multiple of pixels larger than the dimensions of the
+    /// builder would only leave an empty page, so this fails instead.
+    fn largest_page_size(&self) -> Result<(usize, usize), TextureAtlas2DError> {
+        let (width, height) = self.sizing_policy.largest_size(self.width, self.height);
+        if (width == 0 && self.width > 0) || (height == 0 && self.height > 0) {
+            return Err(self.error(ErrorKind::InvalidSizingPolicy));
+        }
+
+        Ok((width, height))
+    }
+
+    /// Pack the textures into a single texture atlas. This fails if the textures do not 
+    /// all fit inside one atlas image.
+    pub fn build(&self) -> Result<TextureAtlas2D, TextureAtlas2DError> {
+        let images = self.prepare_images()?;
+        let (width, height) = self.largest_page_size()?;
+        let mut pages = self.pack_pages(&images, width, height)?;
+        let page = match pages.len() {
+            0 => vec![],
+            1 => pages.remove(0),
+            _ => return Err(self.error(ErrorKind::TextureDoesNotFitInAtlas)),
+        };
+        let (page_width, page_height, page) = self.fit_page(&images, page, width, height);
+
+        Ok(self.build_page(&self.atlas_name, &images, &page, page_width, page_height))
+    }
+
+    /// Pack the textures into a multi texture atlas, opening a new page each time the 
+    /// textures overflow the current one. The dimensions of the builder are the dimensions
+    /// of each page, or the maximum dimensions of each page when the sizing policy shrinks 
+    /// the pages. The pages are named after the builder with the page number appended, 
+    /// starting from one, e.g. `atlas1`, `atlas2`, and so on.
+    pub fn build_multi(&self) -> Result<MultiTextureAtlas2D, TextureAtlas2DError> {
+        let images = self.prepare_images()?;
+        let (width, height) = self.largest_page_size()?;
+        let pages = self.pack_pages(&images, width, height)?;
+        let atlases = pages.into_iter().enumerate().map(|(i, page)| {
+            let page_name = format!("{}{}", self.atlas_name, i + 1);
+            let (page_width, page_height, page) = self.fit_page(&images, page, width, height);
+            self.build_page(&page_name, &images, &page, page_width, page_height)
+        }).collect();
+
+        Ok(MultiTextureAtlas2D::new(atlases))
+    }
+}
+
+/// Merge textures with identical images into the first texture with that image, keeping 
+/// the names of the duplicates as aliases. The remaining textures keep their relative order.
+fn merge_duplicates(images: Vec<PreparedImage>) -> Vec<PreparedImage> {
+    let mut merged: Vec<PreparedImage> = vec![];
+    let mut candidates: HashMap<u64, Vec<usize>> = HashMap::new();
+    for prepared in images.into_iter() {
+        let mut hasher = DefaultHasher::new();
+        prepared.image.width.hash(&mut hasher);
+        prepared.image.height.hash(&mut hasher);
+        prepared.image.data.hash(&mut hasher);
+        let same_hash = candidates.entry(hasher.finish()).or_default();
+        // Distinct images can share a hash, so compare the pixels before merging.
+        let duplicate = same_hash.iter().copied().find(|i| {
+            let original = &merged[*i];
+            original.source == prepared.source &&
+            original.image.width == prepared.image.width &&
+            original.image.height == prepared.image.height &&
+            original.image.data == prepared.image.data
+        });
+        match duplicate {
+            Some(i) => merged[i].aliases.push(prepared.name),
+            None => {
+                same_hash.push(merged.len());
+                merged.push(prepared);
+            }
+        }
+    }
+
+    merged
+}
@@ -0,0 +1,504 @@
+use crate::{
+    AlphaMode,
+    AtlasEntry,
+    BoundingBoxPixelCoords,
+    ErrorKind,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+    TextureAtlas2DResult,
+};
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+
+/// The versions of the Cocos2d property list format for sprite frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Cocos2dPlistFormat {
+    /// Format version 2, where each frame has a `frame`, an `offset`, a `rotated` flag,
+    /// a `sourceColorRect`, and a `sourceSize`. Aliases get written out as frames of
+    /// their own.
+    Version2,
+    /// Format version 3, where each frame has a `textureRect`, a `textureRotated` flag,
+    /// a `spriteOffset`, a `spriteSize`, a `spriteSourceSize`, and a list of `aliases`.
+    Version3,
+}
+
+/// A value in a property list file.
+#[derive(Clone, Debug, PartialEq)]
+enum PlistValue {
+    String(String),
+    Integer(i64),
+    Real(f64),
+    Boolean(bool),
+    Array(Vec<PlistValue>),
+    /// A dictionary, with its entries in the order of the property list file.
+    Dictionary(Vec<(String, PlistValue)>),
+}
+
+impl PlistValue {
+    /// Get the entry of a dictionary with the given key.
+    fn get(&self, key: &str) -> Option<&PlistValue> {
+        match self {
+            PlistValue::Dictionary(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// Parse an element of a property list file. Elements with types sprite frame files
+/// have no use for are read as strings.
+fn parse_value(node: roxmltree::Node) -> Result<PlistValue, String> {
+    let text = node.text().unwrap_or("").trim();
+    match node.tag_name().name() {
+        "dict" => {
+            let mut entries = vec![];
+            let mut key = None;
+            for child in node.children().filter(|child| child.is_element()) {
+                if child.has_tag_name("key") {
+                    key = Some(String::from(child.text().unwrap_or("")));
+                } else {
+                    let key = key.take().ok_or_else(|| String::from("a dictionary value has no key"))?;
+                    entries.push((key, parse_value(child)?));
+                }
+            }
+            Ok(PlistValue::Dictionary(entries))
+        }
+        "array" => {
+            let values = node.children()
+                .filter(|child| child.is_element())
+                .map(parse_value)
+                .collect::<Result<Vec<PlistValue>, String>>()?;
+            Ok(PlistValue::Array(values))
+        }
+        "integer" => text.parse().map(PlistValue::Integer).map_err(|_| format!("invalid integer {}", text)),
+        "real" => text.parse().map(PlistValue::Real).map_err(|_| format!("invalid real number {}", text)),
+        "true" => Ok(PlistValue::Boolean(true)),
+        "false" => Ok(PlistValue::Boolean(false)),
+        _ => Ok(PlistValue::String(String::from(node.text().unwrap_or("")))),
+    }
+}
+
+/// Write a value out to a property list file, indented by the given depth.
+fn write_value(text: &mut String, value: &PlistValue, depth: usize) {
+    let indent = "    ".repeat(depth);
+    // Writing to a string never fails.
+    match value {
+        PlistValue::String(string) => writeln!(text, "{}<string>{}</string>", indent, crate::export::escape_xml(string)).unwrap(),
+        PlistValue::Integer(integer) => writeln!(text, "{}<integer>{}</integer>", indent, integer).unwrap(),
+        PlistValue::Real(real) => writeln!(text, "{}<real>{}</real>", indent, real).unwrap(),
+        PlistValue::Boolean(boolean) => writeln!(text, "{}<{}/>", indent, boolean).unwrap(),
+        PlistValue::Array(values) if values.is_empty() => writeln!(text, "{}<array/>", indent).unwrap(),
+        PlistValue::Array(values) => {
+            writeln!(text, "{}<array>", indent).unwrap();
+            for value in values.iter() {
+                write_value(text, value, depth + 1);
+            }
+            writeln!(text, "{}</array>", indent).unwrap();
+        }
+        PlistValue::Dictionary(entries) => {
+            writeln!(text, "{}<dict>", indent).unwrap();
+            for (key, value) in entries.iter() {
+                writeln!(text, "{}    <key>{}</key>", indent, crate::export::escape_xml(key)).unwrap();
+                write_value(text, value, depth + 1);
+            }
+            writeln!(text, "{}</dict>", indent).unwrap();
+        }
+    }
+}
+
+/// Parse the numbers in a Cocos2d geometry string, such as `{x,y}` for a point or a
+/// size, or `{{x,y},{w,h}}` for a rectangle.
+fn parse_numbers(value: Option<&PlistValue>, key: &str, count: usize) -> Result<Vec<f64>, String> {
+    let string = match value {
+        Some(PlistValue::String(string)) => string,
+        _ => return Err(format!("missing string {}", key)),
+    };
+    let numbers = string
+        .split([',', '{', '}'])
+        .map(|number| number.trim())
+        .filter(|number| !number.is_empty())
+        .map(|number| number.parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| format!("invalid string {} for {}", string, key))?;
+    if numbers.len() != count {
+        return Err(format!("invalid string {} for {}", string, key));
+    }
+
+    Ok(numbers)
+}
+
+/// Parse a Cocos2d geometry string made of pixel counts.
+fn parse_pixels(value: Option<&PlistValue>, key: &str, count: usize) -> Result<Vec<usize>, String> {
+    parse_numbers(value, key, count)?.into_iter()
+        .map(|number| {
+            if number >= 0.0 && number.fract() == 0.0 {
+                Ok(number as usize)
+            } else {
+                Err(format!("invalid pixel count {} for {}", number, key))
+            }
+        })
+        .collect()
+}
+
+/// Get a flag of a property list dictionary, which is false if it is absent.
+fn boolean(dictionary: &PlistValue, key: &str) -> bool {
+    dictionary.get(key) == Some(&PlistValue::Boolean(true))
+}
+
+/// A sprite frame in a Cocos2d property list file, describing one texture.
+#[derive(Clone, Debug)]
+struct Cocos2dFrame {
+    /// The rectangle the texture covers in the atlas image, with the rows counted from
+    /// the top of the image. The dimensions are the dimensions of the texture before it
+    /// gets rotated.
+    rect: (usize, usize, usize, usize),
+    /// Whether the texture is stored rotated by 90 degrees clockwise.
+    rotated: bool,
+    /// Where the texture sits inside the original image.
+    source: SourceImagePixelCoords,
+    /// The other names of the texture.
+    aliases: Vec<String>,
+}
+
+/// Determine where a trimmed texture sits inside its original image from the offset of
+/// its center from the center of the original image. Cocos2d measures the offset with
+/// the y-axis pointing up.
+fn source_from_offset(offset: &[f64], width: usize, height: usize, source_size: &[usize]) -> Result<SourceImagePixelCoords, String> {
+    let x = (source_size[0] as f64 - width as f64) / 2.0 + offset[0];
+    let y = (source_size[1] as f64 - height as f64) / 2.0 - offset[1];
+    let (x, y) = (x.round(), y.round());
+    if x < 0.0 || y < 0.0 || x as usize + width > source_size[0] || y as usize + height > source_size[1] {
+        return Err(format!("the offset {{{},{}}} puts the texture outside of its original image", offset[0], offset[1]));
+    }
+
+    Ok(SourceImagePixelCoords {
+        offset: OffsetPixelCoords::new(x as usize, y as usize),
+        width: source_size[0],
+        height: source_size[1],
+    })
+}
+
+/// Parse a sprite frame of a property list file in format version 2.
+fn parse_frame_version2(frame: &PlistValue) -> Result<Cocos2dFrame, String> {
+    let rect = parse_pixels(frame.get("frame"), "frame", 4)?;
+    let source_size = parse_pixels(frame.get("sourceSize"), "sourceSize", 2)?;
+    let source = match frame.get("sourceColorRect") {
+        Some(_) => {
+            let source_rect = parse_pixels(frame.get("sourceColorRect"), "sourceColorRect", 4)?;
+            SourceImagePixelCoords {
+                offset: OffsetPixelCoords::new(source_rect[0], source_rect[1]),
+                width: source_size[0],
+                height: source_size[1],
+            }
+        }
+        None => {
+            let offset = parse_numbers(frame.get("offset"), "offset", 2)?;
+            source_from_offset(&offset, rect[2], rect[3], &source_size)?
+        }
+    };
+
+    Ok(Cocos2dFrame {
+        rect: (rect[0], rect[1], rect[2], rect[3]),
+        rotated: boolean(frame, "rotated"),
+        source: source,
+        aliases: vec![],
+    })
+}
+
+/// Parse a sprite frame of a property list file in format version 3.
+fn parse_frame_version3(frame: &PlistValue) -> Result<Cocos2dFrame, String> {
+    let rect = parse_pixels(frame.get("textureRect"), "textureRect", 4)?;
+    let source_size = parse_pixels(frame.get("spriteSourceSize"), "spriteSourceSize", 2)?;
+    let offset = parse_numbers(frame.get("spriteOffset"), "spriteOffset", 2)?;
+    let source = source_from_offset(&offset, rect[2], rect[3], &source_size)?;
+    let aliases = match frame.get("aliases") {
+        Some(PlistValue::Array(aliases)) => aliases.iter()
+            .map(|alias| match alias {
+                PlistValue::String(alias) => Ok(alias.clone()),
+                _ => Err(String::from("an alias is not a string")),
+            })
+            .collect::<Result<Vec<String>, String>>()?,
+        _ => vec![],
+    };
+
+    Ok(Cocos2dFrame {
+        rect: (rect[0], rect[1], rect[2], rect[3]),
+        rotated: boolean(frame, "textureRotated"),
+        source: source,
+        aliases: aliases,
+    })
+}
+
+/// Parse the contents of a Cocos2d property list file into its sprite frames, named and
+/// in the order of the file, and its metadata dictionary.
+fn parse_plist_file(plist_data: &[u8]) -> Result<(Vec<(String, Cocos2dFrame)>, PlistValue), String> {
+    let text = std::str::from_utf8(plist_data).map_err(|e| e.to_string())?;
+    // Property list files declare their document type.
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..roxmltree::ParsingOptions::default() };
+    let document = roxmltree::Document::parse_with_options(text, options).map_err(|e| e.to_string())?;
+    let root = document.root_element()
+        .children()
+        .find(|node| node.is_element())
+        .ok_or_else(|| String::from("the property list is empty"))?;
+    let root = parse_value(root)?;
+    let metadata = root.get("metadata").cloned().unwrap_or(PlistValue::Dictionary(vec![]));
+    let frames = match root.get("frames") {
+        Some(PlistValue::Dictionary(frames)) => frames,
+        _ => return Err(String::from("the property list has no frames")),
+    };
+
+    let format = match metadata.get("format") {
+        Some(PlistValue::Integer(format)) => *format,
+        // Without a format version, the keys of the frames tell the formats apart.
+        _ if frames.iter().any(|(_, frame)| frame.get("textureRect").is_some()) => 3,
+        _ => 2,
+    };
+    let mut parsed_frames = vec![];
+    for (name, frame) in frames.iter() {
+        let parsed_frame = match format {
+            2 => parse_frame_version2(frame),
+            3 => parse_frame_version3(frame),
+            _ => return Err(format!("unsupported format version {}", format)),
+        };
+        let parsed_frame = parsed_frame.map_err(|message| format!("frame {}: {}", name, message))?;
+        parsed_frames.push((name.clone(), parsed_frame));
+    }
+
+    Ok((parsed_frames, metadata))
+}
+
+/// Construct an error for a Cocos2d property list file.
+fn error(kind: ErrorKind, page_name: &str, source: Option<Box<dyn std::error::Error + Send + Sync>>) -> TextureAtlas2DError {
+    TextureAtlas2DError::new(kind, None, Some(String::from(page_name)), source)
+}
+
+/// Parse a Cocos2d property list file into its sprite frames and its metadata dictionary.
+fn parse_plist(plist_data: &[u8], page_name: &str) -> Result<(Vec<(String, Cocos2dFrame)>, PlistValue), TextureAtlas2DError> {
+    parse_plist_file(plist_data).map_err(|message| {
+        error(ErrorKind::CouldNotLoadCoordinateCharts, page_name, Some(message.into()))
+    })
+}
+
+/// Construct a page of a texture atlas from the parsed sprite frames and metadata of a
+/// Cocos2d property list file and the contents of its atlas image file.
+fn page_from_plist(
+    frames: Vec<(String, Cocos2dFrame)>,
+    metadata: PlistValue,
+    image_data: &[u8],
+    page_name: &str, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+
+    let tex_image = crate::decode_atlas_image(image_data, origin).map_err(|kind| {
+        error(kind, page_name, None)
+    })?;
+    let width = tex_image.width;
+    let height = tex_image.height;
+    if metadata.get("size").is_some() {
+        let size = parse_pixels(metadata.get("size"), "size", 2).map_err(|message| {
+            error(ErrorKind::CouldNotLoadCoordinateCharts, page_name, Some(message.into()))
+        })?;
+        if (size[0], size[1]) != (width, height) {
+            return Err(error(ErrorKind::InvalidImageData, page_name, None));
+        }
+    }
+
+    let mut names = HashSet::new();
+    let mut atlas_entries: Vec<(usize, AtlasEntry)> = vec![];
+    let mut positions: HashMap<_, usize> = HashMap::new();
+    for (name, frame) in frames.into_iter() {
+        for name in std::iter::once(&name).chain(frame.aliases.iter()) {
+            if !names.insert(name.clone()) {
+                return Err(error(ErrorKind::DuplicateTextureName, page_name, None));
+            }
+        }
+
+        let (x, y, upright_width, upright_height) = frame.rect;
+        let (stored_width, stored_height) = if frame.rotated {
+            (upright_height, upright_width)
+        } else {
+            (upright_width, upright_height)
+        };
+        if x + stored_width > width || y + stored_height > height {
+            return Err(error(ErrorKind::TextureDoesNotFitInAtlas, page_name, None));
+        }
+
+        let source = frame.source;
+        let key = (frame.rect, frame.rotated, source.offset.u, source.offset.v, source.width, source.height);
+        match positions.get(&key) {
+            Some(position) => {
+                let aliases = &mut atlas_entries[*position].1.aliases;
+                aliases.push(name);
+                aliases.extend(frame.aliases);
+            }
+            None => {
+                let bounding_box = BoundingBoxPixelCoords::from_image_rect(
+                    origin, height, x, y, stored_width, stored_height
+                );
+                let mut atlas_entry = AtlasEntry::new(name, bounding_box, source, width, height);
+                atlas_entry.rotated = frame.rotated;
+                atlas_entry.aliases = frame.aliases;
+                positions.insert(key, atlas_entries.len());
+                atlas_entries.push((atlas_entries.len(), atlas_entry));
+            }
+        }
+    }
+
+    let mut atlas = TextureAtlas2D::from_atlas_entries(
+        width, height, tex_image.color_type, origin, atlas_entries, String::from(page_name), tex_image.data
+    );
+    atlas.alpha_mode = if boolean(&metadata, "premultiplyAlpha") { AlphaMode::Premultiplied } else { AlphaMode::Straight };
+    atlas.color_space = crate::png_chunks::color_space(image_data).unwrap_or_default();
+
+    Ok(TextureAtlas2DResult {
+        atlas: atlas,
+        warnings: crate::dimension_warnings(width, height),
+    })
+}
+
+/// Load a page of a texture atlas from the contents of a Cocos2d sprite frame property
+/// list file in format version 2 or 3, and the contents of its atlas image file. The
+/// textures are indexed in the order the property list file lists them, and the pixel
+/// coordinates are converted from the top left corner of the atlas image to the given
+/// origin. The aliases of the frames become aliases of the textures, and so do frames
+/// with the same rectangle in the atlas image.
+pub fn load_cocos2d_plist_from_memory(
+    plist_data: &[u8],
+    image_data: &[u8],
+    page_name: &str, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+
+    let (frames, metadata) = parse_plist(plist_data, page_name)?;
+
+    page_from_plist(frames, metadata, image_data, page_name, origin)
+}
+
+/// Load a page of a texture atlas from a Cocos2d sprite frame property list file. The
+/// atlas image file is read from the `realTextureFileName`, or failing that the
+/// `textureFileName`, the metadata of the property list file gives relative to the
+/// property list file, and the page is named after the property list file. The page is
+/// loaded the same way `load_cocos2d_plist_from_memory` loads it.
+pub fn load_cocos2d_plist_file<P: AsRef<Path>>(path: P, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+    let path = path.as_ref();
+    let page_name = path.file_stem().map_or("", |s| s.to_str().unwrap_or(""));
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let plist_data = fs::read(path).map_err(|e| {
+        error(ErrorKind::CouldNotOpenTextureAtlas, page_name, Some(Box::new(e)))
+    })?;
+    let (frames, metadata) = parse_plist(&plist_data, page_name)?;
+    let image_file_name = match (metadata.get("realTextureFileName"), metadata.get("textureFileName")) {
+        (Some(PlistValue::String(file_name)), _) | (None, Some(PlistValue::String(file_name))) => file_name,
+        _ => return Err(error(ErrorKind::MissingImageBuffer, page_name, None)),
+    };
+    let image_data = fs::read(directory.join(image_file_name)).map_err(|e| {
+        error(ErrorKind::MissingImageBuffer, page_name, Some(Box::new(e)))
+    })?;
+
+    page_from_plist(frames, metadata, &image_data, page_name, origin)
+}
+
+/// Format a point or a size as a Cocos2d geometry string.
+fn format_pair<T: std::fmt::Display>(x: T, y: T) -> PlistValue {
+    PlistValue::String(format!("{{{},{}}}", x, y))
+}
+
+/// Format a rectangle as a Cocos2d geometry string.
+fn format_rect(x: usize, y: usize, width: usize, height: usize) -> PlistValue {
+    PlistValue::String(format!("{{{{{},{}}},{{{},{}}}}}", x, y, width, height))
+}
+
+/// Write a page of a texture atlas out as a Cocos2d sprite frame property list file in
+/// the given format version at the given path, with a PNG atlas image named after the
+/// property list file next to it. The pixel coordinates in the property list file are
+/// measured from the top left corner of the atlas image whatever the origin of the atlas
+/// is. The atlas image is written out as an RGBA image with 8 bits per channel, and the
+/// metadata records whether its alpha is premultiplied.
+pub fn export_cocos2d_plist<P: AsRef<Path>>(atlas: &TextureAtlas2D, path: P, format: Cocos2dPlistFormat) -> Result<(), TextureAtlas2DError> {
+    let path = path.as_ref();
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let image_file_name = format!("{}.png", path.file_stem().map_or("", |s| s.to_str().unwrap_or("")));
+
+    let mut frames = vec![];
+    let mut indices = atlas.indices();
+    indices.sort_unstable();
+    for index in indices {
+        let atlas_entry = &atlas.bounding_boxes[&index];
+        let (x, y, stored_width, stored_height) = atlas_entry.bounding_box_pix.to_image_rect(atlas.origin, atlas.height);
+        let (width, height) = if atlas_entry.rotated { (stored_height, stored_width) } else { (stored_width, stored_height) };
+        let source = atlas_entry.source;
+        // Cocos2d measures the offset of the center of the texture from the center of
+        // the original image, with the y-axis pointing up.
+        let offset_x = (2.0 * source.offset.u as f64 + width as f64 - source.width as f64) / 2.0;
+        let offset_y = (source.height as f64 - 2.0 * source.offset.v as f64 - height as f64) / 2.0;
+        match format {
+            Cocos2dPlistFormat::Version2 => {
+                let frame = PlistValue::Dictionary(vec![
+                    (String::from("frame"), format_rect(x, y, width, height)),
+                    (String::from("offset"), format_pair(offset_x, offset_y)),
+                    (String::from("rotated"), PlistValue::Boolean(atlas_entry.rotated)),
+                    (String::from("sourceColorRect"), format_rect(source.offset.u, source.offset.v, width, height)),
+                    (String::from("sourceSize"), format_pair(source.width, source.height)),
+                ]);
+                for name in std::iter::once(&atlas_entry.name).chain(atlas_entry.aliases.iter()) {
+                    frames.push((name.clone(), frame.clone()));
+                }
+            }
+            Cocos2dPlistFormat::Version3 => {
+                let aliases = atlas_entry.aliases.iter().map(|alias| PlistValue::String(alias.clone())).collect();
+                let frame = PlistValue::Dictionary(vec![
+                    (String::from("aliases"), PlistValue::Array(aliases)),
+                    (String::from("spriteOffset"), format_pair(offset_x, offset_y)),
+                    (String::from("spriteSize"), format_pair(width, height)),
+                    (String::from("spriteSourceSize"), format_pair(source.width, source.height)),
+                    (String::from("textureRect"), format_rect(x, y, width, height)),
+                    (String::from("textureRotated"), PlistValue::Boolean(atlas_entry.rotated)),
+                ]);
+                frames.push((atlas_entry.name.clone(), frame));
+            }
+        }
+    }
+
+    let format_version = match format {
+        Cocos2dPlistFormat::Version2 => 2,
+        Cocos2dPlistFormat::Version3 => 3,
+    };
+    let metadata = PlistValue::Dictionary(vec![
+        (String::from("format"), PlistValue::Integer(format_version)),
+        (String::from("pixelFormat"), PlistValue::String(String::from("RGBA8888"))),
+        (String::from("premultiplyAlpha"), PlistValue::Boolean(atlas.alpha_mode == AlphaMode::Premultiplied)),
+        (String::from("realTextureFileName"), PlistValue::String(image_file_name.clone())),
+        (String::from("size"), format_pair(atlas.width, atlas.height)),
+        (String::from("textureFileName"), PlistValue::String(image_file_name.clone())),
+    ]);
+    let root = PlistValue::Dictionary(vec![
+        (String::from("frames"), PlistValue::Dictionary(frames)),
+        (String::from("metadata"), metadata),
+    ]);
+
+    let mut text = String::new();
+    // Writing to a string never fails.
+    writeln!(text, "{}", r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(text, "{}", r#"<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">"#).unwrap();
+    writeln!(text, "{}", r#"<plist version="1.0">"#).unwrap();
+    write_value(&mut text, &root, 1);
+    writeln!(text, "{}", "</plist>").unwrap();
+
+    if !directory.as_os_str().is_empty() {
+        fs::create_dir_all(directory).map_err(|e| {
+            error(ErrorKind::IoError, &atlas.atlas_name, Some(Box::new(e)))
+        })?;
+    }
+    let image_data = crate::export::encode_sheet_image(atlas, atlas.alpha_mode)?;
+    fs::write(directory.join(&image_file_name), image_data).map_err(|e| {
+        error(ErrorKind::IoError, &atlas.atlas_name, Some(Box::new(e)))
+    })?;
+    fs::write(path, text).map_err(|e| {
+        error(ErrorKind::IoError, &atlas.atlas_name, Some(Box::new(e)))
+    })
+}
@@ -0,0 +1,202 @@
+use crate::{
+    AlphaMode,
+    BoundingBoxPixelCoords,
+    BoundingBoxTexCoords,
+    ColorSpace,
+    ColorType,
+    MultiTextureAtlas2D,
+    Origin,
+    TextureAtlas2D,
+};
+use serde_derive::Serialize;
+
+use std::fmt;
+
+
+/// A description of a texture in a page of a multi texture atlas.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TextureDescription {
+    /// The index of the texture in its page.
+    pub index: usize,
+    /// The name of the texture.
+    pub name: String,
+    /// The other names of the texture.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Whether the texture is stored rotated by 90 degrees clockwise.
+    #[serde(skip_serializing_if = "crate::is_false")]
+    pub rotated: bool,
+    /// The bounding box of the texture in units of pixels.
+    pub bounding_box: BoundingBoxPixelCoords,
+    /// The bounding box of the texture in units of the unit square.
+    pub bounding_box_uv: BoundingBoxTexCoords,
+}
+
+/// A description of a page of a multi texture atlas.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PageDescription {
+    /// The name of the page.
+    pub name: String,
+    /// The width of the page in pixels.
+    pub width: usize,
+    /// The height of the page in pixels.
+    pub height: usize,
+    /// The color type of the page.
+    pub color_type: ColorType,
+    /// The number of channels per pixel.
+    pub channel_count: usize,
+    /// The number of bytes per pixel.
+    pub bytes_per_pixel: usize,
+    /// The origin of the page.
+    pub origin: Origin,
+    /// Whether the color channels of the page are premultiplied by alpha.
+    pub alpha_mode: AlphaMode,
+    /// The transfer function of the color channels of the page.
+    pub color_space: ColorSpace,
+    /// The number of mip levels of the page, counting the page image itself.
+    pub mip_level_count: usize,
+    /// The textures in the page, ordered by index.
+    pub textures: Vec<TextureDescription>,
+}
+
+/// A report describing every page and every texture of a multi texture atlas. The
+/// report lists the pages in order and the textures of each page by index, so that
+/// describing the same atlas twice gives the same report. The `Display` implementation
+/// formats the report as a text table.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AtlasDescription {
+    /// The pages of the multi texture atlas.
+    pub pages: Vec<PageDescription>,
+}
+
+impl AtlasDescription {
+    /// Format the report as pretty printed JSON.
+    pub fn to_json(&self) -> String {
+        // The report contains only strings, numbers, and unit variants, all of which
+        // serialize without error.
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Format the report as a text table, with a header line for each page followed
+    /// by a row for each texture.
+    pub fn to_table(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Describe a page of a multi texture atlas.
+fn describe_page(atlas: &TextureAtlas2D) -> PageDescription {
+    let mut indices = atlas.indices();
+    indices.sort_unstable();
+    let textures = indices.into_iter().map(|index| {
+        let atlas_entry = &atlas.bounding_boxes[&index];
+        TextureDescription {
+            index: index,
+            name: atlas_entry.name.clone(),
+            aliases: atlas_entry.aliases.clone(),
+            rotated: atlas_entry.rotated,
+            bounding_box: atlas_entry.bounding_box_pix,
+            bounding_box_uv: atlas_entry.bounding_box_tex,
+        }
+    }).collect();
+
+    PageDescription {
+        name: atlas.atlas_name.clone(),
+        width: atlas.width,
+        height: atlas.height,
+        color_type: atlas.color_type,
+        channel_count: atlas.channel_count,
+        bytes_per_pixel: atlas.bytes_per_pixel,
+        origin: atlas.origin,
+        alpha_mode: atlas.alpha_mode,
+        color_space: atlas.color_space,
+        mip_level_count: atlas.mip_level_count(),
+        textures: textures,
+    }
+}
+
+/// Describe every page and every texture of a multi texture atlas.
+pub fn describe(multi_atlas: &MultiTextureAtlas2D) -> AtlasDescription {
+    AtlasDescription {
+        pages: multi_atlas.pages().iter().map(describe_page).collect(),
+    }
+}
+
+/// The columns of the texture table, and whether each column is aligned to the left.
+const COLUMNS: [(&str, bool); 11] = [
+    ("index", false),
+    ("name", true),
+    ("u", false),
+    ("v", false),
+    ("width", false),
+    ("height", false),
+    ("uv u", false),
+    ("uv v", false),
+    ("uv width", false),
+    ("uv height", false),
+    ("flags", true),
+];
+
+impl fmt::Display for AtlasDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, page) in self.pages.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(
+                f, "page {}: {} x {}, {:?}, {} channels, {} bytes per pixel, {:?}, {:?} alpha, {:?}, {} mip levels",
+                page.name, page.width, page.height, page.color_type, page.channel_count,
+                page.bytes_per_pixel, page.origin, page.alpha_mode, page.color_space, page.mip_level_count
+            )?;
+
+            let mut rows = vec![COLUMNS.iter().map(|(title, _)| String::from(*title)).collect::<Vec<String>>()];
+            for texture in page.textures.iter() {
+                let bounding_box = texture.bounding_box;
+                let bounding_box_uv = texture.bounding_box_uv;
+                let mut flags = vec![];
+                if texture.rotated {
+                    flags.push(String::from("rotated"));
+                }
+                if !texture.aliases.is_empty() {
+                    flags.push(format!("aliases: {}", texture.aliases.join(", ")));
+                }
+                rows.push(vec![
+                    texture.index.to_string(),
+                    texture.name.clone(),
+                    bounding_box.top_left.u.to_string(),
+                    bounding_box.top_left.v.to_string(),
+                    bounding_box.width.to_string(),
+                    bounding_box.height.to_string(),
+                    format!("{:.6}", bounding_box_uv.top_left.u),
+                    format!("{:.6}", bounding_box_uv.top_left.v),
+                    format!("{:.6}", bounding_box_uv.width),
+                    format!("{:.6}", bounding_box_uv.height),
+                    flags.join("; "),
+                ]);
+            }
+
+            let mut widths = [0; 11];
+            for row in rows.iter() {
+                for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                    *width = usize::max(*width, cell.chars().count());
+                }
+            }
+            for row in rows.iter() {
+                let mut line = String::new();
+                for (j, cell) in row.iter().enumerate() {
+                    if j > 0 {
+                        line.push_str("  ");
+                    }
+                    if COLUMNS[j].1 {
+                        line.push_str(&format!("{:<width$}", cell, width = widths[j]));
+                    } else {
+                        line.push_str(&format!("{:>width$}", cell, width = widths[j]));
+                    }
+                }
+                writeln!(f, "{}", line.trim_end())?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use crate::packer::{
+    MaxRectsHeuristic,
+    MaxRectsPacker,
+    PackedRect,
+    Packer,
+};
+use crate::{
+    AlphaMode,
+    AtlasEntry,
+    BoundingBoxPixelCoords,
+    ColorType,
+    ErrorKind,
+    SourceImagePixelCoords,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+    TextureImage2D,
+};
+
+use std::collections::hash_map::{
+    HashMap
+};
+
+
+/// A texture atlas that supports inserting and removing textures at runtime, for
+/// dynamic content such as text glyphs or user avatars. The atlas keeps the state of its
+/// rectangle packer between insertions, so the space freed by removing a texture gets
+/// reused by later insertions. It also records the regions of the atlas image that
+/// changed since the last upload, so that only those regions need uploading to the GPU.
+#[derive(Clone, Debug)]
+pub struct DynamicTextureAtlas2D {
+    /// The underlying texture atlas.
+    atlas: TextureAtlas2D,
+    /// The number of pixels of empty space between neighboring textures.
+    padding: usize,
+    /// The packer tracking the free space in the atlas image.
+    packer: MaxRectsPacker,
+    /// The rectangle occupied by each texture in the packer, including padding.
+    packed_rects: HashMap<usize, PackedRect>,
+    /// The regions of the atlas image that changed since the last time they were taken.
+    dirty_rects: Vec<BoundingBoxPixelCoords>,
+}
+
+impl DynamicTextureAtlas2D {
+    /// Construct a new dynamic texture atlas from an existing texture atlas. The textures
+    /// already in the atlas keep their places.
+    pub fn new(atlas: TextureAtlas2D) -> DynamicTextureAtlas2D {
+        DynamicTextureAtlas2D::with_padding(atlas, 0)
+    }
+
+    /// Construct a new dynamic texture atlas from an existing texture atlas, keeping the
+    /// given number of pixels of empty space between each inserted texture and its
+    /// neighbors. The textures already in the atlas keep their places.
+    pub fn with_padding(atlas: TextureAtlas2D, padding: usize) -> DynamicTextureAtlas2D {
+        // Enlarging the packing region by the padding lets textures reach the right and
+        // bottom edges of the atlas image without the trailing padding.
+        let mut packer = MaxRectsPacker::new(
+            atlas.width + padding, atlas.height + padding, MaxRectsHeuristic::BestShortSideFit
+        );
+        let mut packed_rects = HashMap::new();
+        for (index, atlas_entry) in atlas.bounding_boxes.iter() {
+            let (x, y, width, height) = atlas_entry.bounding_box_pix.to_image_rect(atlas.origin, atlas.height);
+            let rect = PackedRect::new(x, y, width + padding, height + padding);
+            packer.occupy(rect);
+            packed_rects.insert(*index, rect);
+        }
+
+        DynamicTextureAtlas2D {
+            atlas: atlas,
+            padding: padding,
+            packer: packer,
+            packed_rects: packed_rects,
+            dirty_rects: vec![],
+        }
+    }
+
+    /// Get the underlying texture atlas.
+    #[inline]
+    pub fn atlas(&self) -> &TextureAtlas2D {
+        &self.atlas
+    }
+
+    /// Consume the dynamic texture atlas, returning the underlying texture atlas.
+    #[inline]
+    pub fn into_atlas(self) -> TextureAtlas2D {
+        self.atlas
+    }
+
+    /// Construct an error for the atlas.
+    fn error(&self, kind: ErrorKind) -> TextureAtlas2DError {
+        TextureAtlas2DError::new(kind, None, Some(self.atlas.atlas_name.clone()), None)
+    }
+
+    /// Insert a texture into the atlas from a raw image buffer, and get its bounding box.
+    /// The rows of the image buffer are ordered from the top of the image to the bottom
+    /// of the image. The texture has straight alpha, and gets converted to the color type
+    /// and the alpha mode of the atlas image.
+    /// The texture takes the smallest unused index in the atlas, and the mip levels of 
+    /// the atlas image get removed, since they no longer match it. This fails if the atlas
+    /// already contains a texture with the same name, or if there is no free space
+    /// large enough for the texture.
+    pub fn insert(
+        &mut self,
+        name: &str,
+        width: usize, height: usize, color_type: ColorType, data: &[u8]) -> Result<BoundingBoxPixelCoords, TextureAtlas2DError> {
+
+        if self.atlas.texture_names.contains_key(name) {
+            return Err(self.error(ErrorKind::DuplicateTextureName));
+        }
+        let image = TextureImage2D::new(width, height, color_type, data.to_vec());
+        if !image.is_valid() {
+            return Err(self.error(ErrorKind::InvalidImageData));
+        }
+        let image = image
+            .convert(self.atlas.color_type)
+            .ok_or_else(|| self.error(ErrorKind::InvalidImageData))?
+            .convert_alpha_mode(AlphaMode::Straight, self.atlas.alpha_mode);
+        let rect = self.packer.insert(width + self.padding, height + self.padding).ok_or_else(|| {
+            self.error(ErrorKind::TextureDoesNotFitInAtlas)
+        })?;
+
+        self.atlas.write_image(&image, rect.x, rect.y);
+        self.atlas.clear_mipmaps();
+        let bounding_box = BoundingBoxPixelCoords::from_image_rect(
+            self.atlas.origin, self.atlas.height, rect.x, rect.y, width, height
+        );
+        let source = SourceImagePixelCoords::untrimmed(width, height);
+        let atlas_entry = AtlasEntry::new(
+            String::from(name), bounding_box, source, self.atlas.width, self.atlas.height
+        );
+        let index = self.atlas.insert_entry(atlas_entry);
+        self.packed_rects.insert(index, rect);
+        self.dirty_rects.push(bounding_box);
+
+        Ok(bounding_box)
+    }
+
+    /// Remove a texture from the atlas by name, and get the bounding box it occupied. This
+    /// removes every alias of the texture along with it, clears its pixels in the atlas
+    /// image, and removes the mip levels of the atlas image. The indices of the other 
+    /// textures do not change.
+    pub fn remove(&mut self, name: &str) -> Option<BoundingBoxPixelCoords> {
+        let index = *self.atlas.texture_names.get(name)?;
+        let atlas_entry = self.atlas.remove_entry(index)?;
+        let bounding_box = atlas_entry.bounding_box_pix;
+        if let Some(rect) = self.packed_rects.remove(&index) {
+            self.packer.remove(&rect);
+        }
+
+        let bytes_per_pixel = self.atlas.bytes_per_pixel;
+        let blank = TextureImage2D::new(
+            bounding_box.width,
+            bounding_box.height,
+            self.atlas.color_type,
+            vec![0; bounding_box.width * bounding_box.height * bytes_per_pixel]
+        );
+        let (x, y, _, _) = bounding_box.to_image_rect(self.atlas.origin, self.atlas.height);
+        self.atlas.write_image(&blank, x, y);
+        self.atlas.clear_mipmaps();
+        self.dirty_rects.push(bounding_box);
+
+        Some(bounding_box)
+    }
+
+    /// Get the regions of the atlas image that changed since the dirty rectangles were
+    /// last taken.
+    #[inline]
+    pub fn dirty_rects(&self) -> &[BoundingBoxPixelCoords] {
+        &self.dirty_rects
+    }
+
+    /// Take the regions of the atlas image that changed since the dirty rectangles were
+    /// last taken, e.g. after uploading them to the GPU.
+    pub fn take_dirty_rects(&mut self) -> Vec<BoundingBoxPixelCoords> {
+        std::mem::take(&mut self.dirty_rects)
+    }
+
+    /// Get the fraction of the atlas image covered by textures, including padding.
+    #[inline]
+    pub fn occupancy(&self) -> f32 {
+        self.packer.occupancy()
+    }
+}
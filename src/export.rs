@@ -0,0 +1,40 @@
+use crate::{
+    encode_png_image,
+    orient_image,
+    AlphaMode,
+    ColorType,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+};
+
+
+/// Encode the atlas image of a page into a PNG image file for tools that read sprite 
+/// sheets in other formats. Those tools expect RGBA images with 8 bits per channel, with
+/// the rows ordered from the top of the image to the bottom of the image, so the atlas 
+/// image gets converted to that, with the given alpha mode, before it is encoded.
+pub(crate) fn encode_sheet_image(atlas: &TextureAtlas2D, alpha_mode: AlphaMode) -> Result<Vec<u8>, TextureAtlas2DError> {
+    let atlas = atlas.convert_to(ColorType::Rgba8).convert_alpha_mode(alpha_mode);
+    let mut image = atlas.data;
+    let width_in_bytes = image.bytes_per_pixel * image.width;
+    orient_image(&mut image.data, atlas.origin, image.height, width_in_bytes);
+
+    encode_png_image(&image, atlas.color_space, &atlas.atlas_name)
+}
+
+/// Escape the characters that cannot appear in the text or the attribute values of an 
+/// XML file.
+pub(crate) fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
@@ -1,4 +1,6 @@
-use image::png;
+#![allow(clippy::redundant_field_names, clippy::write_literal)]
+
+use image::codecs::png;
 use image::{
     ImageDecoder
 };
@@ -24,9 +26,56 @@ use std::collections::hash_map::{
     HashMap
 };
 
+mod builder;
+mod cocos2d;
+mod describe;
+mod dynamic;
+mod export;
+mod libgdx;
+mod mipmap;
+mod png_chunks;
+mod sparrow;
+mod texturepacker;
+mod unpack;
+pub mod packer;
+
+pub use builder::{
+    DirectoryOptions,
+    SizingPolicy,
+    TextureAtlas2DBuilder,
+};
+pub use cocos2d::{
+    export_cocos2d_plist,
+    load_cocos2d_plist_file,
+    load_cocos2d_plist_from_memory,
+    Cocos2dPlistFormat,
+};
+pub use describe::{
+    describe,
+    AtlasDescription,
+    PageDescription,
+    TextureDescription,
+};
+pub use dynamic::DynamicTextureAtlas2D;
+pub use libgdx::{
+    export_libgdx_atlas,
+    load_libgdx_atlas_file,
+};
+pub use sparrow::{
+    export_sparrow_xml,
+    load_sparrow_xml_file,
+    load_sparrow_xml_from_memory,
+};
+pub use texturepacker::{
+    export_texturepacker_json,
+    load_texturepacker_file,
+    load_texturepacker_from_memory,
+};
+pub use unpack::unpack_to_directory;
+
 
 /// The color space represented by the underlying image data.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ColorType {
     /// Pixel is 8-bit luminance.
     L8,
@@ -104,6 +153,51 @@ impl ColorType {
             ColorType::Rgba16 => true,
         }
     }
+
+    /// Convert a color type from the underlying image library into a color type for a 
+    /// texture atlas, if the texture atlas supports it.
+    fn from_image_color_type(color_type: image::ColorType) -> Option<ColorType> {
+        match color_type {
+            image::ColorType::L8 => Some(ColorType::L8),
+            image::ColorType::La8 => Some(ColorType::La8),
+            image::ColorType::Rgb8 => Some(ColorType::Rgb8),
+            image::ColorType::Rgba8 => Some(ColorType::Rgba8),
+            image::ColorType::L16 => Some(ColorType::L16),
+            image::ColorType::La16 => Some(ColorType::La16),
+            image::ColorType::Rgb16 => Some(ColorType::Rgb16),
+            image::ColorType::Rgba16 => Some(ColorType::Rgba16),
+            image::ColorType::Bgr8 => Some(ColorType::Bgr8),
+            image::ColorType::Bgra8 => Some(ColorType::Bgra8),
+            _ => None,
+        }
+    }
+
+    /// Convert a color type for a texture atlas into a color type from the underlying 
+    /// image library.
+    fn to_image_color_type(self) -> image::ColorType {
+        match self {
+            ColorType::L8 => image::ColorType::L8,
+            ColorType::La8 => image::ColorType::La8,
+            ColorType::Rgb8 => image::ColorType::Rgb8,
+            ColorType::Rgba8 => image::ColorType::Rgba8,
+            ColorType::L16 => image::ColorType::L16,
+            ColorType::La16 => image::ColorType::La16,
+            ColorType::Rgb16 => image::ColorType::Rgb16,
+            ColorType::Rgba16 => image::ColorType::Rgba16,
+            ColorType::Bgr8 => image::ColorType::Bgr8,
+            ColorType::Bgra8 => image::ColorType::Bgra8,
+        }
+    }
+
+    /// Get the color type a PNG image stores pixels of this color type in. PNG images 
+    /// have no blue-green-red channel orderings, so these get stored as red-green-blue.
+    fn png_color_type(self) -> ColorType {
+        match self {
+            ColorType::Bgr8 => ColorType::Rgb8,
+            ColorType::Bgra8 => ColorType::Rgba8,
+            other => other,
+        }
+    }
 }
 
 /// The kind of error generated by the encoding or decoding process for texture atlases.
@@ -125,6 +219,20 @@ pub enum ErrorKind {
     MissingCoordinateCharts,
     /// An error occurred in writing out the texture atlas.
     IoError,
+    /// The length of an image buffer does not match its dimensions and color type.
+    InvalidImageData,
+    /// Two textures in the same texture atlas have the same name.
+    DuplicateTextureName,
+    /// A texture does not fit inside the texture atlas.
+    TextureDoesNotFitInAtlas,
+    /// A directory of texture images could not be read.
+    CouldNotReadDirectory,
+    /// A texture image could not be opened or decoded.
+    CouldNotLoadTextureImage,
+    /// A texture name cannot be turned into a file name.
+    InvalidTextureName,
+    /// No page size satisfying the sizing policy fits inside the dimensions of the atlas.
+    InvalidSizingPolicy,
 }
 
 impl fmt::Display for ErrorKind {
@@ -154,6 +262,27 @@ impl fmt::Display for ErrorKind {
             ErrorKind::IoError => {
                 write!(f, "{}", "There was an error in writing out the texture atlas.")
             }
+            ErrorKind::InvalidImageData => {
+                write!(f, "{}", "The image buffer length does not match the image dimensions and color format.")
+            }
+            ErrorKind::DuplicateTextureName => {
+                write!(f, "{}", "The texture atlas contains more than one texture with the same name.")
+            }
+            ErrorKind::TextureDoesNotFitInAtlas => {
+                write!(f, "{}", "A texture is too large to fit inside the texture atlas.")
+            }
+            ErrorKind::CouldNotReadDirectory => {
+                write!(f, "{}", "Could not read the directory of texture images.")
+            }
+            ErrorKind::CouldNotLoadTextureImage => {
+                write!(f, "{}", "Could not load a texture image.")
+            }
+            ErrorKind::InvalidTextureName => {
+                write!(f, "{}", "A texture name cannot be used as a file name.")
+            }
+            ErrorKind::InvalidSizingPolicy => {
+                write!(f, "{}", "No page size satisfying the sizing policy fits inside the atlas dimensions.")
+            }
         }
     }
 }
@@ -169,20 +298,25 @@ struct Repr {
 
 impl fmt::Display for Repr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.multi_atlas_name.is_some() && self.atlas_name.is_some() {
-            write!(f, "Texture atlas `{}` from multi texture atlas `{}` generated error `{:?}`", 
-                self.atlas_name.as_ref().unwrap(), self.multi_atlas_name.as_ref().unwrap(), self.kind
-            )
-        } else if self.multi_atlas_name.is_some() && self.atlas_name.is_none() {
-            write!(f, "Multi atlas `{}` generated error `{:?}`", 
-                self.multi_atlas_name.as_ref().unwrap(), self.kind
-            )
-        } else if self.multi_atlas_name.is_none() && self.atlas_name.is_some() {
-            write!(f, "Texture atlas `{}` from multi texture atlas `unnamed` generated error `{:?}`", 
-                self.atlas_name.as_ref().unwrap(), self.kind
-            )
-        } else {
-            write!(f, "Texture atlas `unnamed` from multi texture atlas `unnamed` generated error {:?}", self.kind)
+        match (&self.multi_atlas_name, &self.atlas_name) {
+            (Some(multi_atlas_name), Some(atlas_name)) => {
+                write!(f, "Texture atlas `{}` from multi texture atlas `{}` generated error `{:?}`", 
+                    atlas_name, multi_atlas_name, self.kind
+                )
+            }
+            (Some(multi_atlas_name), None) => {
+                write!(f, "Multi atlas `{}` generated error `{:?}`", 
+                    multi_atlas_name, self.kind
+                )
+            }
+            (None, Some(atlas_name)) => {
+                write!(f, "Texture atlas `{}` from multi texture atlas `unnamed` generated error `{:?}`", 
+                    atlas_name, self.kind
+                )
+            }
+            (None, None) => {
+                write!(f, "Texture atlas `unnamed` from multi texture atlas `unnamed` generated error {:?}", self.kind)
+            }
         }
     }
 }
@@ -243,7 +377,10 @@ impl fmt::Display for TextureAtlas2DError {
 
 impl error::Error for TextureAtlas2DError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        None
+        match &self.repr.error {
+            Some(error) => Some(error.as_ref()),
+            None => None,
+        }
     }
 }
 
@@ -275,6 +412,29 @@ pub enum TextureAtlas2DWarning {
     TextureDimensionsAreNotAPowerOfTwo,
 }
 
+/// The way the color channels of the atlas image relate to its alpha channel.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum AlphaMode {
+    /// The color channels are independent of the alpha channel.
+    #[default]
+    Straight,
+    /// The color channels are already multiplied by the alpha channel, as blending 
+    /// with premultiplied alpha expects.
+    Premultiplied,
+}
+
+/// The transfer function relating the color channels of the atlas image to light 
+/// intensity. Color textures are usually sRGB encoded, while data textures such as normal 
+/// maps and masks are linear, and must not be decoded from sRGB when they are sampled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum ColorSpace {
+    /// The color channels are encoded with the sRGB transfer function.
+    #[default]
+    Srgb,
+    /// The color channels are proportional to light intensity.
+    Linear,
+}
+
 /// The position of the top left corner of the bounding box in texture coordinates
 /// of the unit square [0,1] x [0,1].
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -365,6 +525,40 @@ pub struct BoundingBoxPixelCoords {
     pub height: usize,
 }
 
+impl BoundingBoxPixelCoords {
+    /// Construct the bounding box of a rectangle in an atlas image with the given origin. 
+    /// The rectangle is given by the position of its top left corner, with the rows 
+    /// of the image counted from the top of the image.
+    fn from_image_rect(
+        origin: Origin, atlas_height: usize, 
+        x: usize, y: usize, width: usize, height: usize) -> BoundingBoxPixelCoords {
+        
+        let v = match origin {
+            Origin::TopLeft => y,
+            Origin::BottomLeft => atlas_height - y - 1,
+        };
+
+        BoundingBoxPixelCoords {
+            top_left: OffsetPixelCoords::new(x, v),
+            width: width,
+            height: height,
+        }
+    }
+
+    /// Get the rectangle covered by the bounding box in an atlas image with the given
+    /// origin, given by the position of its top left corner with the rows of the image 
+    /// counted from the top of the image. This is the inverse of `from_image_rect`. The 
+    /// top row of the bounding box must lie inside the atlas image.
+    pub fn to_image_rect(self, origin: Origin, atlas_height: usize) -> (usize, usize, usize, usize) {
+        let y = match origin {
+            Origin::TopLeft => self.top_left.v,
+            Origin::BottomLeft => atlas_height - self.top_left.v - 1,
+        };
+
+        (self.top_left.u, y, self.width, self.height)
+    }
+}
+
 /// The corners of a bounding box in the texture atlas. This is an equivalent 
 /// representation to the usual representation in terms of the top left 
 /// corner, the width, and the height of the bounding box.
@@ -380,6 +574,40 @@ pub struct BoundingBoxCornersPixelCoords {
     pub bottom_right: OffsetPixelCoords,
 }
 
+/// The dimensions of the original image a texture was packed from, and the position of 
+/// the texture inside the original image. When the builder trims the fully transparent 
+/// borders off of an image before packing it, the texture in the atlas covers only part 
+/// of the original image. Otherwise the texture covers the whole original image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceImagePixelCoords {
+    /// The position of the top left corner of the texture inside the original image,
+    /// measured in pixels from the top left corner of the original image.
+    pub offset: OffsetPixelCoords,
+    /// The width in pixels of the original image.
+    pub width: usize,
+    /// The height in pixels of the original image.
+    pub height: usize,
+}
+
+impl SourceImagePixelCoords {
+    /// Construct the source image coordinates for a texture that covers the whole original image.
+    #[inline]
+    fn untrimmed(width: usize, height: usize) -> SourceImagePixelCoords {
+        SourceImagePixelCoords {
+            offset: OffsetPixelCoords::new(0, 0),
+            width: width,
+            height: height,
+        }
+    }
+
+    /// Determine whether the texture covers only part of the original image.
+    #[inline]
+    fn is_trimmed(&self, bounding_box: &BoundingBoxPixelCoords) -> bool {
+        self.offset.u != 0 || self.offset.v != 0 || 
+        self.width != bounding_box.width || self.height != bounding_box.height
+    }
+}
+
 #[derive(Clone, Debug)]
 struct TextureImage2D {
     width: usize,
@@ -429,6 +657,484 @@ impl TextureImage2D {
     fn as_bytes(&self) -> &[u8] {
         self.as_ref()
     }
+
+    /// Determine whether the length of the image buffer agrees with the dimensions
+    /// and the color type of the image.
+    #[inline]
+    fn is_valid(&self) -> bool {
+        self.data.len() == self.width * self.height * self.bytes_per_pixel
+    }
+
+    /// Construct a texture image from an image from the underlying image library.
+    fn from_dynamic_image(image: &image::DynamicImage) -> Option<TextureImage2D> {
+        use image::GenericImageView;
+
+        let color_type = ColorType::from_image_color_type(image.color())?;
+        let (width, height) = image.dimensions();
+
+        Some(TextureImage2D::new(width as usize, height as usize, color_type, image.to_bytes()))
+    }
+
+    /// Copy the pixels of another texture image with the same color type into this one, 
+    /// placing the top left corner of the source image at the given position.
+    fn copy_from(&mut self, source: &TextureImage2D, x: usize, y: usize) {
+        debug_assert_eq!(self.color_type, source.color_type);
+        let bytes_per_pixel = self.bytes_per_pixel;
+        let source_width_in_bytes = source.width * bytes_per_pixel;
+        let width_in_bytes = self.width * bytes_per_pixel;
+        for row in 0..source.height {
+            let source_start = row * source_width_in_bytes;
+            let target_start = (y + row) * width_in_bytes + x * bytes_per_pixel;
+            self.data[target_start..(target_start + source_width_in_bytes)]
+                .copy_from_slice(&source.data[source_start..(source_start + source_width_in_bytes)]);
+        }
+    }
+
+    /// Determine whether the pixel at the given position is fully transparent. Pixels 
+    /// in images without an alpha channel are always opaque.
+    fn is_transparent(&self, x: usize, y: usize) -> bool {
+        let offset = (y * self.width + x) * self.bytes_per_pixel;
+        let alpha = match self.color_type {
+            ColorType::La8 => &self.data[(offset + 1)..(offset + 2)],
+            ColorType::Rgba8 | ColorType::Bgra8 => &self.data[(offset + 3)..(offset + 4)],
+            ColorType::La16 => &self.data[(offset + 2)..(offset + 4)],
+            ColorType::Rgba16 => &self.data[(offset + 6)..(offset + 8)],
+            _ => return false,
+        };
+
+        alpha.iter().all(|byte| *byte == 0)
+    }
+
+    /// Find the smallest rectangle containing every pixel in the texture image that is not
+    /// fully transparent. The rectangle is given by the position of its top left corner, 
+    /// followed by its width and height. This returns `None` if every pixel is transparent.
+    fn opaque_bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        if !self.color_type.has_alpha_channel() {
+            return Some((0, 0, self.width, self.height));
+        }
+
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_transparent(x, y) {
+                    continue;
+                }
+                bounds = match bounds {
+                    Some((min_x, min_y, max_x, max_y)) => {
+                        Some((usize::min(min_x, x), usize::min(min_y, y), usize::max(max_x, x), usize::max(max_y, y)))
+                    }
+                    None => Some((x, y, x, y)),
+                };
+            }
+        }
+
+        bounds.map(|(min_x, min_y, max_x, max_y)| (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1))
+    }
+
+    /// Copy a rectangle of pixels out of the texture image into a new texture image. The 
+    /// rectangle is given by the position of its top left corner.
+    fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> TextureImage2D {
+        let bytes_per_pixel = self.bytes_per_pixel;
+        let width_in_bytes = self.width * bytes_per_pixel;
+        let mut data = Vec::with_capacity(width * height * bytes_per_pixel);
+        for row in y..(y + height) {
+            let start = row * width_in_bytes + x * bytes_per_pixel;
+            data.extend_from_slice(&self.data[start..(start + width * bytes_per_pixel)]);
+        }
+
+        TextureImage2D::new(width, height, self.color_type, data)
+    }
+
+    /// Rotate the texture image by 90 degrees clockwise.
+    fn rotate_clockwise(&self) -> TextureImage2D {
+        let bytes_per_pixel = self.bytes_per_pixel;
+        let rotated_width = self.height;
+        let rotated_height = self.width;
+        let mut data = vec![0; self.data.len()];
+        for y in 0..rotated_height {
+            for x in 0..rotated_width {
+                let source = ((self.height - 1 - x) * self.width + y) * bytes_per_pixel;
+                let target = (y * rotated_width + x) * bytes_per_pixel;
+                data[target..(target + bytes_per_pixel)].copy_from_slice(&self.data[source..(source + bytes_per_pixel)]);
+            }
+        }
+
+        TextureImage2D::new(rotated_width, rotated_height, self.color_type, data)
+    }
+
+    /// Rotate the texture image by 90 degrees counterclockwise, undoing `rotate_clockwise`.
+    fn rotate_counterclockwise(&self) -> TextureImage2D {
+        let bytes_per_pixel = self.bytes_per_pixel;
+        let rotated_width = self.height;
+        let rotated_height = self.width;
+        let mut data = vec![0; self.data.len()];
+        for y in 0..rotated_height {
+            for x in 0..rotated_width {
+                let source = (x * self.width + (self.width - 1 - y)) * bytes_per_pixel;
+                let target = (y * rotated_width + x) * bytes_per_pixel;
+                data[target..(target + bytes_per_pixel)].copy_from_slice(&self.data[source..(source + bytes_per_pixel)]);
+            }
+        }
+
+        TextureImage2D::new(rotated_width, rotated_height, self.color_type, data)
+    }
+
+    /// Rotate the texture image by 180 degrees.
+    fn rotate_half_turn(&self) -> TextureImage2D {
+        let mut data = Vec::with_capacity(self.data.len());
+        for pixel in self.data.chunks_exact(self.bytes_per_pixel).rev() {
+            data.extend_from_slice(pixel);
+        }
+
+        TextureImage2D::new(self.width, self.height, self.color_type, data)
+    }
+
+    /// Extend the edges of a rectangle in the texture image outward by duplicating the
+    /// outermost ring of pixels of the rectangle the given number of times. The rectangle
+    /// is given by the position of its top left corner. 
+    fn extrude(&mut self, x: usize, y: usize, width: usize, height: usize, amount: usize) {
+        if amount == 0 || width == 0 || height == 0 {
+            return;
+        }
+
+        let bytes_per_pixel = self.bytes_per_pixel;
+        let width_in_bytes = self.width * bytes_per_pixel;
+        // Extend each row of the rectangle to the left and to the right.
+        for row in y..(y + height) {
+            let row_start = row * width_in_bytes;
+            let left = row_start + x * bytes_per_pixel;
+            let right = row_start + (x + width - 1) * bytes_per_pixel;
+            for i in 1..=amount {
+                self.data.copy_within(left..(left + bytes_per_pixel), left - i * bytes_per_pixel);
+                self.data.copy_within(right..(right + bytes_per_pixel), right + i * bytes_per_pixel);
+            }
+        }
+
+        // Extend the top and bottom rows, including the pixels we just added.
+        let extruded_start = (x - amount) * bytes_per_pixel;
+        let extruded_length = (width + 2 * amount) * bytes_per_pixel;
+        let top = y * width_in_bytes + extruded_start;
+        let bottom = (y + height - 1) * width_in_bytes + extruded_start;
+        for i in 1..=amount {
+            self.data.copy_within(top..(top + extruded_length), top - i * width_in_bytes);
+            self.data.copy_within(bottom..(bottom + extruded_length), bottom + i * width_in_bytes);
+        }
+    }
+
+    /// Convert the pixels of the texture image into a different color type. This fails
+    /// if the image buffer does not match the dimensions and color type of the image.
+    fn convert(&self, color_type: ColorType) -> Option<TextureImage2D> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        Some(self.convert_with_weights(color_type, LuminanceWeights::default()))
+    }
+
+    /// Convert the pixels of the texture image into a different color type, computing the
+    /// luminance of color pixels with the given weights. Samples get widened from 8 bits to 
+    /// 16 bits exactly, and narrowed from 16 bits to 8 bits with rounding. Converting a 
+    /// pixel without an alpha channel into a color type with one makes it opaque, and 
+    /// converting the other way drops the alpha channel.
+    fn convert_with_weights(&self, color_type: ColorType, weights: LuminanceWeights) -> TextureImage2D {
+        if self.color_type == color_type {
+            return self.clone();
+        }
+
+        let pixel_count = self.width * self.height;
+        let mut data = Vec::with_capacity(pixel_count * color_type.bytes_per_pixel());
+        for pixel in self.data.chunks_exact(self.bytes_per_pixel).take(pixel_count) {
+            let rgba = decode_pixel(self.color_type, pixel);
+            encode_pixel(color_type, rgba, weights, &mut data);
+        }
+
+        TextureImage2D::new(self.width, self.height, color_type, data)
+    }
+
+    /// Convert the color channels of the texture image from one alpha mode to another.
+    fn convert_alpha_mode(&self, from: AlphaMode, to: AlphaMode) -> TextureImage2D {
+        let mut image = self.clone();
+        if from == to || !self.color_type.has_alpha_channel() {
+            return image;
+        }
+
+        let channel_count = self.color_type.channel_count();
+        let bytes_per_sample = self.bytes_per_pixel / channel_count;
+        // The alpha channel is the last channel of every color type with one.
+        let alpha_index = channel_count - 1;
+        let max = if bytes_per_sample == 2 { 65535 } else { 255 };
+        let convert = |sample: u32, alpha: u32| -> u32 {
+            match to {
+                AlphaMode::Premultiplied => (sample * alpha + max / 2) / max,
+                AlphaMode::Straight if alpha == 0 => 0,
+                AlphaMode::Straight => u32::min((sample * max + alpha / 2) / alpha, max),
+            }
+        };
+        for pixel in image.data.chunks_exact_mut(self.bytes_per_pixel) {
+            if bytes_per_sample == 2 {
+                let sample = |pixel: &[u8], i: usize| u16::from_ne_bytes([pixel[2 * i], pixel[2 * i + 1]]) as u32;
+                let alpha = sample(pixel, alpha_index);
+                for i in 0..alpha_index {
+                    let converted = convert(sample(pixel, i), alpha) as u16;
+                    pixel[(2 * i)..(2 * i + 2)].copy_from_slice(&converted.to_ne_bytes());
+                }
+            } else {
+                let alpha = pixel[alpha_index] as u32;
+                for sample in pixel[..alpha_index].iter_mut() {
+                    *sample = convert(*sample as u32, alpha) as u8;
+                }
+            }
+        }
+
+        image
+    }
+
+    /// Convert the color channels of the texture image from one color space to another. 
+    /// The alpha channel is linear in either color space, so it stays the same. Color 
+    /// channels that are premultiplied by alpha get straightened before the conversion, 
+    /// and premultiplied again after it.
+    fn convert_color_space(&self, from: ColorSpace, to: ColorSpace, alpha_mode: AlphaMode) -> TextureImage2D {
+        if from == to {
+            return self.clone();
+        }
+
+        let mut image = self.convert_alpha_mode(alpha_mode, AlphaMode::Straight);
+        let channel_count = self.color_type.channel_count();
+        let bytes_per_sample = self.bytes_per_pixel / channel_count;
+        let color_channel_count = if self.color_type.has_alpha_channel() { channel_count - 1 } else { channel_count };
+        let max = if bytes_per_sample == 2 { 65535 } else { 255 };
+        let transfer = match to {
+            ColorSpace::Srgb => linear_to_srgb,
+            ColorSpace::Linear => srgb_to_linear,
+        };
+        let table: Vec<u16> = (0..=max).map(|sample| {
+            let converted = transfer(sample as f64 / max as f64);
+            (converted * max as f64).round() as u16
+        }).collect();
+        for pixel in image.data.chunks_exact_mut(self.bytes_per_pixel) {
+            if bytes_per_sample == 2 {
+                for i in 0..color_channel_count {
+                    let sample = u16::from_ne_bytes([pixel[2 * i], pixel[2 * i + 1]]);
+                    pixel[(2 * i)..(2 * i + 2)].copy_from_slice(&table[sample as usize].to_ne_bytes());
+                }
+            } else {
+                for sample in pixel[..color_channel_count].iter_mut() {
+                    *sample = table[*sample as usize] as u8;
+                }
+            }
+        }
+
+        image.convert_alpha_mode(AlphaMode::Straight, alpha_mode)
+    }
+}
+
+/// A standalone image of a single texture, copied out of a texture atlas. The rows of the
+/// image are ordered from the top of the image to the bottom of the image, whatever the 
+/// origin of the atlas it came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextureImage {
+    /// The width of the image in pixels.
+    pub width: usize,
+    /// The height of the image in pixels.
+    pub height: usize,
+    /// The number of channels per pixel.
+    pub channel_count: usize,
+    /// The number of bytes per pixel.
+    pub bytes_per_pixel: usize,
+    /// The color type of the image.
+    pub color_type: ColorType,
+    /// The pixels of the image.
+    data: Vec<u8>,
+}
+
+impl TextureImage {
+    /// Construct a texture image from a texture image in the same layout.
+    fn from_texture_image_2d(image: TextureImage2D) -> TextureImage {
+        TextureImage {
+            width: image.width,
+            height: image.height,
+            channel_count: image.channel_count,
+            bytes_per_pixel: image.bytes_per_pixel,
+            color_type: image.color_type,
+            data: image.data,
+        }
+    }
+
+    /// Get the length of the image in units of the number of pixels.
+    #[inline]
+    pub fn len_pixels(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Get the length of the image in units of bytes.
+    #[inline]
+    pub fn len_bytes(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.data.as_ptr()
+    }
+
+    /// Get a view into the image as a byte slice. The samples of 16 bit color types are
+    /// in native byte order.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consume the image, returning its pixels.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Convert the image into an image from the underlying image library, keeping its 
+    /// color type.
+    pub fn to_dynamic_image(&self) -> image::DynamicImage {
+        let width = self.width as u32;
+        let height = self.height as u32;
+        let data = self.data.clone();
+        let samples_16 = || -> Vec<u16> {
+            self.data.chunks_exact(2).map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]])).collect()
+        };
+        // The length of the image buffer always agrees with the dimensions of the image,
+        // so constructing the image buffers cannot fail.
+        match self.color_type {
+            ColorType::L8 => image::DynamicImage::ImageLuma8(image::ImageBuffer::from_raw(width, height, data).unwrap()),
+            ColorType::La8 => image::DynamicImage::ImageLumaA8(image::ImageBuffer::from_raw(width, height, data).unwrap()),
+            ColorType::Rgb8 => image::DynamicImage::ImageRgb8(image::ImageBuffer::from_raw(width, height, data).unwrap()),
+            ColorType::Rgba8 => image::DynamicImage::ImageRgba8(image::ImageBuffer::from_raw(width, height, data).unwrap()),
+            ColorType::Bgr8 => image::DynamicImage::ImageBgr8(image::ImageBuffer::from_raw(width, height, data).unwrap()),
+            ColorType::Bgra8 => image::DynamicImage::ImageBgra8(image::ImageBuffer::from_raw(width, height, data).unwrap()),
+            ColorType::L16 => image::DynamicImage::ImageLuma16(image::ImageBuffer::from_raw(width, height, samples_16()).unwrap()),
+            ColorType::La16 => image::DynamicImage::ImageLumaA16(image::ImageBuffer::from_raw(width, height, samples_16()).unwrap()),
+            ColorType::Rgb16 => image::DynamicImage::ImageRgb16(image::ImageBuffer::from_raw(width, height, samples_16()).unwrap()),
+            ColorType::Rgba16 => image::DynamicImage::ImageRgba16(image::ImageBuffer::from_raw(width, height, samples_16()).unwrap()),
+        }
+    }
+
+    /// Convert the image into an 8 bit RGBA image buffer from the underlying image library,
+    /// for editing and making thumbnails.
+    pub fn to_rgba_image(&self) -> image::RgbaImage {
+        self.to_dynamic_image().into_rgba8()
+    }
+}
+
+/// Decode an sRGB encoded sample in the unit interval into a linear sample.
+fn srgb_to_linear(sample: f64) -> f64 {
+    if sample <= 0.04045 {
+        sample / 12.92
+    } else {
+        ((sample + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear sample in the unit interval into an sRGB encoded sample.
+fn linear_to_srgb(sample: f64) -> f64 {
+    if sample <= 0.0031308 {
+        sample * 12.92
+    } else {
+        1.055 * sample.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The weights of the red, green, and blue channels of a pixel in its luminance, for 
+/// converting color images into grayscale images. The weights get normalized to sum 
+/// to one, so converting a gray pixel into grayscale leaves it unchanged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LuminanceWeights {
+    /// The weight of the red channel.
+    pub red: f32,
+    /// The weight of the green channel.
+    pub green: f32,
+    /// The weight of the blue channel.
+    pub blue: f32,
+}
+
+impl LuminanceWeights {
+    /// The luminance weights of the ITU-R BT.709 standard used by sRGB.
+    pub const REC_709: LuminanceWeights = LuminanceWeights { red: 0.2126, green: 0.7152, blue: 0.0722 };
+    /// The luminance weights of the ITU-R BT.601 standard.
+    pub const REC_601: LuminanceWeights = LuminanceWeights { red: 0.299, green: 0.587, blue: 0.114 };
+
+    /// Compute the luminance of a 16 bit color.
+    fn luminance(self, red: u16, green: u16, blue: u16) -> u16 {
+        let total = self.red + self.green + self.blue;
+        if total <= 0.0 {
+            return 0;
+        }
+        let luminance = (self.red * red as f32 + self.green * green as f32 + self.blue * blue as f32) / total;
+
+        luminance.round().clamp(0.0, 65535.0) as u16
+    }
+}
+
+impl Default for LuminanceWeights {
+    fn default() -> LuminanceWeights {
+        LuminanceWeights::REC_709
+    }
+}
+
+/// Widen an 8 bit sample to 16 bits.
+#[inline]
+fn widen_sample(sample: u8) -> u16 {
+    sample as u16 * 257
+}
+
+/// Narrow a 16 bit sample to 8 bits, rounding to the nearest value.
+#[inline]
+fn narrow_sample(sample: u16) -> u8 {
+    ((sample as u32 * 255 + 32767) / 65535) as u8
+}
+
+/// Decode a pixel into 16 bit red, green, blue, and alpha samples. Gray pixels have the 
+/// same value in every color channel, and pixels without an alpha channel are opaque.
+fn decode_pixel(color_type: ColorType, pixel: &[u8]) -> [u16; 4] {
+    let sample16 = |i: usize| u16::from_ne_bytes([pixel[2 * i], pixel[2 * i + 1]]);
+    let sample8 = |i: usize| widen_sample(pixel[i]);
+    match color_type {
+        ColorType::L8 => [sample8(0), sample8(0), sample8(0), 65535],
+        ColorType::La8 => [sample8(0), sample8(0), sample8(0), sample8(1)],
+        ColorType::Rgb8 => [sample8(0), sample8(1), sample8(2), 65535],
+        ColorType::Rgba8 => [sample8(0), sample8(1), sample8(2), sample8(3)],
+        ColorType::Bgr8 => [sample8(2), sample8(1), sample8(0), 65535],
+        ColorType::Bgra8 => [sample8(2), sample8(1), sample8(0), sample8(3)],
+        ColorType::L16 => [sample16(0), sample16(0), sample16(0), 65535],
+        ColorType::La16 => [sample16(0), sample16(0), sample16(0), sample16(1)],
+        ColorType::Rgb16 => [sample16(0), sample16(1), sample16(2), 65535],
+        ColorType::Rgba16 => [sample16(0), sample16(1), sample16(2), sample16(3)],
+    }
+}
+
+/// Encode 16 bit red, green, blue, and alpha samples into a pixel of the given color type,
+/// appending it to an image buffer.
+fn encode_pixel(color_type: ColorType, rgba: [u16; 4], weights: LuminanceWeights, data: &mut Vec<u8>) {
+    let [red, green, blue, alpha] = rgba;
+    let luminance = || weights.luminance(red, green, blue);
+    let mut push16 = |samples: &[u16]| {
+        for sample in samples.iter() {
+            data.extend_from_slice(&sample.to_ne_bytes());
+        }
+    };
+    match color_type {
+        ColorType::L16 => push16(&[luminance()]),
+        ColorType::La16 => push16(&[luminance(), alpha]),
+        ColorType::Rgb16 => push16(&[red, green, blue]),
+        ColorType::Rgba16 => push16(&[red, green, blue, alpha]),
+        _ => {
+            let samples8 = match color_type {
+                ColorType::L8 => vec![luminance()],
+                ColorType::La8 => vec![luminance(), alpha],
+                ColorType::Rgb8 => vec![red, green, blue],
+                ColorType::Rgba8 => vec![red, green, blue, alpha],
+                ColorType::Bgr8 => vec![blue, green, red],
+                _ => vec![blue, green, red, alpha],
+            };
+            data.extend(samples8.into_iter().map(narrow_sample));
+        }
+    }
 }
 
 /// An atlas entry contains all the information about where a 
@@ -442,18 +1148,37 @@ struct AtlasEntry {
     bounding_box_tex: BoundingBoxTexCoords,
     /// The bounding box for the texture in units of pixels.
     bounding_box_pix: BoundingBoxPixelCoords,
+    /// The dimensions of the original image of the texture, and where the texture 
+    /// sits inside it.
+    source: SourceImagePixelCoords,
+    /// Whether the texture is stored in the atlas image rotated by 90 degrees clockwise.
+    rotated: bool,
+    /// The other names of the texture. Every alias refers to the same bounding box.
+    aliases: Vec<String>,
 }
 
 impl AtlasEntry {
-    /// Construct a new atlas entry.
+    /// Construct a new atlas entry for a texture in an atlas with the given dimensions.
     fn new(name: String, 
-        bounding_box_tex: BoundingBoxTexCoords, 
-        bounding_box_pix: BoundingBoxPixelCoords) -> AtlasEntry {
+        bounding_box_pix: BoundingBoxPixelCoords, 
+        source: SourceImagePixelCoords,
+        atlas_width: usize, atlas_height: usize) -> AtlasEntry {
         
+        let top_left = bounding_box_pix.top_left;
+        let u = top_left.u as f32 / atlas_width as f32;
+        let v = top_left.v as f32 / atlas_height as f32;
+        let offset_tex = OffsetTexCoords::new(u, v);
+        let width_tex = bounding_box_pix.width as f32 / atlas_width as f32;
+        let height_tex = bounding_box_pix.height as f32 / atlas_height as f32;
+        let bounding_box_tex = BoundingBoxTexCoords::new(offset_tex, width_tex, height_tex);
+
         AtlasEntry {
             name: name,
             bounding_box_tex: bounding_box_tex,
             bounding_box_pix: bounding_box_pix,
+            source: source,
+            rotated: false,
+            aliases: vec![],
         }
     }
 }
@@ -464,13 +1189,36 @@ impl AtlasEntry {
 struct TextureAtlas2DSerializationEntry {
     name: String,
     bounding_box: BoundingBoxPixelCoords,
+    /// The original image of a trimmed texture. This is absent for untrimmed textures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<SourceImagePixelCoords>,
+    /// Whether the texture is stored rotated. This is absent for upright textures.
+    #[serde(default, skip_serializing_if = "is_false")]
+    rotated: bool,
+    /// The other names of the texture. This is absent for textures without aliases.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+}
+
+#[inline]
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 impl TextureAtlas2DSerializationEntry {
-    fn new(name: String, bounding_box: BoundingBoxPixelCoords) -> TextureAtlas2DSerializationEntry {
+    fn new(
+        name: String, 
+        bounding_box: BoundingBoxPixelCoords, 
+        source: Option<SourceImagePixelCoords>,
+        rotated: bool,
+        aliases: Vec<String>) -> TextureAtlas2DSerializationEntry {
+        
         TextureAtlas2DSerializationEntry {
             name: name,
             bounding_box: bounding_box,
+            source: source,
+            rotated: rotated,
+            aliases: aliases,
         }
     } 
 }
@@ -480,13 +1228,39 @@ impl TextureAtlas2DSerializationEntry {
 #[derive(Serialize, Deserialize)]
 struct TextureAtlas2DSerialization {
     origin: Origin,
+    /// The color type of the atlas image. The atlas image file stores the pixels in the
+    /// nearest color type a PNG image supports, so the atlas gets converted back to this 
+    /// color type when it is loaded. This is left out when the atlas image file has the 
+    /// same color type, as are older atlas files, in which case the atlas has the color 
+    /// type of its atlas image file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color_type: Option<ColorType>,
+    /// Whether the color channels of the atlas image are premultiplied by alpha. This is
+    /// left out for straight alpha, as are older atlas files, in which case the alpha is 
+    /// straight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alpha_mode: Option<AlphaMode>,
+    /// The color space of the atlas image. This is left out for sRGB, as are older atlas 
+    /// files, in which case the atlas has the color space its atlas image file declares, 
+    /// or sRGB if it declares none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color_space: Option<ColorSpace>,
     coordinate_charts: HashMap<usize, TextureAtlas2DSerializationEntry>,
 }
 
 impl TextureAtlas2DSerialization {
-    fn new(origin: Origin, coordinate_charts: HashMap<usize, TextureAtlas2DSerializationEntry>) -> TextureAtlas2DSerialization {
+    fn new(
+        origin: Origin, 
+        color_type: ColorType, 
+        alpha_mode: AlphaMode,
+        color_space: ColorSpace,
+        coordinate_charts: HashMap<usize, TextureAtlas2DSerializationEntry>) -> TextureAtlas2DSerialization {
+        
         TextureAtlas2DSerialization {
             origin: origin,
+            color_type: Some(color_type).filter(|color_type| color_type.png_color_type() != *color_type),
+            alpha_mode: Some(alpha_mode).filter(|alpha_mode| *alpha_mode != AlphaMode::Straight),
+            color_space: Some(color_space).filter(|color_space| *color_space != ColorSpace::Srgb),
             coordinate_charts: coordinate_charts,
         }
     }
@@ -507,6 +1281,10 @@ pub struct TextureAtlas2D {
     pub color_type: ColorType,
     /// The origin in the atlas image for orienting the texture.
     origin: Origin,
+    /// Whether the color channels of the atlas image are premultiplied by alpha.
+    alpha_mode: AlphaMode,
+    /// The transfer function of the color channels of the atlas image.
+    color_space: ColorSpace,
     /// The table of texture names.
     texture_names: HashMap<String, usize>,
     /// The bounding boxes for each texture in the texture atlas.
@@ -515,32 +1293,50 @@ pub struct TextureAtlas2D {
     atlas_name: String,
     /// The underlying texture image.
     data: TextureImage2D,
+    /// The downsampled texture images of each mip level after the first, from the
+    /// largest to the smallest.
+    mipmaps: Vec<TextureImage2D>,
 }
 
 impl TextureAtlas2D {
-    /// Construct a new texture atlas.
+    /// Construct a new texture atlas. Entries that share an index are aliases of the same
+    /// texture: the first entry with a given index names the texture and supplies its 
+    /// bounding box, and the names of the remaining entries with that index become aliases 
+    /// for it.
     pub fn new(
         width: usize, height: usize, color_type: ColorType, origin: Origin, 
         entries: Vec<(usize, String, BoundingBoxPixelCoords)>, atlas_name: String, data: Vec<u8>) -> TextureAtlas2D {
         
-        let image_data = TextureImage2D::new(width, height, color_type, data);
-        let mut bounding_boxes = HashMap::new();
-        for (i, name_i, bounding_box_pix_i) in entries.iter() {
-            let top_left_i = bounding_box_pix_i.top_left;
-            let u = top_left_i.u as f32 / width as f32;
-            let v = top_left_i.v as f32 / height as f32;
-            let offset_tex_i = OffsetTexCoords::new(u, v);
-            let width_tex_i = bounding_box_pix_i.width as f32 / width as f32;
-            let height_tex_i = bounding_box_pix_i.height as f32 / height as f32;
-            let bounding_box_tex_i = BoundingBoxTexCoords::new(offset_tex_i, width_tex_i, height_tex_i);
-            let atlas_entry = AtlasEntry::new(name_i.clone(), bounding_box_tex_i, *bounding_box_pix_i);
-            bounding_boxes.insert(*i, atlas_entry);
+        let mut atlas_entries: Vec<(usize, AtlasEntry)> = vec![];
+        let mut positions: HashMap<usize, usize> = HashMap::new();
+        for (i, name_i, bounding_box_pix_i) in entries.into_iter() {
+            match positions.get(&i) {
+                Some(position) => atlas_entries[*position].1.aliases.push(name_i),
+                None => {
+                    let source_i = SourceImagePixelCoords::untrimmed(bounding_box_pix_i.width, bounding_box_pix_i.height);
+                    positions.insert(i, atlas_entries.len());
+                    atlas_entries.push((i, AtlasEntry::new(name_i, bounding_box_pix_i, source_i, width, height)));
+                }
+            }
         }
 
+        TextureAtlas2D::from_atlas_entries(width, height, color_type, origin, atlas_entries, atlas_name, data)
+    }
+
+    /// Construct a new texture atlas from a collection of atlas entries.
+    fn from_atlas_entries(
+        width: usize, height: usize, color_type: ColorType, origin: Origin, 
+        entries: Vec<(usize, AtlasEntry)>, atlas_name: String, data: Vec<u8>) -> TextureAtlas2D {
+
+        let image_data = TextureImage2D::new(width, height, color_type, data);
+        let bounding_boxes: HashMap<usize, AtlasEntry> = entries.into_iter().collect();
+
         let mut texture_names = HashMap::new();
-        for i in 0..bounding_boxes.len() {
-            let texture_name = bounding_boxes[&i].name.clone();
-            texture_names.insert(texture_name, i);
+        for (i, atlas_entry) in bounding_boxes.iter() {
+            texture_names.insert(atlas_entry.name.clone(), *i);
+            for alias in atlas_entry.aliases.iter() {
+                texture_names.insert(alias.clone(), *i);
+            }
         }
         
         TextureAtlas2D {
@@ -550,10 +1346,13 @@ impl TextureAtlas2D {
             bytes_per_pixel: image_data.bytes_per_pixel,
             color_type: color_type,
             origin: origin,
+            alpha_mode: AlphaMode::Straight,
+            color_space: ColorSpace::Srgb,
             texture_names: texture_names,
             bounding_boxes: bounding_boxes,
             atlas_name: atlas_name,
             data: image_data,
+            mipmaps: vec![],
         }
     }
 
@@ -577,13 +1376,14 @@ impl TextureAtlas2D {
     /// Get a view into the texture atlas image as a byte slice
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
-        &self.data.as_bytes()
+        self.data.as_bytes()
     }
 
-    /// Get the number of textures in the texture atlas.
+    /// Get the number of textures in the texture atlas. Aliases of a texture do not 
+    /// count as separate textures.
     #[inline]
     pub fn texture_count(&self) -> usize {
-        self.texture_names.len()
+        self.bounding_boxes.len()
     }
 
     /// Get the position of the origin in the texture atlas.
@@ -592,6 +1392,119 @@ impl TextureAtlas2D {
         self.origin
     }
 
+    /// Get whether the color channels of the atlas image are premultiplied by alpha.
+    #[inline]
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    /// Mark the color channels of the atlas image as premultiplied by alpha or not, 
+    /// without changing the atlas image. This is for atlases constructed from image data
+    /// that is already premultiplied. Use `convert_alpha_mode` to convert the atlas image.
+    #[inline]
+    pub fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    /// Convert the atlas image to the given alpha mode, multiplying the color channels by 
+    /// alpha to premultiply them, or dividing them by alpha to straighten them. Fully 
+    /// transparent pixels have no color left after premultiplying, so straightening them
+    /// gives black. Atlas images without an alpha channel are the same in both alpha 
+    /// modes, so only the alpha mode of the atlas changes.
+    pub fn convert_alpha_mode(&self, alpha_mode: AlphaMode) -> TextureAtlas2D {
+        let mut atlas = self.clone();
+        atlas.data = self.data.convert_alpha_mode(self.alpha_mode, alpha_mode);
+        atlas.mipmaps = self.mipmaps.iter()
+            .map(|mipmap| mipmap.convert_alpha_mode(self.alpha_mode, alpha_mode))
+            .collect();
+        atlas.alpha_mode = alpha_mode;
+
+        atlas
+    }
+
+    /// Get the color space of the atlas image.
+    #[inline]
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    /// Mark the atlas image as sRGB encoded or linear, without changing the atlas image. 
+    /// This is for atlases of data textures such as normal maps and masks, which must 
+    /// not be decoded from sRGB when they are sampled. Use `convert_color_space` to 
+    /// convert the atlas image.
+    #[inline]
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+    }
+
+    /// Convert the atlas image to the given color space, by applying the sRGB transfer 
+    /// function to the color channels to encode them, or its inverse to decode them. The 
+    /// alpha channel stays the same. Converting to a color space loses precision in the 
+    /// dark tones of 8 bit atlas images, so converting there and back again does not 
+    /// always give the original atlas image.
+    pub fn convert_color_space(&self, color_space: ColorSpace) -> TextureAtlas2D {
+        let mut atlas = self.clone();
+        atlas.data = self.data.convert_color_space(self.color_space, color_space, self.alpha_mode);
+        atlas.mipmaps = self.mipmaps.iter()
+            .map(|mipmap| mipmap.convert_color_space(self.color_space, color_space, self.alpha_mode))
+            .collect();
+        atlas.color_space = color_space;
+
+        atlas
+    }
+
+    /// Generate a full chain of mip levels for the atlas image, down to a single pixel,
+    /// replacing any mip levels the atlas already has. Each texture is filtered on its
+    /// own, with the filter clamped to its bounding box, so neighboring textures never 
+    /// bleed into each other. The filter averages colors in linear light, weighted by 
+    /// alpha. The pixels outside of every texture are transparent black in the generated
+    /// mip levels.
+    pub fn generate_mipmaps(&mut self) {
+        self.generate_mipmaps_with_gutter(0);
+    }
+
+    /// Generate a full chain of mip levels for the atlas image, surrounding each texture 
+    /// in every mip level with a gutter of the given number of pixels that repeats the 
+    /// edges of the texture. The gutter keeps texture filtering from sampling the empty 
+    /// space between textures in the smaller mip levels.
+    pub fn generate_mipmaps_with_gutter(&mut self, gutter: usize) {
+        self.mipmaps = mipmap::generate_mipmaps(self, gutter);
+    }
+
+    /// Remove the mip levels of the atlas image, leaving only the atlas image itself.
+    #[inline]
+    pub fn clear_mipmaps(&mut self) {
+        self.mipmaps.clear();
+    }
+
+    /// Get the number of mip levels of the atlas image, including the atlas image 
+    /// itself at level zero.
+    #[inline]
+    pub fn mip_level_count(&self) -> usize {
+        self.mipmaps.len() + 1
+    }
+
+    /// Get the width and height of a mip level of the atlas image.
+    pub fn mip_level_dimensions(&self, level: usize) -> Option<(usize, usize)> {
+        self.mip_level_image(level).map(|image| (image.width, image.height))
+    }
+
+    /// Get a view into a mip level of the atlas image as a byte slice. Level zero is 
+    /// the atlas image itself. The rows are in the same order as the rows of the atlas
+    /// image.
+    pub fn mip_level(&self, level: usize) -> Option<&[u8]> {
+        self.mip_level_image(level).map(|image| image.as_bytes())
+    }
+
+    /// Get the underlying texture image of a mip level.
+    fn mip_level_image(&self, level: usize) -> Option<&TextureImage2D> {
+        if level == 0 {
+            Some(&self.data)
+        } else {
+            self.mipmaps.get(level - 1)
+        }
+    }
+
     /// Get the name of the texture atlas.
     #[inline]
     pub fn atlas_name(&self) -> &str {
@@ -599,168 +1512,293 @@ impl TextureAtlas2D {
     }
 
     /// Get the set of all texture names for the textures inside the 
-    /// texture atlas.
+    /// texture atlas, including aliases.
     pub fn texture_names(&self) -> impl Iterator<Item = &str> {
         self.texture_names.keys().map(|s| s.as_str())
     }
 
+    /// Get the index of a texture by name. Every alias of a texture has the 
+    /// same index as the texture.
+    pub fn by_texture_name_index(&self, name: &str) -> Option<usize> {
+        self.texture_names.get(name).copied()
+    }
+
+    /// Get the name of a texture by index. This is the name the texture was packed 
+    /// under, not one of its aliases.
+    pub fn by_index_name(&self, index: usize) -> Option<&str> {
+        self.bounding_boxes.get(&index).map(|entry| entry.name.as_str())
+    }
+
+    /// Get the aliases of a texture by index.
+    pub fn by_index_aliases(&self, index: usize) -> Option<&[String]> {
+        self.bounding_boxes.get(&index).map(|entry| entry.aliases.as_slice())
+    }
+
     /// Get the set of all texture indices for the textures inside
     /// the texture atlas.
     pub fn indices(&self) -> Vec<usize> {
-        self.bounding_boxes.keys().map(|i| *i).collect()
+        self.bounding_boxes.keys().copied().collect()
     }
 
     /// Get the bounding box in units of pixels for a texture by name.
     pub fn by_texture_name(&self, name: &str) -> Option<BoundingBoxPixelCoords> {
-        match self.texture_names.get(name) {
-            Some(index) => Some(self.bounding_boxes[index].bounding_box_pix),
-            None => None,
-        }
+        self.texture_names.get(name).map(|index| self.bounding_boxes[index].bounding_box_pix)
     }
 
     /// Get the bounding box in units of the unit square for a texture by name.
     pub fn by_texture_name_uv(&self, name: &str) -> Option<BoundingBoxTexCoords> {
-        match self.texture_names.get(name) {
-            Some(index) => Some(self.bounding_boxes[index].bounding_box_tex),
-            None => None,
-        }
+        self.texture_names.get(name).map(|index| self.bounding_boxes[index].bounding_box_tex)
+    }
+
+    /// Get the dimensions of the original image of a texture by name, along with the
+    /// position of the texture inside the original image. 
+    pub fn by_texture_name_source(&self, name: &str) -> Option<SourceImagePixelCoords> {
+        self.texture_names.get(name).map(|index| self.bounding_boxes[index].source)
     }
 
     /// Get the bounding box in units of pixels for a texture by index.
     pub fn by_index(&self, index: usize) -> Option<BoundingBoxPixelCoords> {
-        if index < self.bounding_boxes.len() {
-            Some(self.bounding_boxes[&index].bounding_box_pix)
-        } else {
-            None
+        self.bounding_boxes.get(&index).map(|atlas_entry| atlas_entry.bounding_box_pix)
+    }
+
+    /// Copy a texture out of the atlas image by name into a standalone image. The rows of 
+    /// the image are ordered from the top of the texture to the bottom of the texture,
+    /// whatever the origin of the atlas, and rotated textures get rotated back upright.
+    /// Trimmed textures come out trimmed.
+    pub fn extract(&self, name: &str) -> Option<TextureImage> {
+        let index = self.texture_names.get(name)?;
+
+        self.extract_by_index(*index)
+    }
+
+    /// Copy a texture out of the atlas image by index into a standalone image.
+    pub fn extract_by_index(&self, index: usize) -> Option<TextureImage> {
+        self.extract_image(index).map(TextureImage::from_texture_image_2d)
+    }
+
+    /// Copy a texture out of the atlas image by index into a texture image, with its rows
+    /// ordered from the top down.
+    fn extract_image(&self, index: usize) -> Option<TextureImage2D> {
+        let atlas_entry = self.bounding_boxes.get(&index)?;
+        let (x, y, width, height) = atlas_entry.bounding_box_pix.to_image_rect(self.origin, self.height);
+        let mut image = match self.origin {
+            Origin::TopLeft => self.data.crop(x, y, width, height),
+            Origin::BottomLeft => self.data.crop(x, self.height - y - height, width, height),
+        };
+        let width_in_bytes = width * image.bytes_per_pixel;
+        orient_image(&mut image.data, self.origin, height, width_in_bytes);
+        if atlas_entry.rotated {
+            image = image.rotate_counterclockwise();
         }
+
+        Some(image)
     }
 
     /// Get the bounding box in units of the unit square for a texture by index.
     pub fn by_index_uv(&self, index: usize) -> Option<BoundingBoxTexCoords> {
-        if index < self.bounding_boxes.len() {
-            Some(self.bounding_boxes[&index].bounding_box_tex)
-        } else {
-            None
-        }
+        self.bounding_boxes.get(&index).map(|atlas_entry| atlas_entry.bounding_box_tex)
     }
 
-    /// Get the bounding box in units of pixels for a given texture by index.
-    pub fn by_index_corners(&self, index: usize) -> Option<BoundingBoxCornersPixelCoords> {
-        self.by_index(index).map(|bounding_box| {
-            let width = bounding_box.width;
-            let height = bounding_box.height;
-            let top_left = bounding_box.top_left;
-            let bottom_left = OffsetPixelCoords::new(top_left.u, top_left.v - height);
-            let top_right = OffsetPixelCoords::new(top_left.u + width, top_left.v);
-            let bottom_right = OffsetPixelCoords::new(top_left.u + width, top_left.v - height);
-    
+    /// Get the dimensions of the original image of a texture by index, along with the
+    /// position of the texture inside the original image. 
+    pub fn by_index_source(&self, index: usize) -> Option<SourceImagePixelCoords> {
+        self.bounding_boxes.get(&index).map(|entry| entry.source)
+    }
+
+    /// Get the corners of the bounding box of an atlas entry in units of pixels. The 
+    /// corners are labeled by where they appear when the texture is upright, so the corners
+    /// of a rotated texture are permuted relative to the bounding box in the atlas image.
+    /// The bottom corners sit on the bottom edge of the texture in the atlas image, so their 
+    /// rows are smaller than the top row with a bottom left origin, and larger with a top 
+    /// left one.
+    fn corners(&self, entry: &AtlasEntry) -> BoundingBoxCornersPixelCoords {
+        let bounding_box = entry.bounding_box_pix;
+        let width = bounding_box.width;
+        let height = bounding_box.height;
+        let top_left = bounding_box.top_left;
+        let bottom_v = match self.origin {
+            // The rows count up from the bottom of the atlas image, so the bottom row
+            // of the texture sits `height - 1` rows below its top row.
+            Origin::BottomLeft => top_left.v + 1 - height,
+            Origin::TopLeft => top_left.v + height,
+        };
+        let bottom_left = OffsetPixelCoords::new(top_left.u, bottom_v);
+        let top_right = OffsetPixelCoords::new(top_left.u + width, top_left.v);
+        let bottom_right = OffsetPixelCoords::new(top_left.u + width, bottom_v);
+
+        if entry.rotated {
+            // Rotating a texture clockwise moves its top left corner to the top right,
+            // its top right corner to the bottom right, and so on.
+            BoundingBoxCornersPixelCoords {
+                top_left: top_right,
+                top_right: bottom_right,
+                bottom_left: top_left,
+                bottom_right: bottom_left,
+            }
+        } else {
             BoundingBoxCornersPixelCoords {
                 top_left: top_left,
                 top_right: top_right,
                 bottom_left: bottom_left,
                 bottom_right: bottom_right,
             }
-        })
+        }
+    }
+
+    /// Get the corners of the bounding box of an atlas entry in units of the unit square.
+    fn corners_uv(&self, entry: &AtlasEntry) -> BoundingBoxCornersTexCoords {
+        let atlas_width = self.width as f32;
+        let atlas_height = self.height as f32;
+        let to_uv = |corner: OffsetPixelCoords| {
+            OffsetTexCoords::new(corner.u as f32 / atlas_width, corner.v as f32 / atlas_height)
+        };
+        let corners = self.corners(entry);
+
+        BoundingBoxCornersTexCoords {
+            top_left: to_uv(corners.top_left),
+            top_right: to_uv(corners.top_right),
+            bottom_left: to_uv(corners.bottom_left),
+            bottom_right: to_uv(corners.bottom_right),
+        }
     }
 
-    /// Get the bounding box in units of the unit square for a given texture by index.
+    /// Get the bounding box in units of pixels for a given texture by index. The corners 
+    /// of a rotated texture are labeled by where they appear when the texture is upright.
+    pub fn by_index_corners(&self, index: usize) -> Option<BoundingBoxCornersPixelCoords> {
+        self.bounding_boxes.get(&index).map(|entry| self.corners(entry))
+    }
+
+    /// Get the bounding box in units of the unit square for a given texture by index. The 
+    /// corners of a rotated texture are labeled by where they appear when the texture is 
+    /// upright, so a quad mapped with these corners renders the texture upright.
     pub fn by_index_corners_uv(&self, index: usize) -> Option<BoundingBoxCornersTexCoords> {
-        self.by_index(index).map(|bounding_box| {
-            let atlas_width = self.width;
-            let atlas_height = self.height;
-            let width = bounding_box.width;
-            let height = bounding_box.height;
-            let top_left = bounding_box.top_left;
-            let bottom_left = OffsetTexCoords::new(
-                (top_left.u as f32) / (atlas_width as f32), ((top_left.v - height) as f32) / (atlas_height as f32)
-            );
-            let top_right = OffsetTexCoords::new(
-                ((top_left.u + width) as f32) / (atlas_width as f32), (top_left.v as f32) / (atlas_height as f32)
-            );
-            let bottom_right = OffsetTexCoords::new(
-                ((top_left.u + width) as f32) / (atlas_width as f32), ((top_left.v - height) as f32) / (atlas_height as f32)
-            );
-            let top_left = OffsetTexCoords::new(
-                top_left.u as f32 / atlas_width as f32, top_left.v as f32 / atlas_height as f32
-            );
-    
-            BoundingBoxCornersTexCoords {
-                top_left: top_left,
-                top_right: top_right,
-                bottom_left: bottom_left,
-                bottom_right: bottom_right,
-            }
-        })
+        self.bounding_boxes.get(&index).map(|entry| self.corners_uv(entry))
     }
 
-    /// Get the bounding box in units of pixels for a given texture by name.
+    /// Get the bounding box in units of pixels for a given texture by name. The corners 
+    /// of a rotated texture are labeled by where they appear when the texture is upright.
     pub fn by_texture_name_corners(&self, name: &str) -> Option<BoundingBoxCornersPixelCoords> {
-        self.by_texture_name(name).map(|bounding_box| {
-            let width = bounding_box.width;
-            let height = bounding_box.height;
-            let top_left = bounding_box.top_left;
-            let bottom_left = OffsetPixelCoords::new(top_left.u, top_left.v - height);
-            let top_right = OffsetPixelCoords::new(top_left.u + width, top_left.v);
-            let bottom_right = OffsetPixelCoords::new(top_left.u + width, top_left.v - height);
-    
-            BoundingBoxCornersPixelCoords {
-                top_left: top_left,
-                top_right: top_right,
-                bottom_left: bottom_left,
-                bottom_right: bottom_right,
-            }
-        })
+        self.texture_names.get(name).map(|index| self.corners(&self.bounding_boxes[index]))
     }
 
-    /// Get the bounding box in units of the unit square for a given texture by name.
+    /// Get the bounding box in units of the unit square for a given texture by name. The 
+    /// corners of a rotated texture are labeled by where they appear when the texture is 
+    /// upright, so a quad mapped with these corners renders the texture upright.
     pub fn by_texture_name_corners_uv(&self, name: &str) -> Option<BoundingBoxCornersTexCoords> {
-        self.by_texture_name(name).map(|bounding_box| {
-            let atlas_width = self.width;
-            let atlas_height = self.height;
-            let width = bounding_box.width;
-            let height = bounding_box.height;
-            let top_left = bounding_box.top_left;
-            let bottom_left = OffsetTexCoords::new(
-                (top_left.u as f32) / (atlas_width as f32), ((top_left.v - height) as f32) / (atlas_height as f32)
-            );
-            let top_right = OffsetTexCoords::new(
-                ((top_left.u + width) as f32) / (atlas_width as f32), (top_left.v as f32) / (atlas_height as f32)
-            );
-            let bottom_right = OffsetTexCoords::new(
-                ((top_left.u + width) as f32) / (atlas_width as f32), ((top_left.v - height) as f32) / (atlas_height as f32)
-            );
-            let top_left = OffsetTexCoords::new(
-                top_left.u as f32 / atlas_width as f32, top_left.v as f32 / atlas_height as f32
-            );
-    
-            BoundingBoxCornersTexCoords {
-                top_left: top_left,
-                top_right: top_right,
-                bottom_left: bottom_left,
-                bottom_right: bottom_right,
-            }
-        })
+        self.texture_names.get(name).map(|index| self.corners_uv(&self.bounding_boxes[index]))
+    }
+
+    /// Determine whether a texture is stored in the atlas image rotated by 90 degrees 
+    /// clockwise, by name.
+    pub fn by_texture_name_rotated(&self, name: &str) -> Option<bool> {
+        self.texture_names.get(name).map(|index| self.bounding_boxes[index].rotated)
+    }
+
+    /// Determine whether a texture is stored in the atlas image rotated by 90 degrees 
+    /// clockwise, by index.
+    pub fn by_index_rotated(&self, index: usize) -> Option<bool> {
+        self.bounding_boxes.get(&index).map(|entry| entry.rotated)
     }
 
     /// Get the collection of all bounding boxes for the textures inside the 
     /// texture atlas.
     fn coordinate_charts(&self) -> TextureAtlas2DSerialization {
         let mut coordinate_charts = HashMap::new();
-        for name in self.texture_names.keys() {
-            let name_str = name.clone();
-            let index = self.texture_names[name.as_str()];
-            let bounding_box = self.bounding_boxes[&index].bounding_box_pix;
-            let entry = TextureAtlas2DSerializationEntry::new(name_str, bounding_box);
-            coordinate_charts.insert(index, entry);
+        for (index, atlas_entry) in self.bounding_boxes.iter() {
+            let name_str = atlas_entry.name.clone();
+            let bounding_box = atlas_entry.bounding_box_pix;
+            let source = if atlas_entry.source.is_trimmed(&bounding_box) {
+                Some(atlas_entry.source)
+            } else {
+                None
+            };
+            let entry = TextureAtlas2DSerializationEntry::new(
+                name_str, bounding_box, source, atlas_entry.rotated, atlas_entry.aliases.clone()
+            );
+            coordinate_charts.insert(*index, entry);
         }
 
-        TextureAtlas2DSerialization::new(self.origin, coordinate_charts)
+        TextureAtlas2DSerialization::new(
+            self.origin, self.color_type, self.alpha_mode, self.color_space, coordinate_charts
+        )
+    }
+
+    /// Convert the atlas image into a different color type, computing the luminance of 
+    /// color pixels with the ITU-R BT.709 weights. The textures keep their places in the
+    /// atlas. Samples get widened from 8 bits to 16 bits exactly, and narrowed from 16 
+    /// bits to 8 bits with rounding. Converting an atlas without an alpha channel into a
+    /// color type with one makes every pixel opaque, and converting the other way drops 
+    /// the alpha channel.
+    pub fn convert_to(&self, color_type: ColorType) -> TextureAtlas2D {
+        self.convert_to_with_weights(color_type, LuminanceWeights::default())
+    }
+
+    /// Convert the atlas image into a different color type, computing the luminance of
+    /// color pixels with the given weights.
+    pub fn convert_to_with_weights(&self, color_type: ColorType, weights: LuminanceWeights) -> TextureAtlas2D {
+        let data = self.data.convert_with_weights(color_type, weights);
+        let mipmaps = self.mipmaps.iter()
+            .map(|mipmap| mipmap.convert_with_weights(color_type, weights))
+            .collect();
+
+        TextureAtlas2D {
+            width: self.width,
+            height: self.height,
+            channel_count: data.channel_count,
+            bytes_per_pixel: data.bytes_per_pixel,
+            color_type: color_type,
+            origin: self.origin,
+            alpha_mode: self.alpha_mode,
+            color_space: self.color_space,
+            texture_names: self.texture_names.clone(),
+            bounding_boxes: self.bounding_boxes.clone(),
+            atlas_name: self.atlas_name.clone(),
+            data: data,
+            mipmaps: mipmaps,
+        }
     }
 
     /// Get the underlying texture image.
     fn image(&self) -> &TextureImage2D {
         &self.data
     }
+
+    /// Add an entry for a texture to the texture atlas under the smallest unused index.
+    fn insert_entry(&mut self, atlas_entry: AtlasEntry) -> usize {
+        let index = (0..).find(|i| !self.bounding_boxes.contains_key(i)).unwrap();
+        self.texture_names.insert(atlas_entry.name.clone(), index);
+        for alias in atlas_entry.aliases.iter() {
+            self.texture_names.insert(alias.clone(), index);
+        }
+        self.bounding_boxes.insert(index, atlas_entry);
+
+        index
+    }
+
+    /// Remove the entry for a texture from the texture atlas, along with every name of
+    /// the texture. The indices of the other textures do not change.
+    fn remove_entry(&mut self, index: usize) -> Option<AtlasEntry> {
+        let atlas_entry = self.bounding_boxes.remove(&index)?;
+        self.texture_names.retain(|_, i| *i != index);
+
+        Some(atlas_entry)
+    }
+
+    /// Copy an image into the atlas image at a given position, with the rows of the 
+    /// image and the position counted from the top of the atlas image.
+    fn write_image(&mut self, image: &TextureImage2D, x: usize, y: usize) {
+        match self.origin {
+            Origin::TopLeft => self.data.copy_from(image, x, y),
+            Origin::BottomLeft => {
+                // The rows of the atlas image are stored from the bottom up, so we flip the 
+                // image over and place it as many rows from the bottom as it is from the top.
+                let mut flipped = image.clone();
+                let width_in_bytes = flipped.width * flipped.bytes_per_pixel;
+                orient_image(&mut flipped.data, Origin::BottomLeft, flipped.height, width_in_bytes);
+                self.data.copy_from(&flipped, x, self.height - y - image.height);
+            }
+        }
+    }
 }
 
 /// A data structure storing a collection of texture atlases. In a multi-texture atlas we denote
@@ -777,8 +1815,8 @@ impl MultiTextureAtlas2D {
     /// Construct a new multi-texture atlas. 
     pub fn new(pages: Vec<TextureAtlas2D>) -> MultiTextureAtlas2D {
         let mut page_names = HashMap::new();
-        for i in 0..pages.len() {
-            page_names.insert(pages[i].atlas_name.clone(), i);
+        for (i, page) in pages.iter().enumerate() {
+            page_names.insert(page.atlas_name.clone(), i);
         }
 
         MultiTextureAtlas2D {
@@ -838,7 +1876,7 @@ impl std::ops::Index<&str> for MultiTextureAtlas2D {
 
     #[inline]
     fn index(&self, index: &str) -> &Self::Output {
-        &self.by_page_name(index).unwrap()
+        self.by_page_name(index).unwrap()
     } 
 }
 
@@ -871,35 +1909,23 @@ fn orient_image(image: &mut [u8], origin: Origin, height: usize, width_in_bytes:
         let half_height = height / 2;
         for row in 0..half_height {
             for col in 0..width_in_bytes {
-                let temp = image[row * width_in_bytes + col];
-                image[row * width_in_bytes + col] = image[((height - row - 1) * width_in_bytes) + col];
-                image[((height - row - 1) * width_in_bytes) + col] = temp;
+                image.swap(row * width_in_bytes + col, ((height - row - 1) * width_in_bytes) + col);
             }
         }
     }
 }
 
-/// Load an atlas image file from a reader.
+/// Load an atlas image file from a reader. The rows of the resulting image are 
+/// ordered from the top of the image to the bottom of the image.
 fn load_image_from_reader<R: io::Read>(reader: R) -> Result<TextureImage2D, ErrorKind> {
     let png_reader = png::PngDecoder::new(reader).map_err(|_| {
         ErrorKind::CouldNotLoadAtlasImageBuffer
     })?;
     let (width, height) = png_reader.dimensions();
     let (width, height) = (width as usize, height as usize);
-    let color_type = match png_reader.color_type() {
-        image::ColorType::L8 => ColorType::L8,
-        image::ColorType::La8 => ColorType::La8,
-        image::ColorType::Rgb8 => ColorType::Rgb8,
-        image::ColorType::Rgba8 => ColorType::Rgba8,
-        image::ColorType::L16 => ColorType::L16,
-        image::ColorType::La16 => ColorType::La16,
-        image::ColorType::Rgb16 => ColorType::Rgb16,
-        image::ColorType::Rgba16 => ColorType::Rgba16,
-        image::ColorType::Bgr8 => ColorType::Bgr8,
-        image::ColorType::Bgra8 => ColorType::Bgra8,
-        _ => {
-            return Err(ErrorKind::UnrecognizedColorType);
-        }
+    let color_type = match ColorType::from_image_color_type(png_reader.color_type()) {
+        Some(color_type) => color_type,
+        None => return Err(ErrorKind::UnrecognizedColorType),
     };
     let bytes_per_pixel = png_reader.color_type().bytes_per_pixel() as usize;
     let mut image_data: Vec<u8> = vec![0; width * height * bytes_per_pixel];
@@ -907,21 +1933,88 @@ fn load_image_from_reader<R: io::Read>(reader: R) -> Result<TextureImage2D, Erro
         ErrorKind::CouldNotLoadAtlasImageBuffer
     })?;
 
-    let width_in_bytes = bytes_per_pixel * width;
-    let half_height = height / 2;
-    for row in 0..half_height {
-        for col in 0..width_in_bytes {
-            let temp = image_data[row * width_in_bytes + col];
-            image_data[row * width_in_bytes + col] = image_data[((height - row - 1) * width_in_bytes) + col];
-            image_data[((height - row - 1) * width_in_bytes) + col] = temp;
+    let tex_image = TextureImage2D::new(width, height, color_type, image_data);
+
+    Ok(tex_image)
+}
+
+/// Load an atlas image file out of an atlas file, converting it to the color type the 
+/// coordinate charts record and orienting it for the origin of the atlas. This also 
+/// returns the contents of the atlas image file.
+fn atlas_image_from_reader<R: io::Read + io::Seek>(
+    zip_reader: &mut ZipArchive<R>,
+    multi_atlas_name: &str,
+    page_name: &str,
+    image_file_name: &str,
+    atlas_chart_data: &TextureAtlas2DSerialization) -> Result<(TextureImage2D, Vec<u8>), TextureAtlas2DError> {
+
+    let mut image_file = zip_reader.by_name(image_file_name).map_err(|e| {
+        let kind = ErrorKind::CouldNotLoadAtlasImageBuffer;
+        let some_multi_atlas_name = Some(String::from(multi_atlas_name));
+        let atlas_name = Some(String::from(page_name));
+        TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, Some(Box::new(e)))
+    })?;
+    let mut image_file_data = vec![];
+    io::Read::read_to_end(&mut image_file, &mut image_file_data).map_err(|e| {
+        let kind = ErrorKind::CouldNotLoadAtlasImageBuffer;
+        let some_multi_atlas_name = Some(String::from(multi_atlas_name));
+        let atlas_name = Some(String::from(page_name));
+        TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, Some(Box::new(e)))
+    })?;
+    let mut tex_image = load_image_from_reader(image_file_data.as_slice()).map_err(|kind| {
+        let some_multi_atlas_name = Some(String::from(multi_atlas_name));
+        let atlas_name = Some(String::from(page_name));
+        TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, None)
+    })?;
+
+    // Convert the atlas image back to the color type it had when it was written out,
+    // if the atlas image file could not store it directly.
+    if let Some(color_type) = atlas_chart_data.color_type {
+        if color_type != tex_image.color_type {
+            tex_image = tex_image.convert(color_type).ok_or_else(|| {
+                let kind = ErrorKind::CouldNotLoadAtlasImageBuffer;
+                let some_multi_atlas_name = Some(String::from(multi_atlas_name));
+                let atlas_name = Some(String::from(page_name));
+                TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, None)
+            })?;
         }
     }
 
-    let tex_image = TextureImage2D::new(width, height, color_type, image_data);
+    // PNG images index start from the top left corner of the image, so we flip the image
+    // over if the origin of the atlas is the bottom left corner of the image.
+    let width_in_bytes = tex_image.bytes_per_pixel * tex_image.width;
+    orient_image(&mut tex_image.data, atlas_chart_data.origin, tex_image.height, width_in_bytes);
+
+    Ok((tex_image, image_file_data))
+}
+
+/// Decode an atlas image file in any image format the image library supports, and 
+/// orient it for the origin of the atlas.
+fn decode_atlas_image(image_data: &[u8], origin: Origin) -> Result<TextureImage2D, ErrorKind> {
+    let image = image::load_from_memory(image_data).map_err(|_| {
+        ErrorKind::CouldNotLoadAtlasImageBuffer
+    })?;
+    let mut tex_image = TextureImage2D::from_dynamic_image(&image).ok_or(ErrorKind::UnrecognizedColorType)?;
+    let width_in_bytes = tex_image.bytes_per_pixel * tex_image.width;
+    orient_image(&mut tex_image.data, origin, tex_image.height, width_in_bytes);
 
     Ok(tex_image)
 }
 
+/// Check that the dimensions of an atlas image are powers of two.
+fn dimension_warnings(width: usize, height: usize) -> TextureAtlas2DWarning {
+    if (width & (width - 1)) != 0 || (height & (height - 1)) != 0 {
+        TextureAtlas2DWarning::TextureDimensionsAreNotAPowerOfTwo
+    } else {
+        TextureAtlas2DWarning::NoWarnings
+    }
+}
+
+/// Get the name of the file in an atlas file storing a mip level of an atlas image.
+fn mip_level_file_name(page_name: &str, level: usize) -> String {
+    format!("{}.mip{}.png", page_name, level)
+}
+
 fn atlas_from_reader<R: io::Read + io::Seek>(zip_reader: &mut ZipArchive<R>, multi_atlas_name: &str, page_name: &str) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
     let coordinate_charts_name = format!("{}.json", page_name);
     let coordinate_charts_file = zip_reader.by_name(&coordinate_charts_name).map_err(|e| {
@@ -937,37 +2030,58 @@ fn atlas_from_reader<R: io::Read + io::Seek>(zip_reader: &mut ZipArchive<R>, mul
         TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, Some(Box::new(e)))
     })?;
     let image_file_name = format!("{}.png", page_name);
-    let image_file = zip_reader.by_name(&image_file_name).map_err(|e| {
-        let kind = ErrorKind::CouldNotLoadAtlasImageBuffer;
-        let some_multi_atlas_name = Some(String::from(multi_atlas_name));
-        let atlas_name = Some(String::from(page_name));
-        TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, Some(Box::new(e)))
-    })?;
-    let tex_image = load_image_from_reader(image_file).map_err(|kind| {
-        let some_multi_atlas_name = Some(String::from(multi_atlas_name));
-        let atlas_name = Some(String::from(page_name));
-        TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, None)
-    })?;
+    let (tex_image, image_file_data) = atlas_image_from_reader(
+        zip_reader, multi_atlas_name, page_name, &image_file_name, &atlas_chart_data
+    )?;
+
+    // Load the mip levels of the atlas image, if the atlas file has any.
+    let mut mipmaps = vec![];
+    for level in 1..mipmap::level_count(tex_image.width, tex_image.height) {
+        let mip_file_name = mip_level_file_name(page_name, level);
+        if zip_reader.file_names().all(|file_name| file_name != mip_file_name) {
+            break;
+        }
+        let (mipmap, _) = atlas_image_from_reader(
+            zip_reader, multi_atlas_name, page_name, &mip_file_name, &atlas_chart_data
+        )?;
+        let expected_dimensions = mipmap::level_dimensions(tex_image.width, tex_image.height, level);
+        if (mipmap.width, mipmap.height) != expected_dimensions || mipmap.color_type != tex_image.color_type {
+            let kind = ErrorKind::InvalidImageData;
+            let some_multi_atlas_name = Some(String::from(multi_atlas_name));
+            let atlas_name = Some(String::from(page_name));
+            return Err(TextureAtlas2DError::new(kind, some_multi_atlas_name, atlas_name, None));
+        }
+        mipmaps.push(mipmap);
+    }
     
-    // Check that the image size is a power of two.
     let width = tex_image.width;
     let height = tex_image.height;
-    let warnings = if (width & (width - 1)) != 0 || (height & (height - 1)) != 0 {
-        TextureAtlas2DWarning::TextureDimensionsAreNotAPowerOfTwo
-    } else {
-        TextureAtlas2DWarning::NoWarnings
-    };
+    let warnings = dimension_warnings(width, height);
 
     let coordinate_charts = atlas_chart_data.coordinate_charts;
-    let mut atlas_entries: Vec<(usize, String, BoundingBoxPixelCoords)> = vec![];
-    for (i, chart_i) in coordinate_charts.iter() {
-        atlas_entries.push((*i, chart_i.name.clone(), chart_i.bounding_box));
+    let mut atlas_entries = vec![];
+    for (i, chart_i) in coordinate_charts.into_iter() {
+        let bounding_box_i = chart_i.bounding_box;
+        let source_i = chart_i.source.unwrap_or_else(|| {
+            SourceImagePixelCoords::untrimmed(bounding_box_i.width, bounding_box_i.height)
+        });
+        let mut atlas_entry = AtlasEntry::new(chart_i.name, bounding_box_i, source_i, width, height);
+        atlas_entry.rotated = chart_i.rotated;
+        atlas_entry.aliases = chart_i.aliases;
+        atlas_entries.push((i, atlas_entry));
     }
 
     let color_type = tex_image.color_type;
     let origin = atlas_chart_data.origin;
     let atlas_name = String::from(page_name);
-    let atlas = TextureAtlas2D::new(width, height, color_type, origin, atlas_entries, atlas_name, tex_image.data);
+    let mut atlas = TextureAtlas2D::from_atlas_entries(
+        width, height, color_type, origin, atlas_entries, atlas_name, tex_image.data
+    );
+    atlas.alpha_mode = atlas_chart_data.alpha_mode.unwrap_or_default();
+    atlas.color_space = atlas_chart_data.color_space
+        .or_else(|| png_chunks::color_space(&image_file_data))
+        .unwrap_or_default();
+    atlas.mipmaps = mipmaps;
 
     Ok(TextureAtlas2DResult {
         atlas: atlas,
@@ -976,46 +2090,59 @@ fn atlas_from_reader<R: io::Read + io::Seek>(zip_reader: &mut ZipArchive<R>, mul
 }
 
 fn extract_atlas_names<R: io::Read + io::Seek>(zip_reader: &ZipArchive<R>) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let chart_names: Vec<&str> = zip_reader
+        .file_names()
+        .filter_map(|file_name| file_name.strip_suffix(".json"))
+        .collect();
+    let image_names: Vec<&str> = zip_reader
+        .file_names()
+        .filter_map(|file_name| file_name.strip_suffix(".png"))
+        .collect();
+    // The mip levels of an atlas image are stored next to it, and are not pages of 
+    // their own.
+    let is_mip_level = |image_name: &str| -> bool {
+        match image_name.rfind(".mip") {
+            Some(position) => {
+                let level = &image_name[(position + 4)..];
+                !level.is_empty() && 
+                level.bytes().all(|byte| byte.is_ascii_digit()) && 
+                chart_names.contains(&&image_name[..position])
+            }
+            None => false,
+        }
+    };
+
     let mut atlas_names = vec![];
     let mut atlases_missing_coordinates = vec![];
     let mut atlases_missing_images = vec![];
-    let mut file_names = zip_reader
-        .file_names()
-        .filter(|file_name| file_name.ends_with(".json") || file_name.ends_with(".png"))
-        .collect::<Vec<&str>>();
-    file_names.sort();
-
-    let mut i = 0;
-    while i < file_names.len() {
-        if file_names[i].ends_with(".json") && file_names[i + 1].ends_with(".png") {
+    for chart_name in chart_names.iter() {
+        if image_names.contains(chart_name) {
             // The atlas contains both the coordinate chart file and the atlas image file.
-            let length = file_names[i].len() - 5;
-            let atlas_name = String::from(&file_names[i][..length]);
-            atlas_names.push(atlas_name);
-            i += 2;
-        } else if file_names[i].ends_with(".json") && !file_names[i + i].ends_with(".png") {
+            atlas_names.push(String::from(*chart_name));
+        } else {
             // The atlas has the coordinate chart file but lacks the atlas image file.
-            let length = file_names[i].len() - 5;
-            let atlas_name = String::from(&file_names[i][..length]);
-            atlases_missing_images.push(atlas_name);
-            i += 1;
-        } else if !file_names[i].ends_with(".json") && file_names[i + i].ends_with(".png") {
+            atlases_missing_images.push(String::from(*chart_name));
+        }
+    }
+    for image_name in image_names.iter() {
+        if !chart_names.contains(image_name) && !is_mip_level(image_name) {
             // The atlas lacks the coordinate chart but has the atlas image file.
-            let length = file_names[i].len() - 4;
-            let atlas_name = String::from(&file_names[i][..length]);
-            atlases_missing_coordinates.push(atlas_name);
-            i += 1;
-        } else {
-            // We should not get here. The code before the loop that filtered and sorted the file list 
-            // should have taken care of this.
-            panic!();
+            atlases_missing_coordinates.push(String::from(*image_name));
         }
     }
+    atlas_names.sort();
+    atlases_missing_coordinates.sort();
+    atlases_missing_images.sort();
 
     (atlas_names, atlases_missing_coordinates, atlases_missing_images)
 }
 
 /// Load a multi texture atlas from a readable endpoint. This primarily includes files and buffers in memory.
+///
+/// The atlas image files store their rows from the top of the image to the bottom, so 
+/// the rows are flipped over only for atlases with a bottom left origin. Earlier versions 
+/// of this library flipped the rows for either origin, so atlases with a top left origin 
+/// now load with the pixels they were written out with instead of upside down.
 pub fn from_reader<R: io::Read + io::Seek>(reader: R, multi_atlas_name: &str) -> Result<MultiTextureAtlas2DResult, TextureAtlas2DError> {
     let mut zip_reader = zip::ZipArchive::new(reader).map_err(|e| {
         let kind = ErrorKind::CouldNotOpenTextureAtlas;
@@ -1071,6 +2198,95 @@ pub fn from_reader<R: io::Read + io::Seek>(reader: R, multi_atlas_name: &str) ->
     })
 }
 
+/// Encode an image into a PNG image file declaring the given color space. The rows of 
+/// the image must be ordered from the top of the image to the bottom of the image.
+fn encode_png_image(image: &TextureImage2D, color_space: ColorSpace, atlas_name: &str) -> Result<Vec<u8>, TextureAtlas2DError> {
+    // PNG images cannot store every color type, so we convert the image to the nearest 
+    // color type a PNG image can store. The coordinate charts record the original color
+    // type for converting it back.
+    let png_color_type = image.color_type.png_color_type();
+    let mut png_image = if png_color_type == image.color_type {
+        image.clone()
+    } else {
+        image.convert(png_color_type).ok_or_else(|| {
+            let kind = ErrorKind::InvalidImageData;
+            let multi_atlas_name = None;
+            let atlas_name = Some(String::from(atlas_name));
+
+            TextureAtlas2DError::new(kind, multi_atlas_name, atlas_name, None)
+        })?
+    };
+
+    // The samples of 16 bit images are stored in native byte order, but the PNG 
+    // encoder expects them in the big endian byte order of PNG images.
+    if png_color_type.bits_per_pixel() / png_color_type.channel_count() == 16 {
+        for sample in png_image.data.chunks_exact_mut(2) {
+            let bytes = u16::from_ne_bytes([sample[0], sample[1]]).to_be_bytes();
+            sample.copy_from_slice(&bytes);
+        }
+    }
+
+    let mut png_data = vec![];
+    let png_writer = png::PngEncoder::new(&mut png_data);
+    let height = image.height as u32;
+    let width = image.width as u32;
+    let color = png_color_type.to_image_color_type();
+    png_writer.encode(png_image.as_bytes(), width, height, color).map_err(|e| {
+        let kind = ErrorKind::IoError;
+        let multi_atlas_name = None;
+        let atlas_name = None;
+
+        TextureAtlas2DError::new(
+            kind, multi_atlas_name, atlas_name, Some(Box::new(e))
+        )
+    })?;
+
+    // Declare the color space of the image, so that image viewers and tools that only 
+    // read the image file interpret it correctly.
+    Ok(png_chunks::with_color_space(png_data, color_space))
+}
+
+/// Write an atlas image, or one of its mip levels, out to an atlas file.
+fn write_atlas_image<W>(
+    zip_file: &mut zip::ZipWriter<W>,
+    options: zip::write::FileOptions,
+    file_name: &str,
+    atlas: &TextureAtlas2D,
+    image: &TextureImage2D) -> Result<(), TextureAtlas2DError>
+    where W: io::Write + io::Seek
+{
+    // If the origin is the bottom left of the image, we need to flip the image back over
+    // before writing it out. PNG images index start from the top left corner of
+    // the image.
+    let mut image = image.clone();
+    let width_in_bytes = image.bytes_per_pixel * image.width;
+    orient_image(&mut image.data, atlas.origin, image.height, width_in_bytes);
+    let png_data = encode_png_image(&image, atlas.color_space, &atlas.atlas_name)?;
+
+    // Write out the image file.
+    zip_file.start_file(file_name, options)
+            .map_err(|e| {
+                let kind = ErrorKind::IoError;
+                let multi_atlas_name = None;
+                let atlas_name = None;
+
+                TextureAtlas2DError::new(
+                    kind, multi_atlas_name, atlas_name, Some(Box::new(e))
+                )
+            })?;
+    io::Write::write_all(zip_file, &png_data).map_err(|e| {
+        let kind = ErrorKind::IoError;
+        let multi_atlas_name = None;
+        let atlas_name = None;
+
+        TextureAtlas2DError::new(
+            kind, multi_atlas_name, atlas_name, Some(Box::new(e))
+        )
+    })?;
+
+    Ok(())
+}
+
 /// Write a multi texture atlas out to any writable endpoint. This 
 /// includes files and buffers in memory.
 pub fn to_writer<W>(writer: W, multi_atlas: &MultiTextureAtlas2D) -> Result<(), TextureAtlas2DError> 
@@ -1104,38 +2320,13 @@ pub fn to_writer<W>(writer: W, multi_atlas: &MultiTextureAtlas2D) -> Result<(),
                         )
                    })?;
 
-        // If the origin is the bottom left of the image, we need to flip the image back over
-        // before writing it out. PNG images index start from the top left corner of
-        // the image.
-        let mut image = atlas.image().clone();
-        let bytes_per_pixel = atlas.color_type.bytes_per_pixel();
-        let width_in_bytes =  bytes_per_pixel * atlas.width;
-        orient_image(&mut image.data, atlas.origin, atlas.height, width_in_bytes);
-
-        // Write out the atlas image.
-        zip_file.start_file(format!("{}.png", &atlas.atlas_name), options)
-                .map_err(|e| {
-                    let kind = ErrorKind::IoError;
-                    let multi_atlas_name = None;
-                    let atlas_name = None;
-
-                    TextureAtlas2DError::new(
-                        kind, multi_atlas_name, atlas_name, Some(Box::new(e))
-                    )
-                })?;
-        let png_writer = png::PNGEncoder::new(&mut zip_file);
-        let height = atlas.height as u32;
-        let width = atlas.width as u32;
-        let color = image::ColorType::Rgba8;
-        png_writer.encode(image.as_bytes(), width, height, color).map_err(|e| {
-            let kind = ErrorKind::IoError;
-            let multi_atlas_name = None;
-            let atlas_name = None;
-
-            TextureAtlas2DError::new(
-                kind, multi_atlas_name, atlas_name, Some(Box::new(e))
-            )
-        })?;
+        // Write out the atlas image, followed by its mip levels.
+        let image_file_name = format!("{}.png", &atlas.atlas_name);
+        write_atlas_image(&mut zip_file, options, &image_file_name, atlas, atlas.image())?;
+        for (i, mipmap) in atlas.mipmaps.iter().enumerate() {
+            let mip_file_name = mip_level_file_name(&atlas.atlas_name, i + 1);
+            write_atlas_image(&mut zip_file, options, &mip_file_name, atlas, mipmap)?;
+        }
     }
 
     zip_file.finish().map_err(|e| {
@@ -1157,10 +2348,13 @@ pub fn load_from_memory(buffer: &[u8]) -> Result<MultiTextureAtlas2DResult, Text
     from_reader(reader, "")
 }
 
-/// Load a texture atlas directly from a file.
+/// Load a texture atlas directly from a file. Files with the `*.atlas` extension are 
+/// also used by libGDX and Spine for their text atlas files, so files that look like 
+/// libGDX atlas files get loaded the same way `load_libgdx_atlas_file` loads them, 
+/// with a bottom left origin.
 pub fn load_file<P: AsRef<Path>>(path: P) -> Result<MultiTextureAtlas2DResult, TextureAtlas2DError> {
     let file_name = path.as_ref().file_name().map_or("", |s| s.to_str().unwrap_or(""));
-    let reader = File::open(&path).map_err(|e| {
+    let data = std::fs::read(&path).map_err(|e| {
         let kind = ErrorKind::CouldNotOpenTextureAtlas;
         let multi_atlas_name = Some(String::from(file_name));
         let atlas_name = None;
@@ -1169,7 +2363,12 @@ pub fn load_file<P: AsRef<Path>>(path: P) -> Result<MultiTextureAtlas2DResult, T
             kind, multi_atlas_name, atlas_name, Some(Box::new(e))
         )
     })?;
-    from_reader(reader, file_name)
+    if libgdx::is_atlas_file(&data) {
+        let directory = path.as_ref().parent().unwrap_or_else(|| Path::new(""));
+        return libgdx::from_atlas_file_data(&data, directory, file_name, Origin::BottomLeft);
+    }
+
+    from_reader(io::Cursor::new(data), file_name)
 }
 
 /// Write a texture atlas direct to a file.
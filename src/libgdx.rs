@@ -0,0 +1,477 @@
+use crate::{
+    AlphaMode,
+    AtlasEntry,
+    BoundingBoxPixelCoords,
+    ColorType,
+    ErrorKind,
+    MultiTextureAtlas2D,
+    MultiTextureAtlas2DResult,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+    TextureAtlas2DResult,
+};
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+
+/// A region in a libGDX atlas file, describing one texture.
+#[derive(Clone, Debug, Default)]
+struct LibGdxRegion {
+    /// The name of the region. Regions sharing a name are told apart by their index.
+    name: String,
+    /// The index of the region among the regions sharing its name, or -1 if the region
+    /// has no index.
+    index: i64,
+    /// The position of the top left corner of the region in the page image, with the
+    /// rows counted from the top of the image.
+    x: usize,
+    y: usize,
+    /// The dimensions of the region before it gets rotated.
+    width: usize,
+    height: usize,
+    /// The dimensions of the original image, if the region was trimmed.
+    original: Option<(usize, usize)>,
+    /// The position of the region inside the original image, measured from the bottom
+    /// left corner of the original image.
+    offset: (usize, usize),
+    /// The number of degrees the region is stored rotated by counterclockwise.
+    degrees: u32,
+}
+
+/// A page in a libGDX atlas file.
+#[derive(Clone, Debug, Default)]
+struct LibGdxPage {
+    /// The path of the page image, relative to the atlas file.
+    image: String,
+    /// The dimensions of the page image.
+    size: Option<(usize, usize)>,
+    /// Whether the color channels of the page image are premultiplied by alpha.
+    premultiplied: bool,
+    /// The regions in the page.
+    regions: Vec<LibGdxRegion>,
+}
+
+/// Split a line of a libGDX atlas file into the name of a field and its values. This
+/// returns `None` for lines naming a page or a region.
+fn parse_field(line: &str) -> Option<(&str, Vec<&str>)> {
+    let position = line.find(':')?;
+    let name = line[..position].trim();
+    let values = line[(position + 1)..].split(',').map(|value| value.trim()).collect();
+
+    Some((name, values))
+}
+
+/// Parse the numbers in the values of a field of a libGDX atlas file.
+fn parse_numbers<T: std::str::FromStr>(line_number: usize, name: &str, values: &[&str], count: usize) -> Result<Vec<T>, String> {
+    if values.len() < count {
+        return Err(format!("line {}: expected {} values for {}", line_number, count, name));
+    }
+
+    values[..count].iter()
+        .map(|value| value.parse::<T>().map_err(|_| format!("line {}: invalid value {} for {}", line_number, value, name)))
+        .collect()
+}
+
+/// Parse the rotation of a region in a libGDX atlas file. Older files write `true` or
+/// `false`, and newer files write the number of degrees.
+fn parse_degrees(line_number: usize, value: &str) -> Result<u32, String> {
+    match value {
+        "true" => Ok(90),
+        "false" => Ok(0),
+        _ => match value.parse::<u32>() {
+            Ok(degrees) if degrees % 90 == 0 && degrees < 360 => Ok(degrees),
+            _ => Err(format!("line {}: invalid rotation {}", line_number, value)),
+        },
+    }
+}
+
+/// Parse the contents of a libGDX atlas file, in either the format of libGDX 1.9.11 and
+/// older and Spine 3, or the format of newer versions of libGDX and Spine 4. A page
+/// starts with the name of its page image after a blank line, followed by the fields
+/// of the page. A region starts with the name of the region, followed by the fields of
+/// the region. Fields this crate has no use for are skipped.
+fn parse_atlas_file(text: &str) -> Result<Vec<LibGdxPage>, String> {
+    let mut pages: Vec<LibGdxPage> = vec![];
+    let mut in_page = false;
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            in_page = false;
+            continue;
+        }
+
+        let (name, values) = match parse_field(line) {
+            Some(field) => field,
+            None if in_page => {
+                // The page always exists once we are in one.
+                let page = pages.last_mut().unwrap();
+                page.regions.push(LibGdxRegion {
+                    name: String::from(line),
+                    index: -1,
+                    ..LibGdxRegion::default()
+                });
+                continue;
+            }
+            None => {
+                pages.push(LibGdxPage {
+                    image: String::from(line),
+                    ..LibGdxPage::default()
+                });
+                in_page = true;
+                continue;
+            }
+        };
+
+        let page = match pages.last_mut() {
+            Some(page) if in_page => page,
+            _ => return Err(format!("line {}: {} is outside of a page", line_number, name)),
+        };
+        match page.regions.last_mut() {
+            None => match name {
+                "size" => {
+                    let size = parse_numbers(line_number, name, &values, 2)?;
+                    page.size = Some((size[0], size[1]));
+                }
+                "pma" => page.premultiplied = values[0] == "true",
+                _ => {}
+            },
+            Some(region) => match name {
+                "rotate" => region.degrees = parse_degrees(line_number, values[0])?,
+                "xy" => {
+                    let xy = parse_numbers(line_number, name, &values, 2)?;
+                    region.x = xy[0];
+                    region.y = xy[1];
+                }
+                "size" => {
+                    let size = parse_numbers(line_number, name, &values, 2)?;
+                    region.width = size[0];
+                    region.height = size[1];
+                }
+                "bounds" => {
+                    let bounds = parse_numbers(line_number, name, &values, 4)?;
+                    region.x = bounds[0];
+                    region.y = bounds[1];
+                    region.width = bounds[2];
+                    region.height = bounds[3];
+                }
+                "orig" => {
+                    let original = parse_numbers(line_number, name, &values, 2)?;
+                    region.original = Some((original[0], original[1]));
+                }
+                "offset" => {
+                    let offset = parse_numbers(line_number, name, &values, 2)?;
+                    region.offset = (offset[0], offset[1]);
+                }
+                "offsets" => {
+                    let offsets = parse_numbers(line_number, name, &values, 4)?;
+                    region.offset = (offsets[0], offsets[1]);
+                    region.original = Some((offsets[2], offsets[3]));
+                }
+                "index" => region.index = parse_numbers(line_number, name, &values, 1)?[0],
+                _ => {}
+            },
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Determine whether the contents of a file look like a libGDX atlas file: the first
+/// line names a page image, and the next line is a field of the page.
+pub(crate) fn is_atlas_file(data: &[u8]) -> bool {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return false,
+    };
+    let mut lines = text.lines().map(|line| line.trim()).filter(|line| !line.is_empty());
+    match (lines.next(), lines.next()) {
+        (Some(page_line), Some(field_line)) => parse_field(page_line).is_none() && parse_field(field_line).is_some(),
+        _ => false,
+    }
+}
+
+/// Construct an error for a page of a libGDX atlas file.
+fn error(
+    kind: ErrorKind,
+    multi_atlas_name: &str, page_name: &str,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>) -> TextureAtlas2DError {
+
+    TextureAtlas2DError::new(kind, Some(String::from(multi_atlas_name)), Some(String::from(page_name)), source)
+}
+
+/// Construct a page of a texture atlas from a parsed page of a libGDX atlas file and the
+/// contents of its page image file.
+fn page_from_libgdx(
+    page: LibGdxPage,
+    image_data: &[u8],
+    multi_atlas_name: &str, page_name: &str, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+
+    // We orient the page image for the origin only after turning the rotated regions
+    // around, so the rows are ordered from the top of the image until then.
+    let mut tex_image = crate::decode_atlas_image(image_data, Origin::TopLeft).map_err(|kind| {
+        error(kind, multi_atlas_name, page_name, None)
+    })?;
+    let width = tex_image.width;
+    let height = tex_image.height;
+    if let Some(size) = page.size {
+        if size != (width, height) {
+            return Err(error(ErrorKind::InvalidImageData, multi_atlas_name, page_name, None));
+        }
+    }
+
+    // libGDX writes identical textures out once, and repeats the region for each of
+    // their names, so regions covering the same part of the page image become aliases.
+    let mut names = HashSet::new();
+    let mut atlas_entries: Vec<(usize, AtlasEntry)> = vec![];
+    let mut positions: HashMap<_, usize> = HashMap::new();
+    for region in page.regions.into_iter() {
+        let name = if region.index >= 0 {
+            format!("{}_{}", region.name, region.index)
+        } else {
+            region.name
+        };
+        if !names.insert(name.clone()) {
+            return Err(error(ErrorKind::DuplicateTextureName, multi_atlas_name, page_name, None));
+        }
+
+        let quarter_turn = region.degrees == 90 || region.degrees == 270;
+        let (stored_width, stored_height) = if quarter_turn {
+            (region.height, region.width)
+        } else {
+            (region.width, region.height)
+        };
+        if region.x + stored_width > width || region.y + stored_height > height {
+            return Err(error(ErrorKind::TextureDoesNotFitInAtlas, multi_atlas_name, page_name, None));
+        }
+        let (original_width, original_height) = region.original.unwrap_or((region.width, region.height));
+        let offset_y = original_height.checked_sub(region.offset.1 + region.height).ok_or_else(|| {
+            error(ErrorKind::CouldNotLoadCoordinateCharts, multi_atlas_name, page_name, None)
+        })?;
+        let source = SourceImagePixelCoords {
+            offset: OffsetPixelCoords::new(region.offset.0, offset_y),
+            width: original_width,
+            height: original_height,
+        };
+
+        let key = (region.x, region.y, region.width, region.height, region.degrees, region.original, region.offset);
+        match positions.get(&key) {
+            Some(position) => atlas_entries[*position].1.aliases.push(name),
+            None => {
+                // libGDX stores rotated regions turned counterclockwise, and this crate
+                // stores them turned clockwise, which differs by half a turn. Regions
+                // stored upside down get turned upright.
+                if region.degrees == 90 || region.degrees == 180 {
+                    let turned = tex_image.crop(region.x, region.y, stored_width, stored_height).rotate_half_turn();
+                    tex_image.copy_from(&turned, region.x, region.y);
+                }
+                let bounding_box = BoundingBoxPixelCoords::from_image_rect(
+                    origin, height, region.x, region.y, stored_width, stored_height
+                );
+                let mut atlas_entry = AtlasEntry::new(name, bounding_box, source, width, height);
+                atlas_entry.rotated = quarter_turn;
+                positions.insert(key, atlas_entries.len());
+                atlas_entries.push((atlas_entries.len(), atlas_entry));
+            }
+        }
+    }
+
+    let width_in_bytes = width * tex_image.bytes_per_pixel;
+    crate::orient_image(&mut tex_image.data, origin, height, width_in_bytes);
+    let mut atlas = TextureAtlas2D::from_atlas_entries(
+        width, height, tex_image.color_type, origin, atlas_entries, String::from(page_name), tex_image.data
+    );
+    atlas.alpha_mode = if page.premultiplied { AlphaMode::Premultiplied } else { AlphaMode::Straight };
+    atlas.color_space = crate::png_chunks::color_space(image_data).unwrap_or_default();
+
+    Ok(TextureAtlas2DResult {
+        atlas: atlas,
+        warnings: crate::dimension_warnings(width, height),
+    })
+}
+
+/// Load a multi texture atlas from the contents of a libGDX atlas file, reading the page
+/// images from the given directory.
+pub(crate) fn from_atlas_file_data(
+    data: &[u8],
+    directory: &Path,
+    multi_atlas_name: &str, origin: Origin) -> Result<MultiTextureAtlas2DResult, TextureAtlas2DError> {
+
+    let text = std::str::from_utf8(data).map_err(|e| {
+        TextureAtlas2DError::new(ErrorKind::CouldNotLoadCoordinateCharts, Some(String::from(multi_atlas_name)), None, Some(Box::new(e)))
+    })?;
+    let libgdx_pages = parse_atlas_file(text).map_err(|message| {
+        TextureAtlas2DError::new(ErrorKind::CouldNotLoadCoordinateCharts, Some(String::from(multi_atlas_name)), None, Some(message.into()))
+    })?;
+
+    let mut pages = vec![];
+    let mut warnings = vec![];
+    for libgdx_page in libgdx_pages.into_iter() {
+        let image_path = directory.join(&libgdx_page.image);
+        let page_name = image_path.file_stem().map_or("", |s| s.to_str().unwrap_or("")).to_string();
+        let image_data = fs::read(&image_path).map_err(|e| {
+            error(ErrorKind::MissingImageBuffer, multi_atlas_name, &page_name, Some(Box::new(e)))
+        })?;
+        let result = page_from_libgdx(libgdx_page, &image_data, multi_atlas_name, &page_name, origin)?;
+        pages.push(result.atlas);
+        warnings.push(result.warnings);
+    }
+
+    Ok(MultiTextureAtlas2DResult {
+        multi_atlas: MultiTextureAtlas2D::new(pages),
+        warnings: warnings,
+    })
+}
+
+/// Load a multi texture atlas from a libGDX or Spine text atlas file. Each page of the
+/// atlas file becomes a page named after its page image, and the page images are read
+/// from the paths the atlas file gives relative to the atlas file. The textures are
+/// indexed in the order the atlas file lists them, and regions with an index are named
+/// by joining the name and the index of the region with an underscore, the way the
+/// libGDX texture packer splits them apart. The pixel coordinates are converted from the
+/// top left corner of the page image to the given origin, and regions covering the same
+/// rectangle of a page image become aliases of a single texture. This crate has no use
+/// for the nine patch splits and paddings of regions, so they are skipped.
+pub fn load_libgdx_atlas_file<P: AsRef<Path>>(path: P, origin: Origin) -> Result<MultiTextureAtlas2DResult, TextureAtlas2DError> {
+    let path = path.as_ref();
+    let multi_atlas_name = path.file_name().map_or("", |s| s.to_str().unwrap_or(""));
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let data = fs::read(path).map_err(|e| {
+        TextureAtlas2DError::new(ErrorKind::CouldNotOpenTextureAtlas, Some(String::from(multi_atlas_name)), None, Some(Box::new(e)))
+    })?;
+
+    from_atlas_file_data(&data, directory, multi_atlas_name, origin)
+}
+
+/// Split the index off of the end of a texture name, the way the libGDX texture packer
+/// does. A name ending in an underscore followed by a number without leading zeros is
+/// split into the name before the underscore and the number.
+fn split_index(name: &str) -> (&str, i64) {
+    if let Some(position) = name.rfind('_') {
+        let (prefix, digits) = (&name[..position], &name[(position + 1)..]);
+        let has_leading_zero = digits.len() > 1 && digits.starts_with('0');
+        if !prefix.is_empty() && !has_leading_zero && digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            if let Ok(index) = digits.parse::<i32>() {
+                return (prefix, index as i64);
+            }
+        }
+    }
+
+    (name, -1)
+}
+
+/// Construct the region of a libGDX atlas file for a texture name. The region is
+/// rotated if the texture is, and it is given by the rectangle the texture covers in the
+/// page image, with the rows counted from the top of the image.
+fn region_for_texture(
+    name: &str,
+    rotated: bool,
+    (x, y, width, height): (usize, usize, usize, usize),
+    source: SourceImagePixelCoords) -> LibGdxRegion {
+
+    let (name, index) = split_index(name);
+    let offset_y = source.height.saturating_sub(source.offset.v + height);
+
+    LibGdxRegion {
+        name: String::from(name),
+        index: index,
+        x: x,
+        y: y,
+        width: width,
+        height: height,
+        original: Some((source.width, source.height)),
+        offset: (source.offset.u, offset_y),
+        degrees: if rotated { 90 } else { 0 },
+    }
+}
+
+/// Write out a region of a libGDX atlas file, in the format every version of libGDX
+/// and Spine reads.
+fn write_region(text: &mut String, region: &LibGdxRegion) {
+    // The original dimensions are always known for the regions we write out.
+    let (original_width, original_height) = region.original.unwrap_or((region.width, region.height));
+    // Writing to a string never fails.
+    writeln!(text, "{}", region.name).unwrap();
+    writeln!(text, "  rotate: {}", region.degrees == 90).unwrap();
+    writeln!(text, "  xy: {}, {}", region.x, region.y).unwrap();
+    writeln!(text, "  size: {}, {}", region.width, region.height).unwrap();
+    writeln!(text, "  orig: {}, {}", original_width, original_height).unwrap();
+    writeln!(text, "  offset: {}, {}", region.offset.0, region.offset.1).unwrap();
+    writeln!(text, "  index: {}", region.index).unwrap();
+}
+
+/// Write every page of a multi texture atlas out as a libGDX atlas file at the given
+/// path, with a PNG page image named after each page next to it, for libGDX and Spine
+/// to read. The pixel coordinates in the atlas file are measured from the top left
+/// corner of the page image whatever the origin of the atlas is, and every alias of a
+/// texture gets a region of its own. Texture names ending in an underscore followed by
+/// a number are split into a region name and an index. The page images are written out
+/// as RGBA images with 8 bits per channel, and pages with premultiplied alpha are marked
+/// with the `pma` field that newer versions of libGDX and Spine read.
+pub fn export_libgdx_atlas<P: AsRef<Path>>(multi_atlas: &MultiTextureAtlas2D, path: P) -> Result<(), TextureAtlas2DError> {
+    let path = path.as_ref();
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    if !directory.as_os_str().is_empty() {
+        fs::create_dir_all(directory).map_err(|e| {
+            TextureAtlas2DError::new(ErrorKind::IoError, None, None, Some(Box::new(e)))
+        })?;
+    }
+
+    let mut text = String::new();
+    for atlas in multi_atlas.pages() {
+        let image_file_name = format!("{}.png", atlas.atlas_name);
+        writeln!(text).unwrap();
+        writeln!(text, "{}", image_file_name).unwrap();
+        writeln!(text, "size: {}, {}", atlas.width, atlas.height).unwrap();
+        writeln!(text, "format: RGBA8888").unwrap();
+        writeln!(text, "filter: Nearest, Nearest").unwrap();
+        writeln!(text, "repeat: none").unwrap();
+        if atlas.alpha_mode == AlphaMode::Premultiplied {
+            writeln!(text, "pma: true").unwrap();
+        }
+
+        let mut page = atlas.convert_to(ColorType::Rgba8);
+        let mut indices = atlas.indices();
+        indices.sort_unstable();
+        for index in indices {
+            let atlas_entry = &atlas.bounding_boxes[&index];
+            let (x, y, stored_width, stored_height) = atlas_entry.bounding_box_pix.to_image_rect(atlas.origin, atlas.height);
+            let (width, height) = if atlas_entry.rotated {
+                // Turning the region around half a turn gives the counterclockwise
+                // rotation libGDX expects. Half a turn looks the same whichever way the
+                // rows of the atlas image are ordered.
+                let storage_y = match atlas.origin {
+                    Origin::TopLeft => y,
+                    Origin::BottomLeft => atlas.height - y - stored_height,
+                };
+                let turned = page.data.crop(x, storage_y, stored_width, stored_height).rotate_half_turn();
+                page.data.copy_from(&turned, x, storage_y);
+                (stored_height, stored_width)
+            } else {
+                (stored_width, stored_height)
+            };
+            let rect = (x, y, width, height);
+            for name in std::iter::once(&atlas_entry.name).chain(atlas_entry.aliases.iter()) {
+                write_region(&mut text, &region_for_texture(name, atlas_entry.rotated, rect, atlas_entry.source));
+            }
+        }
+
+        let image_data = crate::export::encode_sheet_image(&page, atlas.alpha_mode)?;
+        fs::write(directory.join(&image_file_name), image_data).map_err(|e| {
+            error(ErrorKind::IoError, "", &atlas.atlas_name, Some(Box::new(e)))
+        })?;
+    }
+
+    fs::write(path, text).map_err(|e| {
+        TextureAtlas2DError::new(ErrorKind::IoError, None, None, Some(Box::new(e)))
+    })
+}
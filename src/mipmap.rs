@@ -0,0 +1,246 @@
+use crate::{
+    AlphaMode,
+    ColorSpace,
+    Origin,
+    TextureAtlas2D,
+    TextureImage2D,
+};
+
+
+/// Get the dimensions of a mip level of an atlas image with the given dimensions. Each
+/// mip level is half the size of the one before it, rounded down, and at least one pixel
+/// on a side.
+pub(crate) fn level_dimensions(width: usize, height: usize, level: usize) -> (usize, usize) {
+    let shift = |size: usize| if level >= usize::BITS as usize { 1 } else { usize::max(size >> level, 1) };
+
+    (shift(width), shift(height))
+}
+
+/// Get the number of mip levels in a full mipmap chain for an atlas image with the
+/// given dimensions, including the atlas image itself.
+pub(crate) fn level_count(width: usize, height: usize) -> usize {
+    let size = usize::max(usize::max(width, height), 1);
+
+    (usize::BITS - size.leading_zeros()) as usize
+}
+
+/// A rectangle of pixels in a mip level, with its position given in the order the rows
+/// of the atlas image are stored.
+#[derive(Copy, Clone, Debug)]
+struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    /// Get the position in the rectangle nearest to a position in the mip level.
+    fn clamp(&self, x: usize, y: usize) -> (usize, usize) {
+        let x = usize::min(usize::max(x, self.x), self.x + self.width - 1);
+        let y = usize::min(usize::max(y, self.y), self.y + self.height - 1);
+
+        (x - self.x, y - self.y)
+    }
+}
+
+/// The pixels of a single texture in one mip level. The samples are linear and
+/// premultiplied by alpha, so that averaging them gives the correct colors.
+struct TextureLevel {
+    rect: Rect,
+    samples: Vec<f64>,
+}
+
+/// The layout and encoding of the samples of an atlas image.
+struct SampleFormat {
+    channel_count: usize,
+    bytes_per_sample: usize,
+    has_alpha_channel: bool,
+    max: f64,
+    alpha_mode: AlphaMode,
+    color_space: ColorSpace,
+}
+
+impl SampleFormat {
+    fn new(atlas: &TextureAtlas2D) -> SampleFormat {
+        let channel_count = atlas.color_type.channel_count();
+        let bytes_per_sample = atlas.color_type.bytes_per_pixel() / channel_count;
+
+        SampleFormat {
+            channel_count: channel_count,
+            bytes_per_sample: bytes_per_sample,
+            has_alpha_channel: atlas.color_type.has_alpha_channel(),
+            max: if bytes_per_sample == 2 { 65535.0 } else { 255.0 },
+            alpha_mode: atlas.alpha_mode,
+            color_space: atlas.color_space,
+        }
+    }
+
+    /// Get the number of color channels in each pixel, not counting alpha.
+    fn color_channel_count(&self) -> usize {
+        if self.has_alpha_channel { self.channel_count - 1 } else { self.channel_count }
+    }
+
+    /// Decode a pixel into linear samples premultiplied by alpha.
+    fn decode(&self, pixel: &[u8], samples: &mut Vec<f64>) {
+        let sample = |i: usize| -> f64 {
+            let value = if self.bytes_per_sample == 2 {
+                u16::from_ne_bytes([pixel[2 * i], pixel[2 * i + 1]]) as f64
+            } else {
+                pixel[i] as f64
+            };
+
+            value / self.max
+        };
+        let alpha = if self.has_alpha_channel { sample(self.channel_count - 1) } else { 1.0 };
+        for i in 0..self.color_channel_count() {
+            let mut value = sample(i);
+            if self.alpha_mode == AlphaMode::Premultiplied {
+                value = if alpha > 0.0 { f64::min(value / alpha, 1.0) } else { 0.0 };
+            }
+            if self.color_space == ColorSpace::Srgb {
+                value = crate::srgb_to_linear(value);
+            }
+            samples.push(value * alpha);
+        }
+        if self.has_alpha_channel {
+            samples.push(alpha);
+        }
+    }
+
+    /// Encode linear samples premultiplied by alpha into a pixel.
+    fn encode(&self, samples: &[f64], pixel: &mut [u8]) {
+        let alpha = if self.has_alpha_channel { samples[self.channel_count - 1] } else { 1.0 };
+        let mut set_sample = |i: usize, value: f64| {
+            let value = (value.clamp(0.0, 1.0) * self.max).round();
+            if self.bytes_per_sample == 2 {
+                pixel[(2 * i)..(2 * i + 2)].copy_from_slice(&(value as u16).to_ne_bytes());
+            } else {
+                pixel[i] = value as u8;
+            }
+        };
+        for (i, sample) in samples.iter().enumerate().take(self.color_channel_count()) {
+            let mut value = if alpha > 0.0 { f64::min(sample / alpha, 1.0) } else { 0.0 };
+            if self.color_space == ColorSpace::Srgb {
+                value = crate::linear_to_srgb(value);
+            }
+            if self.alpha_mode == AlphaMode::Premultiplied {
+                value *= alpha;
+            }
+            set_sample(i, value);
+        }
+        if self.has_alpha_channel {
+            set_sample(self.channel_count - 1, alpha);
+        }
+    }
+}
+
+/// Halve a texture level into the next mip level. Each pixel averages the two by two
+/// block of pixels below it, with positions outside the texture clamped to its edges, so
+/// that the pixels of neighboring textures never mix.
+fn downsample(texture: &TextureLevel, width: usize, height: usize, channel_count: usize) -> TextureLevel {
+    let source = texture.rect;
+    let x = usize::min(source.x / 2, width - 1);
+    let y = usize::min(source.y / 2, height - 1);
+    let rect = Rect {
+        x: x,
+        y: y,
+        width: usize::max(usize::min((source.x + source.width).div_ceil(2), width), x + 1) - x,
+        height: usize::max(usize::min((source.y + source.height).div_ceil(2), height), y + 1) - y,
+    };
+    let mut samples = Vec::with_capacity(rect.width * rect.height * channel_count);
+    for row in rect.y..(rect.y + rect.height) {
+        for column in rect.x..(rect.x + rect.width) {
+            let mut sum = vec![0.0; channel_count];
+            for (source_column, source_row) in [(0, 0), (1, 0), (0, 1), (1, 1)].iter() {
+                let (u, v) = source.clamp(2 * column + source_column, 2 * row + source_row);
+                let offset = (v * source.width + u) * channel_count;
+                for (total, sample) in sum.iter_mut().zip(&texture.samples[offset..(offset + channel_count)]) {
+                    *total += sample;
+                }
+            }
+            samples.extend(sum.iter().map(|total| total / 4.0));
+        }
+    }
+
+    TextureLevel {
+        rect: rect,
+        samples: samples,
+    }
+}
+
+/// Write the pixels of a texture level into a mip level, along with a gutter around it
+/// of the given width that repeats the edges of the texture.
+fn write_texture_level(format: &SampleFormat, image: &mut TextureImage2D, texture: &TextureLevel, gutter: usize) {
+    let rect = texture.rect;
+    let bytes_per_pixel = image.bytes_per_pixel;
+    let channel_count = format.channel_count;
+    let rows = rect.y.saturating_sub(gutter)..usize::min(rect.y + rect.height + gutter, image.height);
+    for row in rows {
+        let columns = rect.x.saturating_sub(gutter)..usize::min(rect.x + rect.width + gutter, image.width);
+        for column in columns {
+            let (u, v) = rect.clamp(column, row);
+            let offset = (v * rect.width + u) * channel_count;
+            let pixel_offset = (row * image.width + column) * bytes_per_pixel;
+            format.encode(
+                &texture.samples[offset..(offset + channel_count)],
+                &mut image.data[pixel_offset..(pixel_offset + bytes_per_pixel)]
+            );
+        }
+    }
+}
+
+/// Generate every mip level after the first of a texture atlas, filtering each texture
+/// separately. The pixels between textures are transparent black, except for the
+/// gutter of the given width around each texture.
+pub(crate) fn generate_mipmaps(atlas: &TextureAtlas2D, gutter: usize) -> Vec<TextureImage2D> {
+    let format = SampleFormat::new(atlas);
+    let bytes_per_pixel = atlas.bytes_per_pixel;
+    let mut indices: Vec<&usize> = atlas.bounding_boxes.keys().collect();
+    indices.sort();
+    let mut textures = vec![];
+    for index in indices {
+        let bounding_box = atlas.bounding_boxes[index].bounding_box_pix;
+        if bounding_box.width == 0 || bounding_box.height == 0 {
+            continue;
+        }
+        let (x, y, width, height) = bounding_box.to_image_rect(atlas.origin, atlas.height);
+        let y = match atlas.origin {
+            Origin::TopLeft => y,
+            Origin::BottomLeft => atlas.height - y - height,
+        };
+        let mut samples = Vec::with_capacity(width * height * format.channel_count);
+        for row in y..(y + height) {
+            let start = (row * atlas.width + x) * bytes_per_pixel;
+            for pixel in atlas.data.data[start..(start + width * bytes_per_pixel)].chunks_exact(bytes_per_pixel) {
+                format.decode(pixel, &mut samples);
+            }
+        }
+        let rect = Rect { x: x, y: y, width: width, height: height };
+        textures.push(TextureLevel { rect: rect, samples: samples });
+    }
+
+    let mut levels = vec![];
+    for level in 1..level_count(atlas.width, atlas.height) {
+        let (width, height) = level_dimensions(atlas.width, atlas.height, level);
+        textures = textures.iter()
+            .map(|texture| downsample(texture, width, height, format.channel_count))
+            .collect();
+        let mut image = TextureImage2D::new(
+            width, height, atlas.color_type, vec![0; width * height * bytes_per_pixel]
+        );
+        // Neighboring textures can share pixels in the smaller mip levels, so we write
+        // the gutters first to keep them from covering the textures themselves.
+        if gutter > 0 {
+            for texture in textures.iter() {
+                write_texture_level(&format, &mut image, texture, gutter);
+            }
+        }
+        for texture in textures.iter() {
+            write_texture_level(&format, &mut image, texture, 0);
+        }
+        levels.push(image);
+    }
+
+    levels
+}
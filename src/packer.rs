@@ -0,0 +1,948 @@
+/// The position and dimensions of a rectangle placed inside a packing region. The
+/// position of the rectangle is the top left corner of the rectangle, with the x-axis
+/// pointing right, and the y-axis pointing down.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PackedRect {
+    /// The horizontal position of the top left corner of the rectangle.
+    pub x: usize,
+    /// The vertical position of the top left corner of the rectangle.
+    pub y: usize,
+    /// The width of the rectangle.
+    pub width: usize,
+    /// The height of the rectangle.
+    pub height: usize,
+    /// Whether the packer rotated the rectangle by 90 degrees clockwise to place it. The 
+    /// width and height of a rotated rectangle are the width and height after rotation.
+    pub rotated: bool,
+}
+
+impl PackedRect {
+    #[inline]
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> PackedRect {
+        PackedRect {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+            rotated: false,
+        }
+    }
+
+    /// Get the area of the rectangle.
+    #[inline]
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+/// A rectangle packer places rectangles inside a fixed size packing region, one at 
+/// a time, without overlapping the rectangles already placed. The position of each 
+/// rectangle is measured from the top left corner of the packing region.
+pub trait Packer {
+    /// Get the width of the packing region.
+    fn width(&self) -> usize;
+
+    /// Get the height of the packing region.
+    fn height(&self) -> usize;
+
+    /// Place a rectangle with the given dimensions inside the packing region. This returns
+    /// `None` when the rectangle does not fit in the remaining free space.
+    fn insert(&mut self, width: usize, height: usize) -> Option<PackedRect>;
+
+    /// Place a rectangle with the given dimensions inside the packing region, allowing the 
+    /// packer to rotate the rectangle by 90 degrees when that gives a better fit. This 
+    /// returns `None` when the rectangle does not fit in either orientation.
+    fn insert_rotatable(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert(width, height).or_else(|| {
+            self.insert(height, width).map(|rect| PackedRect { rotated: true, ..rect })
+        })
+    }
+
+    /// Get the fraction of the packing region covered by rectangles.
+    fn occupancy(&self) -> f32;
+}
+
+/// Get the orientations a rectangle can be placed in. The rotated orientation is omitted 
+/// for squares since it is the same as the upright one.
+fn orientations(width: usize, height: usize, allow_rotation: bool) -> Vec<(usize, usize, bool)> {
+    if allow_rotation && width != height {
+        vec![(width, height, false), (height, width, true)]
+    } else {
+        vec![(width, height, false)]
+    }
+}
+
+/// A shelf packer places rectangles from left to right in horizontal rows called shelves.
+/// When a rectangle does not fit on the current shelf, the packer opens a new shelf on top
+/// of the tallest rectangle on the current one. Shelf packing is fast, and works well when
+/// the rectangles are sorted by decreasing height.
+#[derive(Clone, Debug)]
+pub struct ShelfPacker {
+    /// The width of the packing region.
+    width: usize,
+    /// The height of the packing region.
+    height: usize,
+    /// The vertical position of the current shelf.
+    shelf_y: usize,
+    /// The height of the tallest rectangle on the current shelf.
+    shelf_height: usize,
+    /// The horizontal position of the next free spot on the current shelf.
+    cursor_x: usize,
+    /// The total area of the rectangles placed in the packing region.
+    used_area: usize,
+}
+
+impl ShelfPacker {
+    /// Construct a new shelf packer for an empty packing region.
+    pub fn new(width: usize, height: usize) -> ShelfPacker {
+        ShelfPacker {
+            width: width,
+            height: height,
+            shelf_y: 0,
+            shelf_height: 0,
+            cursor_x: 0,
+            used_area: 0,
+        }
+    }
+
+    /// Find the position of a rectangle on the current shelf. This leaves the packer 
+    /// unchanged, so a rectangle that does not fit does not close the current shelf.
+    fn find_position_on_current_shelf(&self, width: usize, height: usize) -> Option<PackedRect> {
+        if self.cursor_x + width > self.width || self.shelf_y + height > self.height {
+            return None;
+        }
+
+        Some(PackedRect::new(self.cursor_x, self.shelf_y, width, height))
+    }
+
+    /// Find the position of a rectangle on a new shelf on top of the current one. This 
+    /// leaves the packer unchanged.
+    fn find_position_on_new_shelf(&self, width: usize, height: usize) -> Option<PackedRect> {
+        let y = self.shelf_y + self.shelf_height;
+        if width > self.width || y + height > self.height {
+            return None;
+        }
+
+        Some(PackedRect::new(0, y, width, height))
+    }
+
+    /// Place a rectangle found on the current shelf or on a new shelf, opening the new 
+    /// shelf when the rectangle does not sit on the current one.
+    fn place_rect(&mut self, rect: PackedRect) {
+        if rect.y != self.shelf_y {
+            self.shelf_y = rect.y;
+            self.shelf_height = 0;
+        }
+        self.cursor_x = rect.x + rect.width;
+        self.shelf_height = usize::max(self.shelf_height, rect.height);
+        self.used_area += rect.area();
+    }
+
+    /// Place a rectangle in the first of the allowed orientations that fits, trying the 
+    /// upright orientation first. Every orientation gets tried on the current shelf before
+    /// the packer opens a new shelf.
+    fn insert_oriented(&mut self, width: usize, height: usize, allow_rotation: bool) -> Option<PackedRect> {
+        let orientations = orientations(width, height, allow_rotation);
+        let find_oriented = |find_position: fn(&ShelfPacker, usize, usize) -> Option<PackedRect>| {
+            orientations.iter().find_map(|&(width, height, rotated)| {
+                find_position(self, width, height).map(|rect| PackedRect { rotated: rotated, ..rect })
+            })
+        };
+        let rect = find_oriented(ShelfPacker::find_position_on_current_shelf)
+            .or_else(|| find_oriented(ShelfPacker::find_position_on_new_shelf))?;
+        self.place_rect(rect);
+
+        Some(rect)
+    }
+}
+
+impl Packer for ShelfPacker {
+    #[inline]
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn insert(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, false)
+    }
+
+    fn insert_rotatable(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, true)
+    }
+
+    fn occupancy(&self) -> f32 {
+        if self.width * self.height == 0 {
+            return 0.0;
+        }
+
+        self.used_area as f32 / (self.width * self.height) as f32
+    }
+}
+
+/// The rule a maximal rectangles packer uses to choose where to place a rectangle among
+/// the free rectangles in the packing region.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MaxRectsHeuristic {
+    /// Place the rectangle in the free rectangle with the smallest leftover space along 
+    /// the shorter side.
+    BestShortSideFit,
+    /// Place the rectangle in the free rectangle with the smallest leftover space along 
+    /// the longer side.
+    BestLongSideFit,
+    /// Place the rectangle in the smallest free rectangle it fits into.
+    BestAreaFit,
+    /// Place the rectangle as close to the top left corner of the packing region as
+    /// possible, in the style of Tetris.
+    BottomLeft,
+    /// Place the rectangle where it touches the most edges of the packing region and the 
+    /// rectangles already placed.
+    ContactPoint,
+}
+
+/// A maximal rectangles packer keeps track of every maximal free rectangle in the 
+/// packing region, and places each new rectangle into the free rectangle that scores 
+/// best under its heuristic. It produces the densest packings of the packers in this
+/// module, at the cost of more work per rectangle.
+#[derive(Clone, Debug)]
+pub struct MaxRectsPacker {
+    /// The width of the packing region.
+    width: usize,
+    /// The height of the packing region.
+    height: usize,
+    /// The rule for choosing where to place each rectangle.
+    heuristic: MaxRectsHeuristic,
+    /// The rectangles placed in the packing region.
+    used_rects: Vec<PackedRect>,
+    /// The maximal free rectangles in the packing region.
+    free_rects: Vec<PackedRect>,
+}
+
+impl MaxRectsPacker {
+    /// Construct a new maximal rectangles packer for an empty packing region.
+    pub fn new(width: usize, height: usize, heuristic: MaxRectsHeuristic) -> MaxRectsPacker {
+        MaxRectsPacker {
+            width: width,
+            height: height,
+            heuristic: heuristic,
+            used_rects: vec![],
+            free_rects: vec![PackedRect::new(0, 0, width, height)],
+        }
+    }
+
+    /// Get the heuristic the packer uses for placing rectangles.
+    #[inline]
+    pub fn heuristic(&self) -> MaxRectsHeuristic {
+        self.heuristic
+    }
+
+    /// Mark a rectangle of the packing region as used, so the packer places no other 
+    /// rectangles on top of it. This lets a packer resume packing a region that already 
+    /// contains rectangles.
+    pub fn occupy(&mut self, rect: PackedRect) {
+        self.place_rect(rect);
+    }
+
+    /// Free a rectangle placed in the packing region so the packer can reuse its space. 
+    /// This returns `false` if the rectangle was never placed.
+    pub fn remove(&mut self, rect: &PackedRect) -> bool {
+        let position = self.used_rects.iter().position(|used_rect| {
+            used_rect.x == rect.x && used_rect.y == rect.y && 
+            used_rect.width == rect.width && used_rect.height == rect.height
+        });
+        match position {
+            Some(position) => {
+                self.used_rects.swap_remove(position);
+                // Merging the freed rectangle back into the free rectangles can miss free
+                // rectangles spanning it, so we rebuild them from the remaining rectangles.
+                let used_rects = std::mem::take(&mut self.used_rects);
+                self.free_rects = vec![PackedRect::new(0, 0, self.width, self.height)];
+                for used_rect in used_rects {
+                    self.place_rect(used_rect);
+                }
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Find the best position for a rectangle among the free rectangles. Lower scores 
+    /// are better, with the second score breaking ties.
+    fn find_position(&self, width: usize, height: usize, allow_rotation: bool) -> Option<PackedRect> {
+        let mut best: Option<(PackedRect, (isize, isize))> = None;
+        for free_rect in self.free_rects.iter() {
+            for (width, height, rotated) in orientations(width, height, allow_rotation) {
+                if free_rect.width < width || free_rect.height < height {
+                    continue;
+                }
+
+                let rect = PackedRect { rotated: rotated, ..PackedRect::new(free_rect.x, free_rect.y, width, height) };
+                let score = self.score(free_rect, &rect);
+                let is_better = match best {
+                    Some((_, best_score)) => score < best_score,
+                    None => true,
+                };
+                if is_better {
+                    best = Some((rect, score));
+                }
+            }
+        }
+
+        best.map(|(rect, _)| rect)
+    }
+
+    /// Place a rectangle in the best position found among the allowed orientations.
+    fn insert_oriented(&mut self, width: usize, height: usize, allow_rotation: bool) -> Option<PackedRect> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let rect = self.find_position(width, height, allow_rotation)?;
+        self.place_rect(rect);
+
+        Some(rect)
+    }
+
+    /// Score the placement of a rectangle in the top left corner of a free rectangle.
+    fn score(&self, free_rect: &PackedRect, rect: &PackedRect) -> (isize, isize) {
+        let leftover_horizontal = (free_rect.width - rect.width) as isize;
+        let leftover_vertical = (free_rect.height - rect.height) as isize;
+        let short_side = isize::min(leftover_horizontal, leftover_vertical);
+        let long_side = isize::max(leftover_horizontal, leftover_vertical);
+        match self.heuristic {
+            MaxRectsHeuristic::BestShortSideFit => (short_side, long_side),
+            MaxRectsHeuristic::BestLongSideFit => (long_side, short_side),
+            MaxRectsHeuristic::BestAreaFit => {
+                let leftover_area = (free_rect.area() - rect.area()) as isize;
+                (leftover_area, short_side)
+            }
+            MaxRectsHeuristic::BottomLeft => ((rect.y + rect.height) as isize, rect.x as isize),
+            MaxRectsHeuristic::ContactPoint => (-(self.contact_point_score(rect) as isize), 0),
+        }
+    }
+
+    /// Compute the total length of the edges of a rectangle that touch either the 
+    /// boundary of the packing region or a rectangle already placed.
+    fn contact_point_score(&self, rect: &PackedRect) -> usize {
+        fn common_interval_length(start1: usize, end1: usize, start2: usize, end2: usize) -> usize {
+            if end1 < start2 || end2 < start1 {
+                0
+            } else {
+                usize::min(end1, end2) - usize::max(start1, start2)
+            }
+        }
+
+        let mut score = 0;
+        if rect.x == 0 || rect.x + rect.width == self.width {
+            score += rect.height;
+        }
+        if rect.y == 0 || rect.y + rect.height == self.height {
+            score += rect.width;
+        }
+        for used in self.used_rects.iter() {
+            if used.x == rect.x + rect.width || used.x + used.width == rect.x {
+                score += common_interval_length(used.y, used.y + used.height, rect.y, rect.y + rect.height);
+            }
+            if used.y == rect.y + rect.height || used.y + used.height == rect.y {
+                score += common_interval_length(used.x, used.x + used.width, rect.x, rect.x + rect.width);
+            }
+        }
+
+        score
+    }
+
+    /// Mark the space occupied by a rectangle as used, and update the free rectangles.
+    fn place_rect(&mut self, rect: PackedRect) {
+        let mut new_free_rects = vec![];
+        let mut i = 0;
+        while i < self.free_rects.len() {
+            if split_free_rect(&self.free_rects[i], &rect, &mut new_free_rects) {
+                self.free_rects.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        self.free_rects.extend(new_free_rects);
+        prune_free_rects(&mut self.free_rects);
+        self.used_rects.push(rect);
+    }
+}
+
+impl Packer for MaxRectsPacker {
+    #[inline]
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn insert(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, false)
+    }
+
+    fn insert_rotatable(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, true)
+    }
+
+    fn occupancy(&self) -> f32 {
+        if self.width * self.height == 0 {
+            return 0.0;
+        }
+
+        let used_area: usize = self.used_rects.iter().map(|rect| rect.area()).sum();
+
+        used_area as f32 / (self.width * self.height) as f32
+    }
+}
+
+/// Determine whether two rectangles overlap.
+#[inline]
+fn intersects(rect1: &PackedRect, rect2: &PackedRect) -> bool {
+    rect1.x < rect2.x + rect2.width && rect2.x < rect1.x + rect1.width &&
+    rect1.y < rect2.y + rect2.height && rect2.y < rect1.y + rect1.height
+}
+
+/// Determine whether the first rectangle lies entirely inside the second one.
+#[inline]
+fn is_contained_in(rect1: &PackedRect, rect2: &PackedRect) -> bool {
+    rect1.x >= rect2.x && rect1.y >= rect2.y &&
+    rect1.x + rect1.width <= rect2.x + rect2.width &&
+    rect1.y + rect1.height <= rect2.y + rect2.height
+}
+
+/// Split a free rectangle around a used rectangle into the maximal free rectangles that 
+/// remain on each side of the used rectangle. This returns `false` when the rectangles 
+/// do not overlap, in which case the free rectangle is unchanged.
+fn split_free_rect(free_rect: &PackedRect, used: &PackedRect, new_free_rects: &mut Vec<PackedRect>) -> bool {
+    if !intersects(free_rect, used) {
+        return false;
+    }
+
+    let free_right = free_rect.x + free_rect.width;
+    let free_bottom = free_rect.y + free_rect.height;
+    let used_right = used.x + used.width;
+    let used_bottom = used.y + used.height;
+    if used.x > free_rect.x {
+        new_free_rects.push(PackedRect::new(free_rect.x, free_rect.y, used.x - free_rect.x, free_rect.height));
+    }
+    if used_right < free_right {
+        new_free_rects.push(PackedRect::new(used_right, free_rect.y, free_right - used_right, free_rect.height));
+    }
+    if used.y > free_rect.y {
+        new_free_rects.push(PackedRect::new(free_rect.x, free_rect.y, free_rect.width, used.y - free_rect.y));
+    }
+    if used_bottom < free_bottom {
+        new_free_rects.push(PackedRect::new(free_rect.x, used_bottom, free_rect.width, free_bottom - used_bottom));
+    }
+
+    true
+}
+
+/// Remove every free rectangle that lies inside another free rectangle.
+fn prune_free_rects(free_rects: &mut Vec<PackedRect>) {
+    let mut i = 0;
+    while i < free_rects.len() {
+        let mut j = i + 1;
+        let mut removed_i = false;
+        while j < free_rects.len() {
+            if is_contained_in(&free_rects[i], &free_rects[j]) {
+                free_rects.remove(i);
+                removed_i = true;
+                break;
+            }
+            if is_contained_in(&free_rects[j], &free_rects[i]) {
+                free_rects.remove(j);
+            } else {
+                j += 1;
+            }
+        }
+        if !removed_i {
+            i += 1;
+        }
+    }
+}
+
+/// The rule a skyline packer uses to choose where to place a rectangle along the skyline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SkylineHeuristic {
+    /// Place the rectangle so its bottom edge is as high up in the packing region 
+    /// as possible.
+    BottomLeft,
+    /// Place the rectangle where it leaves the least unusable space underneath it.
+    MinWaste,
+}
+
+/// A horizontal segment of the skyline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct SkylineNode {
+    /// The horizontal position of the left end of the segment.
+    x: usize,
+    /// The vertical position of the segment.
+    y: usize,
+    /// The length of the segment.
+    width: usize,
+}
+
+/// A skyline packer tracks the lowest edge of the free space in each column of the 
+/// packing region as a sequence of horizontal segments, and places each rectangle on 
+/// top of the skyline. Skyline packing is fast and packs densely, which makes it well
+/// suited to packing rectangles at runtime.
+#[derive(Clone, Debug)]
+pub struct SkylinePacker {
+    /// The width of the packing region.
+    width: usize,
+    /// The height of the packing region.
+    height: usize,
+    /// The rule for choosing where to place each rectangle.
+    heuristic: SkylineHeuristic,
+    /// The segments of the skyline, ordered from left to right.
+    skyline: Vec<SkylineNode>,
+    /// The total area of the rectangles placed in the packing region.
+    used_area: usize,
+}
+
+impl SkylinePacker {
+    /// Construct a new skyline packer for an empty packing region.
+    pub fn new(width: usize, height: usize, heuristic: SkylineHeuristic) -> SkylinePacker {
+        SkylinePacker {
+            width: width,
+            height: height,
+            heuristic: heuristic,
+            skyline: vec![SkylineNode { x: 0, y: 0, width: width }],
+            used_area: 0,
+        }
+    }
+
+    /// Get the heuristic the packer uses for placing rectangles.
+    #[inline]
+    pub fn heuristic(&self) -> SkylineHeuristic {
+        self.heuristic
+    }
+
+    /// Determine the vertical position a rectangle would rest at if its left edge were 
+    /// placed at the start of the given skyline segment. This returns `None` if the 
+    /// rectangle does not fit there.
+    fn rect_fits(&self, index: usize, width: usize, height: usize) -> Option<usize> {
+        let x = self.skyline[index].x;
+        if x + width > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut width_left = width;
+        let mut i = index;
+        while width_left > 0 {
+            let node = &self.skyline[i];
+            y = usize::max(y, node.y);
+            if y + height > self.height {
+                return None;
+            }
+            width_left = width_left.saturating_sub(node.width);
+            i += 1;
+        }
+
+        Some(y)
+    }
+
+    /// Compute the area of the gaps between the skyline and the bottom edge of a 
+    /// rectangle resting at the given skyline segment.
+    fn wasted_area(&self, index: usize, width: usize, y: usize) -> usize {
+        let rect_right = self.skyline[index].x + width;
+        let mut wasted_area = 0;
+        for node in self.skyline[index..].iter() {
+            if node.x >= rect_right {
+                break;
+            }
+            let node_right = usize::min(node.x + node.width, rect_right);
+            wasted_area += (node_right - node.x) * (y - node.y);
+        }
+
+        wasted_area
+    }
+
+    /// Place a rectangle in the best position found among the allowed orientations.
+    fn insert_oriented(&mut self, width: usize, height: usize, allow_rotation: bool) -> Option<PackedRect> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        // Lower scores are better, with the second score breaking ties.
+        let mut best: Option<(usize, PackedRect, (usize, usize))> = None;
+        for i in 0..self.skyline.len() {
+            for (width, height, rotated) in orientations(width, height, allow_rotation) {
+                if let Some(y) = self.rect_fits(i, width, height) {
+                    let rect = PackedRect { rotated: rotated, ..PackedRect::new(self.skyline[i].x, y, width, height) };
+                    let score = match self.heuristic {
+                        SkylineHeuristic::BottomLeft => (y + height, self.skyline[i].width),
+                        SkylineHeuristic::MinWaste => (self.wasted_area(i, width, y), y + height),
+                    };
+                    let is_better = match best {
+                        Some((_, _, best_score)) => score < best_score,
+                        None => true,
+                    };
+                    if is_better {
+                        best = Some((i, rect, score));
+                    }
+                }
+            }
+        }
+
+        let (index, rect, _) = best?;
+        self.add_skyline_level(index, &rect);
+        self.used_area += rect.area();
+
+        Some(rect)
+    }
+
+    /// Raise the skyline over a newly placed rectangle.
+    fn add_skyline_level(&mut self, index: usize, rect: &PackedRect) {
+        let new_node = SkylineNode { x: rect.x, y: rect.y + rect.height, width: rect.width };
+        self.skyline.insert(index, new_node);
+
+        // Shrink or remove the segments now lying underneath the new segment.
+        let new_right = new_node.x + new_node.width;
+        let i = index + 1;
+        while i < self.skyline.len() {
+            let node = self.skyline[i];
+            if node.x >= new_right {
+                break;
+            }
+            let node_right = node.x + node.width;
+            if node_right <= new_right {
+                self.skyline.remove(i);
+            } else {
+                self.skyline[i].x = new_right;
+                self.skyline[i].width = node_right - new_right;
+                break;
+            }
+        }
+
+        // Merge neighboring segments at the same height.
+        let mut i = 0;
+        while i + 1 < self.skyline.len() {
+            if self.skyline[i].y == self.skyline[i + 1].y {
+                self.skyline[i].width += self.skyline[i + 1].width;
+                self.skyline.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+impl Packer for SkylinePacker {
+    #[inline]
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn insert(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, false)
+    }
+
+    fn insert_rotatable(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, true)
+    }
+
+    fn occupancy(&self) -> f32 {
+        if self.width * self.height == 0 {
+            return 0.0;
+        }
+
+        self.used_area as f32 / (self.width * self.height) as f32
+    }
+}
+
+/// The rule a guillotine packer uses to choose which free rectangle to place a 
+/// rectangle into.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GuillotineHeuristic {
+    /// Choose the smallest free rectangle the rectangle fits into.
+    BestAreaFit,
+    /// Choose the free rectangle with the smallest leftover space along the shorter side.
+    BestShortSideFit,
+    /// Choose the free rectangle with the smallest leftover space along the longer side.
+    BestLongSideFit,
+    /// Choose the largest free rectangle the rectangle fits into.
+    WorstAreaFit,
+    /// Choose the free rectangle with the largest leftover space along the shorter side.
+    WorstShortSideFit,
+    /// Choose the free rectangle with the largest leftover space along the longer side.
+    WorstLongSideFit,
+}
+
+/// The rule a guillotine packer uses to split the leftover space of a free rectangle 
+/// into two new free rectangles after placing a rectangle inside it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GuillotineSplit {
+    /// Cut along the axis with the shorter leftover length.
+    ShorterLeftoverAxis,
+    /// Cut along the axis with the longer leftover length.
+    LongerLeftoverAxis,
+    /// Cut so that the smaller of the two new free rectangles is as small as possible.
+    MinimizeArea,
+    /// Cut so that the larger of the two new free rectangles is as large as possible.
+    MaximizeArea,
+    /// Cut along the shorter axis of the free rectangle.
+    ShorterAxis,
+    /// Cut along the longer axis of the free rectangle.
+    LongerAxis,
+}
+
+/// A guillotine packer keeps a list of disjoint free rectangles. Placing a rectangle 
+/// inside a free rectangle cuts the leftover space into two new free rectangles with
+/// a single straight cut. Optionally, the packer merges neighboring free rectangles
+/// back together to reduce fragmentation.
+#[derive(Clone, Debug)]
+pub struct GuillotinePacker {
+    /// The width of the packing region.
+    width: usize,
+    /// The height of the packing region.
+    height: usize,
+    /// The rule for choosing the free rectangle to place each rectangle into.
+    heuristic: GuillotineHeuristic,
+    /// The rule for splitting the leftover space of a free rectangle.
+    split: GuillotineSplit,
+    /// Whether to merge neighboring free rectangles after each placement.
+    merge: bool,
+    /// The disjoint free rectangles in the packing region.
+    free_rects: Vec<PackedRect>,
+    /// The total area of the rectangles placed in the packing region.
+    used_area: usize,
+}
+
+impl GuillotinePacker {
+    /// Construct a new guillotine packer for an empty packing region.
+    pub fn new(
+        width: usize, height: usize, 
+        heuristic: GuillotineHeuristic, split: GuillotineSplit, merge: bool) -> GuillotinePacker {
+
+        GuillotinePacker {
+            width: width,
+            height: height,
+            heuristic: heuristic,
+            split: split,
+            merge: merge,
+            free_rects: vec![PackedRect::new(0, 0, width, height)],
+            used_area: 0,
+        }
+    }
+
+    /// Get the heuristic the packer uses for choosing free rectangles.
+    #[inline]
+    pub fn heuristic(&self) -> GuillotineHeuristic {
+        self.heuristic
+    }
+
+    /// Get the rule the packer uses for splitting free rectangles.
+    #[inline]
+    pub fn split(&self) -> GuillotineSplit {
+        self.split
+    }
+
+    /// Score the placement of a rectangle inside a free rectangle. Lower scores are better.
+    fn score(&self, free_rect: &PackedRect, width: usize, height: usize) -> isize {
+        let leftover_horizontal = (free_rect.width - width) as isize;
+        let leftover_vertical = (free_rect.height - height) as isize;
+        let short_side = isize::min(leftover_horizontal, leftover_vertical);
+        let long_side = isize::max(leftover_horizontal, leftover_vertical);
+        let leftover_area = (free_rect.area() - width * height) as isize;
+        match self.heuristic {
+            GuillotineHeuristic::BestAreaFit => leftover_area,
+            GuillotineHeuristic::BestShortSideFit => short_side,
+            GuillotineHeuristic::BestLongSideFit => long_side,
+            GuillotineHeuristic::WorstAreaFit => -leftover_area,
+            GuillotineHeuristic::WorstShortSideFit => -short_side,
+            GuillotineHeuristic::WorstLongSideFit => -long_side,
+        }
+    }
+
+    /// Split the leftover space of a free rectangle around a rectangle placed in its top 
+    /// left corner.
+    fn split_free_rect(&mut self, free_rect: &PackedRect, rect: &PackedRect) {
+        let leftover_width = free_rect.width - rect.width;
+        let leftover_height = free_rect.height - rect.height;
+        let split_horizontal = match self.split {
+            GuillotineSplit::ShorterLeftoverAxis => leftover_width <= leftover_height,
+            GuillotineSplit::LongerLeftoverAxis => leftover_width > leftover_height,
+            GuillotineSplit::MinimizeArea => rect.width * leftover_height > leftover_width * rect.height,
+            GuillotineSplit::MaximizeArea => rect.width * leftover_height <= leftover_width * rect.height,
+            GuillotineSplit::ShorterAxis => free_rect.width <= free_rect.height,
+            GuillotineSplit::LongerAxis => free_rect.width > free_rect.height,
+        };
+
+        let (bottom_width, right_height) = if split_horizontal {
+            (free_rect.width, rect.height)
+        } else {
+            (rect.width, free_rect.height)
+        };
+        let bottom = PackedRect::new(free_rect.x, free_rect.y + rect.height, bottom_width, leftover_height);
+        let right = PackedRect::new(free_rect.x + rect.width, free_rect.y, leftover_width, right_height);
+        if bottom.area() > 0 {
+            self.free_rects.push(bottom);
+        }
+        if right.area() > 0 {
+            self.free_rects.push(right);
+        }
+    }
+
+    /// Place a rectangle in the best free rectangle found among the allowed orientations.
+    fn insert_oriented(&mut self, width: usize, height: usize, allow_rotation: bool) -> Option<PackedRect> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let mut best: Option<(usize, (usize, usize, bool), isize)> = None;
+        for (i, free_rect) in self.free_rects.iter().enumerate() {
+            for (width, height, rotated) in orientations(width, height, allow_rotation) {
+                if free_rect.width < width || free_rect.height < height {
+                    continue;
+                }
+
+                let score = self.score(free_rect, width, height);
+                let is_better = match best {
+                    Some((_, _, best_score)) => score < best_score,
+                    None => true,
+                };
+                if is_better {
+                    best = Some((i, (width, height, rotated), score));
+                }
+            }
+        }
+
+        let (index, (width, height, rotated), _) = best?;
+        let free_rect = self.free_rects.swap_remove(index);
+        let rect = PackedRect { rotated: rotated, ..PackedRect::new(free_rect.x, free_rect.y, width, height) };
+        self.split_free_rect(&free_rect, &rect);
+        if self.merge {
+            self.merge_free_rects();
+        }
+        self.used_area += rect.area();
+
+        Some(rect)
+    }
+
+    /// Merge pairs of neighboring free rectangles that together form a rectangle.
+    fn merge_free_rects(&mut self) {
+        let mut i = 0;
+        while i < self.free_rects.len() {
+            let mut j = i + 1;
+            while j < self.free_rects.len() {
+                let rect1 = self.free_rects[i];
+                let rect2 = self.free_rects[j];
+                let merged = if rect1.x == rect2.x && rect1.width == rect2.width {
+                    if rect1.y + rect1.height == rect2.y {
+                        Some(PackedRect::new(rect1.x, rect1.y, rect1.width, rect1.height + rect2.height))
+                    } else if rect2.y + rect2.height == rect1.y {
+                        Some(PackedRect::new(rect1.x, rect2.y, rect1.width, rect1.height + rect2.height))
+                    } else {
+                        None
+                    }
+                } else if rect1.y == rect2.y && rect1.height == rect2.height {
+                    if rect1.x + rect1.width == rect2.x {
+                        Some(PackedRect::new(rect1.x, rect1.y, rect1.width + rect2.width, rect1.height))
+                    } else if rect2.x + rect2.width == rect1.x {
+                        Some(PackedRect::new(rect2.x, rect1.y, rect1.width + rect2.width, rect1.height))
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+
+                match merged {
+                    Some(rect) => {
+                        self.free_rects[i] = rect;
+                        self.free_rects.remove(j);
+                        // The merged rectangle may now merge with a rectangle we already passed.
+                        j = i + 1;
+                    }
+                    None => j += 1,
+                }
+            }
+            i += 1;
+        }
+    }
+}
+
+impl Packer for GuillotinePacker {
+    #[inline]
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn insert(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, false)
+    }
+
+    fn insert_rotatable(&mut self, width: usize, height: usize) -> Option<PackedRect> {
+        self.insert_oriented(width, height, true)
+    }
+
+    fn occupancy(&self) -> f32 {
+        if self.width * self.height == 0 {
+            return 0.0;
+        }
+
+        self.used_area as f32 / (self.width * self.height) as f32
+    }
+}
+
+/// The algorithm a texture atlas builder uses to pack textures into an atlas image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PackingAlgorithm {
+    /// Pack the textures with a shelf packer.
+    Shelf,
+    /// Pack the textures with a maximal rectangles packer using the given heuristic.
+    MaxRects(MaxRectsHeuristic),
+    /// Pack the textures with a skyline packer using the given heuristic.
+    Skyline(SkylineHeuristic),
+    /// Pack the textures with a guillotine packer.
+    Guillotine {
+        /// The rule for choosing the free rectangle to place each texture into.
+        heuristic: GuillotineHeuristic,
+        /// The rule for splitting the leftover space of a free rectangle.
+        split: GuillotineSplit,
+        /// Whether to merge neighboring free rectangles.
+        merge: bool,
+    },
+}
+
+impl PackingAlgorithm {
+    /// Construct a packer implementing the packing algorithm for a packing region
+    /// with the given dimensions.
+    pub fn packer(self, width: usize, height: usize) -> Box<dyn Packer> {
+        match self {
+            PackingAlgorithm::Shelf => Box::new(ShelfPacker::new(width, height)),
+            PackingAlgorithm::MaxRects(heuristic) => Box::new(MaxRectsPacker::new(width, height, heuristic)),
+            PackingAlgorithm::Skyline(heuristic) => Box::new(SkylinePacker::new(width, height, heuristic)),
+            PackingAlgorithm::Guillotine { heuristic, split, merge } => {
+                Box::new(GuillotinePacker::new(width, height, heuristic, split, merge))
+            }
+        }
+    }
+}
+
+impl Default for PackingAlgorithm {
+    fn default() -> PackingAlgorithm {
+        PackingAlgorithm::MaxRects(MaxRectsHeuristic::BestShortSideFit)
+    }
+}
@@ -0,0 +1,115 @@
+use crate::ColorSpace;
+
+
+/// The signature every PNG file starts with.
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+/// The gamma of a linear image in a `gAMA` chunk, in units of 1/100000.
+const LINEAR_GAMMA: u32 = 100_000;
+/// The gamma that `gAMA` chunks for sRGB images should contain, in units of 1/100000.
+const SRGB_GAMMA: u32 = 45_455;
+
+/// A chunk of a PNG file.
+struct Chunk<'a> {
+    /// The four letter type of the chunk.
+    chunk_type: &'a [u8],
+    /// The data in the chunk.
+    data: &'a [u8],
+    /// The position in the PNG file where the chunk ends.
+    end: usize,
+}
+
+/// Read the chunk starting at a given position in a PNG file.
+fn read_chunk(png_data: &[u8], start: usize) -> Option<Chunk<'_>> {
+    let header = png_data.get(start..(start + 8))?;
+    let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let data_start = start + 8;
+    let data = png_data.get(data_start..(data_start + length))?;
+    // Every chunk ends with a four byte checksum.
+    let end = data_start + length + 4;
+    if end > png_data.len() {
+        return None;
+    }
+
+    Some(Chunk {
+        chunk_type: &header[4..8],
+        data: data,
+        end: end,
+    })
+}
+
+/// Determine the color space a PNG file declares with its `sRGB` and `gAMA` chunks. An
+/// `sRGB` chunk declares an sRGB image, and a `gAMA` chunk with a gamma of one declares a
+/// linear image. A `gAMA` chunk with any other gamma declares a gamma encoded image, which
+/// we treat as sRGB. This returns `None` if the PNG file declares neither.
+pub(crate) fn color_space(png_data: &[u8]) -> Option<ColorSpace> {
+    if !png_data.starts_with(&PNG_SIGNATURE) {
+        return None;
+    }
+
+    // The color space chunks have to come before the image data.
+    let mut gamma = None;
+    let mut position = PNG_SIGNATURE.len();
+    while let Some(chunk) = read_chunk(png_data, position) {
+        match chunk.chunk_type {
+            b"sRGB" => return Some(ColorSpace::Srgb),
+            b"gAMA" if chunk.data.len() == 4 => {
+                gamma = Some(u32::from_be_bytes([chunk.data[0], chunk.data[1], chunk.data[2], chunk.data[3]]));
+            }
+            b"IDAT" | b"IEND" => break,
+            _ => {}
+        }
+        position = chunk.end;
+    }
+
+    gamma.map(|gamma| {
+        // Allow for rounding in the encoder that wrote the PNG file.
+        if (gamma as i64 - LINEAR_GAMMA as i64).abs() <= 1_000 {
+            ColorSpace::Linear
+        } else {
+            ColorSpace::Srgb
+        }
+    })
+}
+
+/// Encode a chunk of a PNG file.
+fn encode_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(chunk_type);
+    hasher.update(data);
+    let mut chunk = Vec::with_capacity(data.len() + 12);
+    chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(chunk_type);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&hasher.finalize().to_be_bytes());
+
+    chunk
+}
+
+/// Declare the color space of an encoded PNG file by inserting color space chunks right
+/// after its header chunk. An sRGB image gets an `sRGB` chunk along with the `gAMA` chunk
+/// the PNG specification recommends for it, and a linear image gets a `gAMA` chunk with
+/// a gamma of one.
+pub(crate) fn with_color_space(png_data: Vec<u8>, color_space: ColorSpace) -> Vec<u8> {
+    let header_end = match read_chunk(&png_data, PNG_SIGNATURE.len()) {
+        Some(chunk) if chunk.chunk_type == b"IHDR" => chunk.end,
+        _ => return png_data,
+    };
+    let mut chunks = vec![];
+    match color_space {
+        ColorSpace::Srgb => {
+            // The rendering intent is perceptual.
+            chunks.extend(encode_chunk(b"sRGB", &[0]));
+            chunks.extend(encode_chunk(b"gAMA", &SRGB_GAMMA.to_be_bytes()));
+        }
+        ColorSpace::Linear => {
+            chunks.extend(encode_chunk(b"gAMA", &LINEAR_GAMMA.to_be_bytes()));
+        }
+    }
+
+    let mut result = Vec::with_capacity(png_data.len() + chunks.len());
+    result.extend_from_slice(&png_data[..header_end]);
+    result.extend(chunks);
+    result.extend_from_slice(&png_data[header_end..]);
+
+    result
+}
@@ -0,0 +1,299 @@
+use crate::{
+    AlphaMode,
+    AtlasEntry,
+    BoundingBoxPixelCoords,
+    ErrorKind,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+    TextureAtlas2DResult,
+};
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+
+/// A `SubTexture` element of a Sparrow atlas file, describing one texture.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct SparrowSubTexture {
+    /// The position of the top left corner of the texture in the atlas image, with the
+    /// rows counted from the top of the image.
+    x: usize,
+    y: usize,
+    /// The dimensions of the rectangle the texture covers in the atlas image. These are
+    /// the dimensions of the texture after it gets rotated.
+    width: usize,
+    height: usize,
+    /// Where the texture sits inside the original image, as the position of the top left
+    /// corner of the original image relative to the texture, and the dimensions of the
+    /// original image. This is absent for untrimmed textures.
+    frame: Option<(i64, i64, usize, usize)>,
+    /// Whether the texture is stored rotated by 90 degrees clockwise.
+    rotated: bool,
+}
+
+/// The contents of a Sparrow atlas file.
+#[derive(Clone, Debug)]
+struct SparrowAtlas {
+    /// The path of the atlas image file relative to the atlas file.
+    image_path: Option<String>,
+    /// The dimensions of the atlas image, if the atlas file gives them.
+    width: Option<usize>,
+    height: Option<usize>,
+    /// The textures, named and in the order of the atlas file.
+    sub_textures: Vec<(String, SparrowSubTexture)>,
+}
+
+/// Construct an error for a Sparrow atlas file.
+fn error(kind: ErrorKind, page_name: &str, source: Option<Box<dyn std::error::Error + Send + Sync>>) -> TextureAtlas2DError {
+    TextureAtlas2DError::new(kind, None, Some(String::from(page_name)), source)
+}
+
+/// Construct an error for a malformed Sparrow atlas file.
+fn parse_error(page_name: &str, message: String) -> TextureAtlas2DError {
+    error(ErrorKind::CouldNotLoadCoordinateCharts, page_name, Some(message.into()))
+}
+
+/// Get an attribute of an element of a Sparrow atlas file as a number.
+fn number_attribute<T: std::str::FromStr>(node: &roxmltree::Node, name: &str) -> Result<Option<T>, String> {
+    match node.attribute(name) {
+        Some(value) => value.trim().parse::<T>().map(Some).map_err(|_| {
+            format!("invalid value {} for attribute {} of element {}", value, name, node.tag_name().name())
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Get an attribute of an element of a Sparrow atlas file that every element must have.
+fn required<T>(node: &roxmltree::Node, name: &str, value: Option<T>) -> Result<T, String> {
+    value.ok_or_else(|| format!("missing attribute {} of element {}", name, node.tag_name().name()))
+}
+
+/// Parse a `SubTexture` element of a Sparrow atlas file.
+fn parse_sub_texture(node: &roxmltree::Node) -> Result<(String, SparrowSubTexture), String> {
+    let name = required(node, "name", node.attribute("name"))?;
+    let x = required(node, "x", number_attribute(node, "x")?)?;
+    let y = required(node, "y", number_attribute(node, "y")?)?;
+    let width = required(node, "width", number_attribute(node, "width")?)?;
+    let height = required(node, "height", number_attribute(node, "height")?)?;
+    let frame = match number_attribute::<i64>(node, "frameX")? {
+        Some(frame_x) => {
+            let frame_y = required(node, "frameY", number_attribute(node, "frameY")?)?;
+            let frame_width = required(node, "frameWidth", number_attribute(node, "frameWidth")?)?;
+            let frame_height = required(node, "frameHeight", number_attribute(node, "frameHeight")?)?;
+            Some((frame_x, frame_y, frame_width, frame_height))
+        }
+        None => None,
+    };
+    let rotated = node.attribute("rotated").map(|value| value.trim()) == Some("true");
+    let sub_texture = SparrowSubTexture {
+        x: x,
+        y: y,
+        width: width,
+        height: height,
+        frame: frame,
+        rotated: rotated,
+    };
+
+    Ok((String::from(name), sub_texture))
+}
+
+/// Parse the contents of a Sparrow atlas file.
+fn parse_atlas(xml_data: &[u8], page_name: &str) -> Result<SparrowAtlas, TextureAtlas2DError> {
+    let text = std::str::from_utf8(xml_data).map_err(|e| {
+        error(ErrorKind::CouldNotLoadCoordinateCharts, page_name, Some(Box::new(e)))
+    })?;
+    let document = roxmltree::Document::parse(text).map_err(|e| {
+        error(ErrorKind::CouldNotLoadCoordinateCharts, page_name, Some(Box::new(e)))
+    })?;
+    let root = document.root_element();
+    if !root.has_tag_name("TextureAtlas") {
+        return Err(error(ErrorKind::CouldNotLoadCoordinateCharts, page_name, None));
+    }
+    let mut sub_textures = vec![];
+    for node in root.children().filter(|node| node.has_tag_name("SubTexture")) {
+        let sub_texture = parse_sub_texture(&node).map_err(|message| parse_error(page_name, message))?;
+        sub_textures.push(sub_texture);
+    }
+    let width = number_attribute::<usize>(&root, "width").map_err(|message| parse_error(page_name, message))?;
+    let height = number_attribute::<usize>(&root, "height").map_err(|message| parse_error(page_name, message))?;
+
+    Ok(SparrowAtlas {
+        image_path: root.attribute("imagePath").map(String::from),
+        width: width,
+        height: height,
+        sub_textures: sub_textures,
+    })
+}
+
+/// Construct a page of a texture atlas from a parsed Sparrow atlas file and the contents
+/// of its atlas image file.
+fn page_from_atlas(
+    sparrow_atlas: SparrowAtlas,
+    image_data: &[u8],
+    page_name: &str, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+
+    let tex_image = crate::decode_atlas_image(image_data, origin).map_err(|kind| {
+        error(kind, page_name, None)
+    })?;
+    let width = tex_image.width;
+    let height = tex_image.height;
+    if sparrow_atlas.width.is_some_and(|w| w != width) || sparrow_atlas.height.is_some_and(|h| h != height) {
+        return Err(error(ErrorKind::InvalidImageData, page_name, None));
+    }
+
+    let mut names = HashSet::new();
+    let mut atlas_entries: Vec<(usize, AtlasEntry)> = vec![];
+    let mut positions: HashMap<SparrowSubTexture, usize> = HashMap::new();
+    for (name, sub_texture) in sparrow_atlas.sub_textures.into_iter() {
+        if !names.insert(name.clone()) {
+            return Err(error(ErrorKind::DuplicateTextureName, page_name, None));
+        }
+        if sub_texture.x + sub_texture.width > width || sub_texture.y + sub_texture.height > height {
+            return Err(error(ErrorKind::TextureDoesNotFitInAtlas, page_name, None));
+        }
+
+        let (upright_width, upright_height) = if sub_texture.rotated {
+            (sub_texture.height, sub_texture.width)
+        } else {
+            (sub_texture.width, sub_texture.height)
+        };
+        let source = match sub_texture.frame {
+            // The frame gives where the original image sits relative to the texture, so
+            // the texture sits inside the original image at the opposite offset.
+            Some((frame_x, frame_y, frame_width, frame_height)) if frame_x <= 0 && frame_y <= 0 => {
+                SourceImagePixelCoords {
+                    offset: OffsetPixelCoords::new((-frame_x) as usize, (-frame_y) as usize),
+                    width: frame_width,
+                    height: frame_height,
+                }
+            }
+            Some(_) => return Err(parse_error(page_name, format!("the frame of {} does not contain it", name))),
+            None => SourceImagePixelCoords::untrimmed(upright_width, upright_height),
+        };
+
+        match positions.get(&sub_texture) {
+            Some(position) => atlas_entries[*position].1.aliases.push(name),
+            None => {
+                let bounding_box = BoundingBoxPixelCoords::from_image_rect(
+                    origin, height, sub_texture.x, sub_texture.y, sub_texture.width, sub_texture.height
+                );
+                let mut atlas_entry = AtlasEntry::new(name, bounding_box, source, width, height);
+                atlas_entry.rotated = sub_texture.rotated;
+                positions.insert(sub_texture, atlas_entries.len());
+                atlas_entries.push((atlas_entries.len(), atlas_entry));
+            }
+        }
+    }
+
+    let mut atlas = TextureAtlas2D::from_atlas_entries(
+        width, height, tex_image.color_type, origin, atlas_entries, String::from(page_name), tex_image.data
+    );
+    atlas.color_space = crate::png_chunks::color_space(image_data).unwrap_or_default();
+
+    Ok(TextureAtlas2DResult {
+        atlas: atlas,
+        warnings: crate::dimension_warnings(width, height),
+    })
+}
+
+/// Load a page of a texture atlas from the contents of a Sparrow or Starling atlas file
+/// and the contents of its atlas image file. The textures are indexed in the order the
+/// atlas file lists them, and the pixel coordinates are converted from the top left
+/// corner of the atlas image to the given origin. `SubTexture` elements with the same
+/// rectangle in the atlas image become aliases of a single texture.
+pub fn load_sparrow_xml_from_memory(
+    xml_data: &[u8],
+    image_data: &[u8],
+    page_name: &str, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+
+    let sparrow_atlas = parse_atlas(xml_data, page_name)?;
+
+    page_from_atlas(sparrow_atlas, image_data, page_name, origin)
+}
+
+/// Load a page of a texture atlas from a Sparrow or Starling atlas file. The atlas image
+/// file is read from the `imagePath` the atlas file gives relative to the atlas file,
+/// and the page is named after the atlas file. The page is loaded the same way
+/// `load_sparrow_xml_from_memory` loads it.
+pub fn load_sparrow_xml_file<P: AsRef<Path>>(path: P, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+    let path = path.as_ref();
+    let page_name = path.file_stem().map_or("", |s| s.to_str().unwrap_or(""));
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let xml_data = fs::read(path).map_err(|e| {
+        error(ErrorKind::CouldNotOpenTextureAtlas, page_name, Some(Box::new(e)))
+    })?;
+    let sparrow_atlas = parse_atlas(&xml_data, page_name)?;
+    let image_path = sparrow_atlas.image_path.as_ref().ok_or_else(|| {
+        error(ErrorKind::MissingImageBuffer, page_name, None)
+    })?;
+    let image_data = fs::read(directory.join(image_path)).map_err(|e| {
+        error(ErrorKind::MissingImageBuffer, page_name, Some(Box::new(e)))
+    })?;
+
+    page_from_atlas(sparrow_atlas, &image_data, page_name, origin)
+}
+
+/// Write a page of a texture atlas out as a Sparrow atlas file at the given path, with
+/// a PNG atlas image named after the atlas file next to it, for Starling, HaxeFlixel,
+/// and other engines that read Sparrow atlas files. The pixel coordinates in the atlas
+/// file are measured from the top left corner of the atlas image whatever the origin of
+/// the atlas is, and every alias of a texture gets a `SubTexture` element of its own.
+/// The atlas image is written out as a straight alpha RGBA image with 8 bits per channel.
+pub fn export_sparrow_xml<P: AsRef<Path>>(atlas: &TextureAtlas2D, path: P) -> Result<(), TextureAtlas2DError> {
+    let path = path.as_ref();
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let image_file_name = format!("{}.png", path.file_stem().map_or("", |s| s.to_str().unwrap_or("")));
+
+    let mut text = String::new();
+    // Writing to a string never fails.
+    writeln!(text, "{}", r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        text, r#"<TextureAtlas imagePath="{}" width="{}" height="{}">"#,
+        crate::export::escape_xml(&image_file_name), atlas.width, atlas.height
+    ).unwrap();
+    let mut indices = atlas.indices();
+    indices.sort_unstable();
+    for index in indices {
+        let atlas_entry = &atlas.bounding_boxes[&index];
+        let (x, y, width, height) = atlas_entry.bounding_box_pix.to_image_rect(atlas.origin, atlas.height);
+        let (upright_width, upright_height) = if atlas_entry.rotated { (height, width) } else { (width, height) };
+        let source = atlas_entry.source;
+        let trimmed = source.offset.u != 0 || source.offset.v != 0 ||
+            source.width != upright_width || source.height != upright_height;
+        let mut attributes = format!(r#"x="{}" y="{}" width="{}" height="{}""#, x, y, width, height);
+        if trimmed {
+            write!(
+                attributes, r#" frameX="-{}" frameY="-{}" frameWidth="{}" frameHeight="{}""#,
+                source.offset.u, source.offset.v, source.width, source.height
+            ).unwrap();
+        }
+        if atlas_entry.rotated {
+            attributes.push_str(r#" rotated="true""#);
+        }
+        for name in std::iter::once(&atlas_entry.name).chain(atlas_entry.aliases.iter()) {
+            writeln!(text, r#"    <SubTexture name="{}" {}/>"#, crate::export::escape_xml(name), attributes).unwrap();
+        }
+    }
+    writeln!(text, "{}", "</TextureAtlas>").unwrap();
+
+    if !directory.as_os_str().is_empty() {
+        fs::create_dir_all(directory).map_err(|e| {
+            error(ErrorKind::IoError, &atlas.atlas_name, Some(Box::new(e)))
+        })?;
+    }
+    let image_data = crate::export::encode_sheet_image(atlas, AlphaMode::Straight)?;
+    fs::write(directory.join(&image_file_name), image_data).map_err(|e| {
+        error(ErrorKind::IoError, &atlas.atlas_name, Some(Box::new(e)))
+    })?;
+    fs::write(path, text).map_err(|e| {
+        error(ErrorKind::IoError, &atlas.atlas_name, Some(Box::new(e)))
+    })
+}
@@ -0,0 +1,384 @@
+use crate::{
+    AlphaMode,
+    AtlasEntry,
+    BoundingBoxPixelCoords,
+    ErrorKind,
+    MultiTextureAtlas2D,
+    MultiTextureAtlas2DResult,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+    TextureAtlas2DResult,
+};
+use serde::{
+    de,
+    ser,
+};
+use serde_derive::{
+    Deserialize,
+    Serialize,
+};
+
+use std::collections::{
+    HashMap,
+    HashSet,
+    VecDeque,
+};
+use std::fmt;
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+
+/// A rectangle in a TexturePacker sheet, with the rows counted from the top of the image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct TexturePackerRect {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+/// The dimensions of an image in a TexturePacker sheet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct TexturePackerSize {
+    w: usize,
+    h: usize,
+}
+
+/// A frame in a TexturePacker sheet, describing one texture.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TexturePackerFrame {
+    /// The name of the texture. Only the array format names the frames this way.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+    /// The rectangle the texture covers in the sheet image. The dimensions are the
+    /// dimensions of the texture before it gets rotated.
+    frame: TexturePackerRect,
+    /// Whether the texture is stored rotated by 90 degrees clockwise.
+    #[serde(default)]
+    rotated: bool,
+    /// Whether the transparent borders of the original image were trimmed off.
+    #[serde(default)]
+    trimmed: bool,
+    /// Where the trimmed texture sits inside the original image.
+    #[serde(default, rename = "spriteSourceSize", skip_serializing_if = "Option::is_none")]
+    sprite_source_size: Option<TexturePackerRect>,
+    /// The dimensions of the original image.
+    #[serde(default, rename = "sourceSize", skip_serializing_if = "Option::is_none")]
+    source_size: Option<TexturePackerSize>,
+}
+
+/// The frames of a TexturePacker sheet, in the order they appear in the file. The hash
+/// format stores the frames in an object keyed by name, and the array format stores
+/// them in an array.
+struct TexturePackerFrames(Vec<(Option<String>, TexturePackerFrame)>);
+
+impl<'de> de::Deserialize<'de> for TexturePackerFrames {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<TexturePackerFrames, D::Error> {
+        struct FramesVisitor;
+
+        impl<'de> de::Visitor<'de> for FramesVisitor {
+            type Value = TexturePackerFrames;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "{}", "an object or an array of frames")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<TexturePackerFrames, A::Error> {
+                let mut frames = vec![];
+                while let Some((name, frame)) = map.next_entry::<String, TexturePackerFrame>()? {
+                    frames.push((Some(name), frame));
+                }
+
+                Ok(TexturePackerFrames(frames))
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<TexturePackerFrames, A::Error> {
+                let mut frames = vec![];
+                while let Some(frame) = seq.next_element::<TexturePackerFrame>()? {
+                    frames.push((None, frame));
+                }
+
+                Ok(TexturePackerFrames(frames))
+            }
+        }
+
+        deserializer.deserialize_any(FramesVisitor)
+    }
+}
+
+impl ser::Serialize for TexturePackerFrames {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Only frames that all have names can be written out in the hash format.
+        if self.0.iter().all(|(name, _)| name.is_some()) {
+            serializer.collect_map(self.0.iter().map(|(name, frame)| (name.as_ref().unwrap(), frame)))
+        } else {
+            serializer.collect_seq(self.0.iter().map(|(_, frame)| frame))
+        }
+    }
+}
+
+/// The description of the sheet image in a TexturePacker sheet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TexturePackerMeta {
+    /// The application that wrote the sheet file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    app: Option<String>,
+    /// The version of the application that wrote the sheet file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// The path of the sheet image, relative to the sheet file.
+    image: String,
+    /// The pixel format of the sheet image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    /// The dimensions of the sheet image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<TexturePackerSize>,
+    /// The scale the textures were packed at. Some tools write this as a string, and 
+    /// others as a number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scale: Option<serde_json::Value>,
+    /// The paths of the other sheet files of a multipack sheet, relative to the sheet file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    related_multi_packs: Vec<String>,
+}
+
+/// A TexturePacker sheet file.
+#[derive(Serialize, Deserialize)]
+struct TexturePackerSheet {
+    frames: TexturePackerFrames,
+    meta: TexturePackerMeta,
+}
+
+/// Construct an error for a page of a TexturePacker sheet.
+fn error(
+    kind: ErrorKind,
+    multi_atlas_name: &str, page_name: &str,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>) -> TextureAtlas2DError {
+
+    TextureAtlas2DError::new(kind, Some(String::from(multi_atlas_name)), Some(String::from(page_name)), source)
+}
+
+/// Parse a TexturePacker sheet file.
+fn parse_sheet(json_data: &[u8], multi_atlas_name: &str, page_name: &str) -> Result<TexturePackerSheet, TextureAtlas2DError> {
+    serde_json::from_slice(json_data).map_err(|e| {
+        error(ErrorKind::CouldNotLoadCoordinateCharts, multi_atlas_name, page_name, Some(Box::new(e)))
+    })
+}
+
+/// Construct a page of a texture atlas from a parsed TexturePacker sheet and the contents
+/// of its sheet image file.
+fn page_from_sheet(
+    sheet: TexturePackerSheet,
+    image_data: &[u8],
+    multi_atlas_name: &str, page_name: &str, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+
+    let tex_image = crate::decode_atlas_image(image_data, origin).map_err(|kind| {
+        error(kind, multi_atlas_name, page_name, None)
+    })?;
+    let width = tex_image.width;
+    let height = tex_image.height;
+    if let Some(size) = sheet.meta.size {
+        if (size.w, size.h) != (width, height) {
+            return Err(error(ErrorKind::InvalidImageData, multi_atlas_name, page_name, None));
+        }
+    }
+
+    // TexturePacker writes identical textures out once, and repeats the frame for each
+    // of their names, so frames covering the same part of the sheet image become aliases.
+    let mut names = HashSet::new();
+    let mut atlas_entries: Vec<(usize, AtlasEntry)> = vec![];
+    let mut positions: HashMap<_, usize> = HashMap::new();
+    for (key, frame) in sheet.frames.0.into_iter() {
+        let name = key.or(frame.filename).ok_or_else(|| {
+            error(ErrorKind::CouldNotLoadCoordinateCharts, multi_atlas_name, page_name, None)
+        })?;
+        if !names.insert(name.clone()) {
+            return Err(error(ErrorKind::DuplicateTextureName, multi_atlas_name, page_name, None));
+        }
+
+        let rect = frame.frame;
+        let (stored_width, stored_height) = if frame.rotated { (rect.h, rect.w) } else { (rect.w, rect.h) };
+        if rect.x + stored_width > width || rect.y + stored_height > height {
+            return Err(error(ErrorKind::TextureDoesNotFitInAtlas, multi_atlas_name, page_name, None));
+        }
+        let source = match (frame.sprite_source_size, frame.source_size) {
+            (Some(sprite_source_size), Some(source_size)) => SourceImagePixelCoords {
+                offset: OffsetPixelCoords::new(sprite_source_size.x, sprite_source_size.y),
+                width: source_size.w,
+                height: source_size.h,
+            },
+            _ => SourceImagePixelCoords::untrimmed(rect.w, rect.h),
+        };
+
+        let key = (rect, frame.rotated, frame.sprite_source_size, frame.source_size);
+        match positions.get(&key) {
+            Some(position) => atlas_entries[*position].1.aliases.push(name),
+            None => {
+                let bounding_box = BoundingBoxPixelCoords::from_image_rect(
+                    origin, height, rect.x, rect.y, stored_width, stored_height
+                );
+                let mut atlas_entry = AtlasEntry::new(name, bounding_box, source, width, height);
+                atlas_entry.rotated = frame.rotated;
+                positions.insert(key, atlas_entries.len());
+                atlas_entries.push((atlas_entries.len(), atlas_entry));
+            }
+        }
+    }
+
+    let mut atlas = TextureAtlas2D::from_atlas_entries(
+        width, height, tex_image.color_type, origin, atlas_entries, String::from(page_name), tex_image.data
+    );
+    atlas.color_space = crate::png_chunks::color_space(image_data).unwrap_or_default();
+
+    Ok(TextureAtlas2DResult {
+        atlas: atlas,
+        warnings: crate::dimension_warnings(width, height),
+    })
+}
+
+/// Load a page of a texture atlas from the contents of a TexturePacker sheet file in
+/// the JSON (Hash) or JSON (Array) format and the contents of its sheet image file. The
+/// textures are indexed in the order the sheet file lists them, and the pixel
+/// coordinates are converted from the top left corner of the sheet image to the given
+/// origin. Frames with the same rectangle in the sheet image become aliases of a single
+/// texture. This ignores the other sheet files of a multipack sheet.
+pub fn load_texturepacker_from_memory(
+    json_data: &[u8],
+    image_data: &[u8],
+    page_name: &str, origin: Origin) -> Result<TextureAtlas2DResult, TextureAtlas2DError> {
+
+    let sheet = parse_sheet(json_data, "", page_name)?;
+
+    page_from_sheet(sheet, image_data, "", page_name, origin)
+}
+
+/// Load a multi texture atlas from a TexturePacker sheet file in the JSON (Hash) or JSON
+/// (Array) format. The sheet image file is read from the path the sheet file gives
+/// relative to the sheet file. Each sheet file of a multipack sheet becomes a page
+/// named after the sheet file, starting with the given file and followed by the files
+/// it lists in `related_multi_packs`, relative to the sheet file listing them. The pages
+/// are loaded the same way `load_texturepacker_from_memory` loads them.
+pub fn load_texturepacker_file<P: AsRef<Path>>(path: P, origin: Origin) -> Result<MultiTextureAtlas2DResult, TextureAtlas2DError> {
+    let path = path.as_ref();
+    let multi_atlas_name = path.file_name().map_or("", |s| s.to_str().unwrap_or(""));
+
+    let mut pages = vec![];
+    let mut warnings = vec![];
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut pending: VecDeque<PathBuf> = VecDeque::new();
+    pending.push_back(path.to_path_buf());
+    while let Some(sheet_path) = pending.pop_front() {
+        // Related sheet files can refer back to each other along different relative paths.
+        let canonical_path = fs::canonicalize(&sheet_path).unwrap_or_else(|_| sheet_path.clone());
+        if !visited.insert(canonical_path) {
+            continue;
+        }
+        let directory = sheet_path.parent().unwrap_or_else(|| Path::new(""));
+        let page_name = sheet_path.file_stem().map_or("", |s| s.to_str().unwrap_or(""));
+        let json_data = fs::read(&sheet_path).map_err(|e| {
+            error(ErrorKind::CouldNotOpenTextureAtlas, multi_atlas_name, page_name, Some(Box::new(e)))
+        })?;
+        let sheet = parse_sheet(&json_data, multi_atlas_name, page_name)?;
+        let image_data = fs::read(directory.join(&sheet.meta.image)).map_err(|e| {
+            error(ErrorKind::MissingImageBuffer, multi_atlas_name, page_name, Some(Box::new(e)))
+        })?;
+        for related_path in sheet.meta.related_multi_packs.iter() {
+            pending.push_back(directory.join(related_path));
+        }
+
+        let result = page_from_sheet(sheet, &image_data, multi_atlas_name, page_name, origin)?;
+        pages.push(result.atlas);
+        warnings.push(result.warnings);
+    }
+
+    Ok(MultiTextureAtlas2DResult {
+        multi_atlas: MultiTextureAtlas2D::new(pages),
+        warnings: warnings,
+    })
+}
+
+/// Construct the TexturePacker sheet describing a page of a multi texture atlas. Every
+/// alias of a texture gets a frame of its own, right after the frame of the texture.
+fn sheet_from_page(atlas: &TextureAtlas2D, image_file_name: &str, related_multi_packs: Vec<String>) -> TexturePackerSheet {
+    let mut indices = atlas.indices();
+    indices.sort_unstable();
+    let mut frames = vec![];
+    for index in indices {
+        let atlas_entry = &atlas.bounding_boxes[&index];
+        let (x, y, stored_width, stored_height) = atlas_entry.bounding_box_pix.to_image_rect(atlas.origin, atlas.height);
+        let (w, h) = if atlas_entry.rotated { (stored_height, stored_width) } else { (stored_width, stored_height) };
+        let source = atlas_entry.source;
+        let trimmed = source.offset.u != 0 || source.offset.v != 0 || source.width != w || source.height != h;
+        let frame = TexturePackerFrame {
+            filename: None,
+            frame: TexturePackerRect { x: x, y: y, w: w, h: h },
+            rotated: atlas_entry.rotated,
+            trimmed: trimmed,
+            sprite_source_size: Some(TexturePackerRect { x: source.offset.u, y: source.offset.v, w: w, h: h }),
+            source_size: Some(TexturePackerSize { w: source.width, h: source.height }),
+        };
+        frames.push((Some(atlas_entry.name.clone()), frame.clone()));
+        for alias in atlas_entry.aliases.iter() {
+            frames.push((Some(alias.clone()), frame.clone()));
+        }
+    }
+
+    TexturePackerSheet {
+        frames: TexturePackerFrames(frames),
+        meta: TexturePackerMeta {
+            app: Some(String::from(env!("CARGO_PKG_NAME"))),
+            version: Some(String::from(env!("CARGO_PKG_VERSION"))),
+            image: String::from(image_file_name),
+            format: Some(String::from("RGBA8888")),
+            size: Some(TexturePackerSize { w: atlas.width, h: atlas.height }),
+            scale: Some(serde_json::Value::from("1")),
+            related_multi_packs: related_multi_packs,
+        },
+    }
+}
+
+/// Write every page of a multi texture atlas out to a directory as a TexturePacker sheet
+/// in the JSON (Hash) format, for engines that read TexturePacker sheets. Each page gets
+/// a sheet file named after the page and a PNG sheet image file next to it, and the 
+/// sheet files of a multi texture atlas with more than one page list each other in 
+/// `related_multi_packs`. The pixel coordinates in the sheet files are measured from the 
+/// top left corner of the sheet image whatever the origin of the atlas is, and the sheet
+/// images are written out as straight alpha RGBA images with 8 bits per channel.
+pub fn export_texturepacker_json<P: AsRef<Path>>(multi_atlas: &MultiTextureAtlas2D, path: P) -> Result<(), TextureAtlas2DError> {
+    let path = path.as_ref();
+    fs::create_dir_all(path).map_err(|e| {
+        TextureAtlas2DError::new(ErrorKind::IoError, None, None, Some(Box::new(e)))
+    })?;
+
+    let sheet_file_names: Vec<String> = multi_atlas.pages().iter()
+        .map(|atlas| format!("{}.json", atlas.atlas_name))
+        .collect();
+    for (atlas, sheet_file_name) in multi_atlas.pages().iter().zip(sheet_file_names.iter()) {
+        let image_file_name = format!("{}.png", atlas.atlas_name);
+        let related_multi_packs = sheet_file_names.iter()
+            .filter(|file_name| *file_name != sheet_file_name)
+            .cloned()
+            .collect();
+        let sheet = sheet_from_page(atlas, &image_file_name, related_multi_packs);
+        let sheet_data = serde_json::to_vec_pretty(&sheet).map_err(|e| {
+            error(ErrorKind::IoError, "", &atlas.atlas_name, Some(Box::new(e)))
+        })?;
+        let image_data = crate::export::encode_sheet_image(atlas, AlphaMode::Straight)?;
+
+        fs::write(path.join(sheet_file_name), sheet_data).map_err(|e| {
+            error(ErrorKind::IoError, "", &atlas.atlas_name, Some(Box::new(e)))
+        })?;
+        fs::write(path.join(&image_file_name), image_data).map_err(|e| {
+            error(ErrorKind::IoError, "", &atlas.atlas_name, Some(Box::new(e)))
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use crate::{
+    ErrorKind,
+    MultiTextureAtlas2D,
+    SourceImagePixelCoords,
+    TextureAtlas2D,
+    TextureAtlas2DError,
+};
+use serde_derive::Serialize;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+
+/// The name of the manifest file that `unpack_to_directory` writes.
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// An entry in the manifest of an unpacked texture atlas, describing one texture.
+#[derive(Serialize)]
+struct UnpackManifestEntry {
+    /// The index of the texture in its page.
+    index: usize,
+    /// The name of the texture.
+    name: String,
+    /// The path of the image file of the texture, relative to the unpacked directory.
+    file: String,
+    /// The other names of the texture.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    /// Where the texture sat in its original image, for textures that were trimmed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<SourceImagePixelCoords>,
+}
+
+/// A page in the manifest of an unpacked texture atlas.
+#[derive(Serialize)]
+struct UnpackManifestPage {
+    /// The name of the page.
+    name: String,
+    /// The textures in the page, ordered by index.
+    textures: Vec<UnpackManifestEntry>,
+}
+
+/// The manifest of an unpacked texture atlas.
+#[derive(Serialize)]
+struct UnpackManifest {
+    /// The pages of the texture atlas.
+    pages: Vec<UnpackManifestPage>,
+}
+
+/// Construct an error for a page of the texture atlas being unpacked.
+fn error(kind: ErrorKind, atlas: &TextureAtlas2D, source: Option<Box<dyn std::error::Error + Send + Sync>>) -> TextureAtlas2DError {
+    TextureAtlas2DError::new(kind, None, Some(String::from(atlas.atlas_name())), source)
+}
+
+/// Turn a texture name into the relative path of its image file. Separators in the name
+/// become directories, so unpacking an atlas built from a directory tree gives back the
+/// directory tree. This returns `None` for names that would escape the directory.
+fn texture_file_name(name: &str) -> Option<String> {
+    let mut components = vec![];
+    for component in name.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => return None,
+            _ => components.push(component),
+        }
+    }
+    if components.is_empty() {
+        return None;
+    }
+
+    let file_name = components.join("/");
+    if file_name.to_ascii_lowercase().ends_with(".png") {
+        Some(file_name)
+    } else {
+        Some(format!("{}.png", file_name))
+    }
+}
+
+/// Write every texture of every page of a multi texture atlas out to a directory as a
+/// PNG image file named after the texture, along with a manifest file named
+/// `manifest.json` listing the page, index, name, and aliases of each texture. Each
+/// texture gets copied out the same way `TextureAtlas2D::extract` copies it. This fails
+/// if two textures would be written to the same file, or if a texture name cannot be
+/// used as a file name.
+pub fn unpack_to_directory<P: AsRef<Path>>(multi_atlas: &MultiTextureAtlas2D, path: P) -> Result<(), TextureAtlas2DError> {
+    let path = path.as_ref();
+    let mut file_names = HashSet::new();
+    let mut pages = vec![];
+    for atlas in multi_atlas.pages() {
+        let mut indices: Vec<&usize> = atlas.bounding_boxes.keys().collect();
+        indices.sort();
+        let mut textures = vec![];
+        for index in indices {
+            let atlas_entry = &atlas.bounding_boxes[index];
+            let file_name = texture_file_name(&atlas_entry.name).ok_or_else(|| {
+                error(ErrorKind::InvalidTextureName, atlas, None)
+            })?;
+            if !file_names.insert(file_name.clone()) {
+                return Err(error(ErrorKind::DuplicateTextureName, atlas, None));
+            }
+
+            // Extracting a texture always succeeds for the indices of the atlas.
+            let image = atlas.extract_image(*index).unwrap();
+            let png_data = crate::encode_png_image(&image, atlas.color_space, &atlas.atlas_name)?;
+            let file_path = path.join(&file_name);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| error(ErrorKind::IoError, atlas, Some(Box::new(e))))?;
+            }
+            fs::write(&file_path, png_data).map_err(|e| error(ErrorKind::IoError, atlas, Some(Box::new(e))))?;
+
+            let source = if atlas_entry.source.is_trimmed(&atlas_entry.bounding_box_pix) {
+                Some(atlas_entry.source)
+            } else {
+                None
+            };
+            textures.push(UnpackManifestEntry {
+                index: *index,
+                name: atlas_entry.name.clone(),
+                file: file_name,
+                aliases: atlas_entry.aliases.clone(),
+                source: source,
+            });
+        }
+        pages.push(UnpackManifestPage {
+            name: atlas.atlas_name.clone(),
+            textures: textures,
+        });
+    }
+
+    let manifest = UnpackManifest { pages: pages };
+    let manifest_data = serde_json::to_vec_pretty(&manifest).map_err(|e| {
+        TextureAtlas2DError::new(ErrorKind::IoError, None, None, Some(Box::new(e)))
+    })?;
+    fs::create_dir_all(path).map_err(|e| {
+        TextureAtlas2DError::new(ErrorKind::IoError, None, None, Some(Box::new(e)))
+    })?;
+    fs::write(path.join(MANIFEST_FILE_NAME), manifest_data).map_err(|e| {
+        TextureAtlas2DError::new(ErrorKind::IoError, None, None, Some(Box::new(e)))
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::solid_image;
+use tex_atlas::{
+    BoundingBoxPixelCoords,
+    ColorType,
+    ErrorKind,
+    MultiTextureAtlas2D,
+    OffsetPixelCoords,
+    Origin,
+    TextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+use std::io;
+
+
+/// An animation with repeated frames, where the first and third frames are identical.
+fn aliasing_builder() -> TextureAtlas2DBuilder {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 8);
+    builder
+        .alias_duplicates(true)
+        .add_image("walk0", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [255, 0, 0, 255]))
+        .add_image("walk1", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 255, 0, 255]))
+        .add_image("walk2", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [255, 0, 0, 255]));
+
+    builder
+}
+
+/// Identical images get packed once, and every name of the image refers to the same 
+/// bounding box.
+#[test]
+fn builder_packs_identical_images_once() {
+    let atlas = aliasing_builder().build().unwrap();
+
+    assert_eq!(atlas.texture_count(), 2);
+    assert_eq!(atlas.texture_names().count(), 3);
+    assert_eq!(atlas.by_texture_name("walk2"), atlas.by_texture_name("walk0"));
+    assert_eq!(atlas.by_texture_name_index("walk2"), Some(0));
+    assert_eq!(atlas.by_texture_name_index("walk1"), Some(1));
+    assert_eq!(atlas.by_index_name(0), Some("walk0"));
+    assert_eq!(atlas.by_index_aliases(0), Some(&[String::from("walk2")][..]));
+}
+
+/// Without aliasing, identical images each get packed separately.
+#[test]
+fn builder_does_not_alias_by_default() {
+    let result = aliasing_builder().alias_duplicates(false).build();
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TextureDoesNotFitInAtlas);
+}
+
+/// Aliases survive writing the atlas out and reading it back in.
+#[test]
+fn aliased_atlas_written_and_then_read_should_match_aliases() {
+    let expected = aliasing_builder().build().unwrap();
+    let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+    let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+    let result = result_multi_atlas.by_page_name("atlas").unwrap();
+
+    assert_eq!(result.texture_count(), expected.texture_count());
+    assert_eq!(result.texture_names().count(), expected.texture_names().count());
+    for name in expected.texture_names() {
+        assert_eq!(result.by_texture_name_index(name), expected.by_texture_name_index(name));
+        assert_eq!(result.by_texture_name(name), expected.by_texture_name(name));
+    }
+}
+
+/// Entries sharing an index in the atlas constructor become aliases of the first entry 
+/// with that index.
+#[test]
+fn atlas_entries_sharing_an_index_are_aliases() {
+    let bounding_box = BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u: 0, v: 0 }, width: 4, height: 4 };
+    let entries = vec![
+        (0, String::from("frame0"), bounding_box), 
+        (0, String::from("frame1"), bounding_box),
+    ];
+    let data = solid_image(4, 4, [0, 0, 0, 255]);
+    let atlas = TextureAtlas2D::new(4, 4, ColorType::Rgba8, Origin::TopLeft, entries, String::from("atlas"), data);
+
+    assert_eq!(atlas.texture_count(), 1);
+    assert_eq!(atlas.by_texture_name("frame1"), Some(bounding_box));
+    assert_eq!(atlas.by_index_aliases(0), Some(&[String::from("frame1")][..]));
+}
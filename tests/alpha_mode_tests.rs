@@ -0,0 +1,121 @@
+mod common;
+
+use common::pixel_atlas;
+use tex_atlas::{
+    AlphaMode,
+    ColorType,
+    DynamicTextureAtlas2D,
+    MultiTextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+use std::io;
+
+
+/// Atlases have straight alpha unless stated otherwise.
+#[test]
+fn atlas_has_straight_alpha_by_default() {
+    let atlas = pixel_atlas(ColorType::Rgba8, &[0; 8]);
+
+    assert_eq!(atlas.alpha_mode(), AlphaMode::Straight);
+}
+
+/// Premultiplying multiplies the color channels by alpha, and leaves alpha alone.
+#[test]
+fn atlas_premultiplies_alpha() {
+    let atlas = pixel_atlas(ColorType::Rgba8, &[200, 100, 50, 128, 10, 20, 30, 0]);
+    let bgra_atlas = pixel_atlas(ColorType::Bgra8, &[200, 100, 50, 128, 10, 20, 30, 255]);
+    let premultiplied = atlas.convert_alpha_mode(AlphaMode::Premultiplied);
+
+    assert_eq!(premultiplied.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(premultiplied.as_bytes(), &[100, 50, 25, 128, 0, 0, 0, 0]);
+    assert_eq!(bgra_atlas.convert_alpha_mode(AlphaMode::Premultiplied).as_bytes(), &[100, 50, 25, 128, 10, 20, 30, 255]);
+}
+
+/// Straightening divides the color channels by alpha, leaves opaque pixels alone, and
+/// turns fully transparent pixels black.
+#[test]
+fn atlas_straightens_premultiplied_alpha() {
+    let mut atlas = pixel_atlas(ColorType::La8, &[64, 128, 90, 0]);
+    atlas.set_alpha_mode(AlphaMode::Premultiplied);
+    let straight = atlas.convert_alpha_mode(AlphaMode::Straight);
+    let opaque = pixel_atlas(ColorType::Rgba8, &[1, 2, 3, 255, 4, 5, 6, 255]);
+    let round_trip = opaque.convert_alpha_mode(AlphaMode::Premultiplied).convert_alpha_mode(AlphaMode::Straight);
+
+    assert_eq!(straight.alpha_mode(), AlphaMode::Straight);
+    assert_eq!(straight.as_bytes(), &[128, 128, 0, 0]);
+    assert_eq!(round_trip.as_bytes(), opaque.as_bytes());
+}
+
+/// Premultiplying works on 16 bit samples.
+#[test]
+fn atlas_premultiplies_16_bit_alpha() {
+    let samples: [u16; 4] = [65535, 32768, 40000, 0];
+    let pixels: Vec<u8> = samples.iter().flat_map(|sample| sample.to_ne_bytes().to_vec()).collect();
+    let atlas = pixel_atlas(ColorType::La16, &pixels);
+    let premultiplied = atlas.convert_alpha_mode(AlphaMode::Premultiplied);
+    let result: Vec<u16> = premultiplied.as_bytes()
+        .chunks_exact(2)
+        .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]))
+        .collect();
+
+    assert_eq!(result, vec![32768, 32768, 0, 0]);
+}
+
+/// Atlases without an alpha channel look the same in either alpha mode.
+#[test]
+fn atlas_without_alpha_channel_only_changes_alpha_mode() {
+    let atlas = pixel_atlas(ColorType::Rgb8, &[1, 2, 3, 4, 5, 6]);
+    let premultiplied = atlas.convert_alpha_mode(AlphaMode::Premultiplied);
+
+    assert_eq!(premultiplied.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(premultiplied.as_bytes(), atlas.as_bytes());
+}
+
+/// The alpha mode survives writing the atlas out and reading it back in.
+#[test]
+fn premultiplied_atlas_written_and_then_read_should_match_alpha_mode() {
+    let expected = pixel_atlas(ColorType::Rgba8, &[200, 100, 50, 128, 10, 20, 30, 0])
+        .convert_alpha_mode(AlphaMode::Premultiplied);
+    let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+    let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+    let result = result_multi_atlas.by_page_name("atlas").unwrap();
+
+    assert_eq!(result.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(result.as_bytes(), expected.as_bytes());
+}
+
+/// The coordinate charts only record the alpha mode of premultiplied atlases, so atlas 
+/// files with straight alpha stay the same as before alpha modes existed.
+#[test]
+fn atlas_charts_only_record_premultiplied_alpha_mode() {
+    let straight = pixel_atlas(ColorType::Rgba8, &[200, 100, 50, 128, 10, 20, 30, 0]);
+    let premultiplied = straight.convert_alpha_mode(AlphaMode::Premultiplied);
+    for (atlas, expected) in [(straight, None), (premultiplied, Some("Premultiplied"))].iter() {
+        let multi_atlas = MultiTextureAtlas2D::new(vec![atlas.clone()]);
+        let mut cursor = io::Cursor::new(vec![]);
+        tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+        let mut zip_reader = zip::ZipArchive::new(cursor).unwrap();
+        let charts: serde_json::Value = serde_json::from_reader(zip_reader.by_name("atlas.json").unwrap()).unwrap();
+
+        assert_eq!(charts.get("alpha_mode").and_then(|alpha_mode| alpha_mode.as_str()), *expected);
+    }
+}
+
+/// The builder and the dynamic atlas premultiply the straight alpha textures added to
+/// a premultiplied atlas.
+#[test]
+fn premultiplied_atlases_premultiply_added_textures() {
+    let atlas = TextureAtlas2DBuilder::new("atlas", 2, 1)
+        .alpha_mode(AlphaMode::Premultiplied)
+        .add_image("pixel", 1, 1, ColorType::Rgba8, &[200, 100, 50, 128])
+        .build()
+        .unwrap();
+    let mut dynamic_atlas = DynamicTextureAtlas2D::new(atlas);
+    dynamic_atlas.insert("other", 1, 1, ColorType::Rgba8, &[10, 20, 30, 0]).unwrap();
+    let atlas = dynamic_atlas.into_atlas();
+
+    assert_eq!(atlas.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(atlas.as_bytes(), &[100, 50, 25, 128, 0, 0, 0, 0]);
+}
@@ -0,0 +1,223 @@
+mod common;
+
+use common::{
+    pixel_at,
+    solid_image,
+    top_left_pixel,
+};
+use tex_atlas::packer::{
+    GuillotineHeuristic,
+    GuillotineSplit,
+    MaxRectsHeuristic,
+    PackingAlgorithm,
+    SkylineHeuristic,
+};
+use tex_atlas::{
+    ColorType,
+    ErrorKind,
+    OffsetPixelCoords,
+    Origin,
+    TextureAtlas2DBuilder,
+    MultiTextureAtlas2D,
+};
+use std::io;
+
+
+/// Four 8 x 8 solid color textures that exactly fill a 16 x 16 atlas.
+fn builder(origin: Origin) -> TextureAtlas2DBuilder {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 16);
+    builder
+        .origin(origin)
+        .add_image("red", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [255, 0, 0, 255]))
+        .add_image("green", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 255, 0, 255]))
+        .add_image("blue", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 0, 255, 255]))
+        .add_image("black", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 0, 0, 255]));
+
+    builder
+}
+
+/// The builder should place every texture it is given into the atlas.
+#[test]
+fn builder_packs_every_texture() {
+    let atlas = builder(Origin::BottomLeft).build().unwrap();
+
+    assert_eq!(atlas.texture_count(), 4);
+    for name in ["red", "green", "blue", "black"].iter() {
+        assert!(atlas.by_texture_name(name).is_some());
+    }
+}
+
+/// The index of each texture in the atlas is the order in which it was added to the builder.
+#[test]
+fn builder_assigns_indices_in_insertion_order() {
+    let atlas = builder(Origin::BottomLeft).build().unwrap();
+
+    assert_eq!(atlas.by_index(0), atlas.by_texture_name("red"));
+    assert_eq!(atlas.by_index(3), atlas.by_texture_name("black"));
+}
+
+/// The bounding box of each texture should point at the pixels of that texture for
+/// either choice of origin.
+#[test]
+fn builder_bounding_boxes_point_at_texture_pixels() {
+    for origin in [Origin::BottomLeft, Origin::TopLeft].iter() {
+        let atlas = builder(*origin).build().unwrap();
+
+        assert_eq!(atlas.origin(), *origin);
+        assert_eq!(top_left_pixel(&atlas, "red"), [255, 0, 0, 255]);
+        assert_eq!(top_left_pixel(&atlas, "green"), [0, 255, 0, 255]);
+        assert_eq!(top_left_pixel(&atlas, "blue"), [0, 0, 255, 255]);
+        assert_eq!(top_left_pixel(&atlas, "black"), [0, 0, 0, 255]);
+    }
+}
+
+/// The builder converts every texture to the color type of the atlas.
+#[test]
+fn builder_converts_textures_to_the_atlas_color_type() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 4, 4);
+    builder
+        .add_image("gray", 2, 2, ColorType::L8, &[128, 128, 128, 128])
+        .add_dynamic_image("white", &image::DynamicImage::new_rgb8(2, 2));
+    let atlas = builder.build().unwrap();
+
+    assert_eq!(atlas.color_type, ColorType::Rgba8);
+    assert_eq!(atlas.len_bytes(), 4 * 4 * 4);
+    assert_eq!(top_left_pixel(&atlas, "gray"), [128, 128, 128, 255]);
+}
+
+/// The choice of packing algorithm determines which sets of textures fit in the atlas.
+#[test]
+fn builder_packs_with_the_chosen_algorithm() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 16);
+    builder
+        .add_image("tall", 4, 16, ColorType::L8, &[0; 4 * 16])
+        .add_image("square", 12, 12, ColorType::L8, &[0; 12 * 12])
+        .add_image("wide", 12, 4, ColorType::L8, &[0; 12 * 4]);
+
+    builder.algorithm(PackingAlgorithm::Shelf);
+    assert!(builder.build().is_err());
+    builder.algorithm(PackingAlgorithm::MaxRects(MaxRectsHeuristic::ContactPoint));
+    assert!(builder.build().is_ok());
+}
+
+/// Every packing algorithm produces an atlas whose bounding boxes point at the pixels 
+/// of the corresponding textures.
+#[test]
+fn builder_packs_textures_with_every_algorithm() {
+    let algorithms = [
+        PackingAlgorithm::Shelf,
+        PackingAlgorithm::MaxRects(MaxRectsHeuristic::BottomLeft),
+        PackingAlgorithm::Skyline(SkylineHeuristic::BottomLeft),
+        PackingAlgorithm::Guillotine {
+            heuristic: GuillotineHeuristic::BestAreaFit,
+            split: GuillotineSplit::ShorterLeftoverAxis,
+            merge: true,
+        },
+    ];
+    for algorithm in algorithms.iter() {
+        let atlas = builder(Origin::BottomLeft).algorithm(*algorithm).build().unwrap();
+
+        assert_eq!(top_left_pixel(&atlas, "red"), [255, 0, 0, 255]);
+        assert_eq!(top_left_pixel(&atlas, "green"), [0, 255, 0, 255]);
+        assert_eq!(top_left_pixel(&atlas, "blue"), [0, 0, 255, 255]);
+        assert_eq!(top_left_pixel(&atlas, "black"), [0, 0, 0, 255]);
+    }
+}
+
+/// A texture larger than the atlas cannot be packed.
+#[test]
+fn builder_rejects_textures_larger_than_the_atlas() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 4, 4);
+    builder.add_image("huge", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 0, 0, 0]));
+    let result = builder.build();
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TextureDoesNotFitInAtlas);
+}
+
+/// Texture names inside an atlas must be unique.
+#[test]
+fn builder_rejects_duplicate_texture_names() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 16);
+    builder
+        .add_image("texture", 2, 2, ColorType::L8, &[0; 4])
+        .add_image("texture", 2, 2, ColorType::L8, &[0; 4]);
+    let result = builder.build();
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::DuplicateTextureName);
+}
+
+/// An image buffer whose length does not match its dimensions is rejected.
+#[test]
+fn builder_rejects_invalid_image_data() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 16);
+    builder.add_image("texture", 2, 2, ColorType::Rgba8, &[0; 4]);
+    let result = builder.build();
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidImageData);
+}
+
+/// A built atlas written out and read back in should have the same bounding boxes and
+/// pixels for either choice of origin.
+#[test]
+fn built_atlas_written_and_then_read_should_match() {
+    for origin in [Origin::BottomLeft, Origin::TopLeft].iter() {
+        let expected = builder(*origin).build().unwrap();
+        let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+        let mut cursor = io::Cursor::new(vec![]);
+        tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+        let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+        let result = result_multi_atlas.by_page_name("atlas").unwrap();
+
+        assert_eq!(result.origin(), expected.origin());
+        assert_eq!(result.as_bytes(), expected.as_bytes());
+        for name in expected.texture_names() {
+            assert_eq!(result.by_texture_name(name), expected.by_texture_name(name));
+        }
+    }
+}
+
+/// The bottom corners of an upright texture in an atlas with a top left origin sit below 
+/// its top corners, one row past the last row of the texture.
+#[test]
+fn builder_top_left_origin_texture_corners_grow_downward() {
+    let atlas = builder(Origin::TopLeft).build().unwrap();
+    for name in ["red", "green", "blue", "black"].iter() {
+        let bounding_box = atlas.by_texture_name(name).unwrap();
+        let corners = atlas.by_texture_name_corners(name).unwrap();
+        let corners_uv = atlas.by_texture_name_corners_uv(name).unwrap();
+        let top_left = bounding_box.top_left;
+
+        assert_eq!(atlas.by_texture_name_rotated(name), Some(false));
+        assert_eq!(corners.top_left, top_left);
+        assert_eq!(corners.top_right, OffsetPixelCoords { u: top_left.u + 8, v: top_left.v });
+        assert_eq!(corners.bottom_left, OffsetPixelCoords { u: top_left.u, v: top_left.v + 8 });
+        assert_eq!(corners.bottom_right, OffsetPixelCoords { u: top_left.u + 8, v: top_left.v + 8 });
+        assert_eq!(pixel_at(&atlas, corners.bottom_left.u, corners.bottom_left.v - 1), top_left_pixel(&atlas, name));
+        assert_eq!(corners_uv.bottom_left.v, (top_left.v + 8) as f32 / atlas.height as f32);
+    }
+}
+
+/// The corners of a texture that fills the atlas lie on the edges of the atlas for either
+/// choice of origin.
+#[test]
+fn builder_texture_filling_the_atlas_has_corners_on_the_atlas_edges() {
+    for origin in [Origin::BottomLeft, Origin::TopLeft].iter() {
+        let mut builder = TextureAtlas2DBuilder::new("atlas", 4, 4);
+        builder
+            .origin(*origin)
+            .add_image("texture", 4, 4, ColorType::Rgba8, &solid_image(4, 4, [255, 0, 0, 255]));
+        let atlas = builder.build().unwrap();
+        let corners = atlas.by_texture_name_corners("texture").unwrap();
+        let corners_uv = atlas.by_texture_name_corners_uv("texture").unwrap();
+        let (top_v, bottom_v) = match origin {
+            Origin::BottomLeft => (3, 0),
+            Origin::TopLeft => (0, 4),
+        };
+
+        assert_eq!(corners.top_left, OffsetPixelCoords { u: 0, v: top_v });
+        assert_eq!(corners.top_right, OffsetPixelCoords { u: 4, v: top_v });
+        assert_eq!(corners.bottom_left, OffsetPixelCoords { u: 0, v: bottom_v });
+        assert_eq!(corners.bottom_right, OffsetPixelCoords { u: 4, v: bottom_v });
+        assert_eq!(corners_uv.bottom_left.v, bottom_v as f32 / 4.0);
+    }
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use common::{
+    scratch_directory,
+    write_image,
+};
+use tex_atlas::{
+    ColorSpace,
+    ColorType,
+};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{
+    Command,
+    Output,
+};
+
+
+/// Run the command line tool with the given arguments.
+fn tex_atlas(arguments: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tex-atlas")).args(arguments).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Pack a directory of sprites into an atlas file with the command line tool.
+fn packed_atlas(test_name: &str) -> (PathBuf, PathBuf) {
+    let directory = scratch_directory(test_name);
+    let sprites = directory.join("sprites");
+    write_image(&sprites.join("idle.png"), 4, 4, [255, 0, 0, 255]);
+    write_image(&sprites.join("walk").join("0.png"), 8, 4, [0, 255, 0, 255]);
+    let atlas_file = directory.join("sprites.atlas");
+    let output = tex_atlas(&[
+        "pack", sprites.to_str().unwrap(), "-o", atlas_file.to_str().unwrap(),
+        "--width", "16", "--height=16", "--origin", "top-left",
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    (directory, atlas_file)
+}
+
+/// Packing a directory writes an atlas file that the info command describes.
+#[test]
+fn cli_packs_directory_and_prints_info() {
+    let (_, atlas_file) = packed_atlas("cli_packs_directory_and_prints_info");
+    let multi_atlas = tex_atlas::load_file(&atlas_file).unwrap().multi_atlas;
+    let output = tex_atlas(&["info", atlas_file.to_str().unwrap()]);
+    let info = stdout(&output);
+
+    assert!(output.status.success());
+    assert_eq!(multi_atlas.pages()[0].width, 16);
+    assert_eq!(multi_atlas.pages()[0].texture_count(), 2);
+    assert!(info.contains("page sprites1: 16 x 16, Rgba8, 4 channels, 4 bytes per pixel, TopLeft"), "{}", info);
+    assert!(info.contains("idle"), "{}", info);
+    assert!(info.contains("walk/0"), "{}", info);
+}
+
+/// The info command prints a JSON report of the atlas file on request.
+#[test]
+fn cli_prints_info_as_json() {
+    let (_, atlas_file) = packed_atlas("cli_prints_info_as_json");
+    let output = tex_atlas(&["info", atlas_file.to_str().unwrap(), "--json"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(report["pages"][0]["name"], "sprites1");
+    assert_eq!(report["pages"][0]["textures"].as_array().unwrap().len(), 2);
+}
+
+/// Unpacking an atlas file writes its textures out as image files.
+#[test]
+fn cli_unpacks_atlas_file() {
+    let (directory, atlas_file) = packed_atlas("cli_unpacks_atlas_file");
+    let unpacked = directory.join("unpacked");
+    let output = tex_atlas(&["unpack", atlas_file.to_str().unwrap(), "-o", unpacked.to_str().unwrap()]);
+    let walk = image::open(unpacked.join("walk").join("0.png")).unwrap().into_rgba8();
+
+    assert!(output.status.success());
+    assert!(unpacked.join("idle.png").exists());
+    assert!(unpacked.join("manifest.json").exists());
+    assert_eq!(walk.dimensions(), (8, 4));
+    assert_eq!(walk.get_pixel(0, 0).0, [0, 255, 0, 255]);
+}
+
+/// Validating accepts a sound atlas file, and rejects a file that is not an atlas file.
+#[test]
+fn cli_validates_atlas_files() {
+    let (directory, atlas_file) = packed_atlas("cli_validates_atlas_files");
+    let not_an_atlas = directory.join("not_an_atlas.atlas");
+    fs::write(&not_an_atlas, "not an atlas").unwrap();
+    let valid = tex_atlas(&["validate", atlas_file.to_str().unwrap()]);
+    let invalid = tex_atlas(&["validate", not_an_atlas.to_str().unwrap()]);
+
+    assert!(valid.status.success());
+    assert_eq!(invalid.status.code(), Some(1));
+}
+
+/// Converting an atlas file changes the color type and color space of its pages.
+#[test]
+fn cli_converts_atlas_file() {
+    let (directory, atlas_file) = packed_atlas("cli_converts_atlas_file");
+    let converted_file = directory.join("converted.atlas");
+    let output = tex_atlas(&[
+        "convert", atlas_file.to_str().unwrap(), "-o", converted_file.to_str().unwrap(),
+        "--color-type", "rgb8", "--color-space", "linear", "--mipmaps",
+    ]);
+    let multi_atlas = tex_atlas::load_file(&converted_file).unwrap().multi_atlas;
+    let atlas = &multi_atlas.pages()[0];
+
+    assert!(output.status.success());
+    assert_eq!(atlas.color_type, ColorType::Rgb8);
+    assert_eq!(atlas.color_space(), ColorSpace::Linear);
+    assert_eq!(atlas.mip_level_count(), 5);
+}
+
+/// Converting an atlas file writes it out at exactly the output path, whatever its 
+/// file extension.
+#[test]
+fn cli_converts_atlas_file_to_the_given_path() {
+    let (directory, atlas_file) = packed_atlas("cli_converts_atlas_file_to_the_given_path");
+    let converted_file = directory.join("converted.zip");
+    let output = tex_atlas(&["convert", atlas_file.to_str().unwrap(), "-o", converted_file.to_str().unwrap()]);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(tex_atlas::load_file(&converted_file).is_ok());
+    assert!(!directory.join("converted.atlas").exists());
+}
+
+/// Packing with a multiple of sizing policy rounds the page dimensions up to multiples of 
+/// the given number of pixels.
+#[test]
+fn cli_packs_with_multiple_of_sizing_policy() {
+    let directory = scratch_directory("cli_packs_with_multiple_of_sizing_policy");
+    let sprites = directory.join("sprites");
+    write_image(&sprites.join("idle.png"), 4, 4, [255, 0, 0, 255]);
+    let atlas_file = directory.join("sprites.atlas");
+    let output = tex_atlas(&[
+        "pack", sprites.to_str().unwrap(), "-o", atlas_file.to_str().unwrap(), "--sizing", "multiple-of:6",
+    ]);
+    let multi_atlas = tex_atlas::load_file(&atlas_file).unwrap().multi_atlas;
+    let invalid = tex_atlas(&["pack", sprites.to_str().unwrap(), "-o", "unused.atlas", "--sizing", "multiple-of:0"]);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!((multi_atlas.pages()[0].width, multi_atlas.pages()[0].height), (6, 6));
+    assert_eq!(invalid.status.code(), Some(2));
+}
+
+/// Malformed command lines exit with a usage error.
+#[test]
+fn cli_rejects_malformed_command_lines() {
+    let unknown_command = tex_atlas(&["squash", "sprites"]);
+    let unknown_option = tex_atlas(&["info", "sprites.atlas", "--verbose"]);
+    let missing_output = tex_atlas(&["pack", "sprites"]);
+
+    assert_eq!(unknown_command.status.code(), Some(2));
+    assert_eq!(unknown_option.status.code(), Some(2));
+    assert_eq!(missing_output.status.code(), Some(2));
+}
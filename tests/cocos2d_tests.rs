@@ -0,0 +1,217 @@
+mod common;
+
+use common::{
+    exported_atlas_builder,
+    scratch_directory,
+    sheet_image,
+    BLUE,
+    GREEN,
+    RED,
+};
+use tex_atlas::{
+    AlphaMode,
+    Cocos2dPlistFormat,
+    ErrorKind,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+};
+use std::fs;
+
+
+const PLIST_VERSION3: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+    <dict>
+        <key>frames</key>
+        <dict>
+            <key>square &amp; co</key>
+            <dict>
+                <key>aliases</key>
+                <array>
+                    <string>square alias</string>
+                </array>
+                <key>spriteOffset</key>
+                <string>{0,-0.5}</string>
+                <key>spriteSize</key>
+                <string>{2,2}</string>
+                <key>spriteSourceSize</key>
+                <string>{4,5}</string>
+                <key>textureRect</key>
+                <string>{{0,0},{2,2}}</string>
+                <key>textureRotated</key>
+                <false/>
+            </dict>
+            <key>bar</key>
+            <dict>
+                <key>aliases</key>
+                <array/>
+                <key>spriteOffset</key>
+                <string>{0,0}</string>
+                <key>spriteSize</key>
+                <string>{2,1}</string>
+                <key>spriteSourceSize</key>
+                <string>{2,1}</string>
+                <key>textureRect</key>
+                <string>{{3,0},{2,1}}</string>
+                <key>textureRotated</key>
+                <true/>
+            </dict>
+            <key>bar_copy</key>
+            <dict>
+                <key>spriteOffset</key>
+                <string>{0,0}</string>
+                <key>spriteSize</key>
+                <string>{2,1}</string>
+                <key>spriteSourceSize</key>
+                <string>{2,1}</string>
+                <key>textureRect</key>
+                <string>{{3,0},{2,1}}</string>
+                <key>textureRotated</key>
+                <true/>
+            </dict>
+        </dict>
+        <key>metadata</key>
+        <dict>
+            <key>format</key>
+            <integer>3</integer>
+            <key>premultiplyAlpha</key>
+            <true/>
+            <key>size</key>
+            <string>{4,4}</string>
+            <key>textureFileName</key>
+            <string>sheet.png</string>
+        </dict>
+    </dict>
+</plist>
+"#;
+
+const PLIST_VERSION2: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+    <dict>
+        <key>frames</key>
+        <dict>
+            <key>square</key>
+            <dict>
+                <key>frame</key>
+                <string>{{0,0},{2,2}}</string>
+                <key>offset</key>
+                <string>{0,-0.5}</string>
+                <key>rotated</key>
+                <false/>
+                <key>sourceColorRect</key>
+                <string>{{1,2},{2,2}}</string>
+                <key>sourceSize</key>
+                <string>{4,5}</string>
+            </dict>
+            <key>bar</key>
+            <dict>
+                <key>frame</key>
+                <string>{{3,0},{2,1}}</string>
+                <key>offset</key>
+                <string>{-0.5,0}</string>
+                <key>rotated</key>
+                <true/>
+                <key>sourceSize</key>
+                <string>{3,1}</string>
+            </dict>
+        </dict>
+        <key>metadata</key>
+        <dict>
+            <key>format</key>
+            <integer>2</integer>
+            <key>textureFileName</key>
+            <string>sheet.png</string>
+        </dict>
+    </dict>
+</plist>
+"#;
+
+/// Importing a property list file in format version 3 indexes the textures in the order
+/// of the file, keeps their trimming and rotation, and turns both the listed aliases and
+/// repeated rectangles into aliases.
+#[test]
+fn cocos2d_imports_version3_plist_file() {
+    let atlas = tex_atlas::load_cocos2d_plist_from_memory(
+        PLIST_VERSION3.as_bytes(), &sheet_image(), "sheet", Origin::BottomLeft
+    ).unwrap().atlas;
+    let square = atlas.by_texture_name("square & co").unwrap();
+    let source = SourceImagePixelCoords { offset: OffsetPixelCoords { u: 1, v: 2 }, width: 4, height: 5 };
+
+    assert_eq!(atlas.texture_count(), 2);
+    assert_eq!(atlas.by_texture_name_index("square alias"), Some(0));
+    assert_eq!(atlas.by_texture_name_index("bar_copy"), Some(1));
+    assert_eq!((square.top_left.u, square.top_left.v, square.width, square.height), (0, 3, 2, 2));
+    assert_eq!(atlas.by_texture_name_source("square & co"), Some(source));
+    assert_eq!(atlas.by_texture_name_rotated("bar"), Some(true));
+    assert_eq!(atlas.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(atlas.extract("bar_copy").unwrap().as_bytes(), [GREEN, BLUE].concat().as_slice());
+    assert_eq!(atlas.extract("square alias").unwrap().as_bytes(), [RED; 4].concat().as_slice());
+}
+
+/// Importing a property list file in format version 2 places trimmed textures with the
+/// source color rectangle, or with the offset from the center when it is absent.
+#[test]
+fn cocos2d_imports_version2_plist_file() {
+    let atlas = tex_atlas::load_cocos2d_plist_from_memory(
+        PLIST_VERSION2.as_bytes(), &sheet_image(), "sheet", Origin::TopLeft
+    ).unwrap().atlas;
+    let square_source = SourceImagePixelCoords { offset: OffsetPixelCoords { u: 1, v: 2 }, width: 4, height: 5 };
+    let bar_source = SourceImagePixelCoords { offset: OffsetPixelCoords { u: 0, v: 0 }, width: 3, height: 1 };
+
+    assert_eq!(atlas.texture_count(), 2);
+    assert_eq!(atlas.by_texture_name_source("square"), Some(square_source));
+    assert_eq!(atlas.by_texture_name_source("bar"), Some(bar_source));
+    assert_eq!(atlas.by_texture_name_rotated("bar"), Some(true));
+    assert_eq!(atlas.alpha_mode(), AlphaMode::Straight);
+    assert_eq!(atlas.extract("bar").unwrap().as_bytes(), [GREEN, BLUE].concat().as_slice());
+}
+
+/// Exporting in either format version writes a property list file and its atlas image,
+/// and importing them gives back the textures of the original atlas.
+#[test]
+fn cocos2d_export_round_trips() {
+    let directory = scratch_directory("cocos2d_export_round_trips");
+    let atlas = exported_atlas_builder("dot <copy>").build().unwrap();
+    for (format, file_name) in [(Cocos2dPlistFormat::Version2, "v2.plist"), (Cocos2dPlistFormat::Version3, "v3.plist")].iter() {
+        let plist_file = directory.join("export").join(file_name);
+        tex_atlas::export_cocos2d_plist(&atlas, &plist_file, *format).unwrap();
+        let text = fs::read_to_string(&plist_file).unwrap();
+        let imported = tex_atlas::load_cocos2d_plist_file(&plist_file, Origin::BottomLeft).unwrap().atlas;
+
+        assert!(text.contains("<string>{8,4}</string>"), "{}", text);
+        assert!(text.contains("<key>dot &lt;copy&gt;</key>") || text.contains("<string>dot &lt;copy&gt;</string>"), "{}", text);
+        assert_eq!(imported.texture_count(), atlas.texture_count());
+        for name in ["tall", "dot", "dot <copy>"].iter() {
+            assert_eq!(imported.by_texture_name(name), atlas.by_texture_name(name));
+            assert_eq!(imported.by_texture_name_index(name), atlas.by_texture_name_index(name));
+            assert_eq!(imported.by_texture_name_source(name), atlas.by_texture_name_source(name));
+            assert_eq!(imported.by_texture_name_rotated(name), atlas.by_texture_name_rotated(name));
+            assert_eq!(imported.extract(name).unwrap().as_bytes(), atlas.extract(name).unwrap().as_bytes());
+        }
+    }
+}
+
+/// Importing fails for malformed property list files, unsupported format versions, and
+/// textures outside of the atlas image.
+#[test]
+fn cocos2d_rejects_invalid_plist_files() {
+    let bad_rect = PLIST_VERSION3.replace("{{3,0},{2,1}}", "{{3,0},{2}}");
+    let outside = PLIST_VERSION3.replace("{{3,0},{2,1}}", "{{4,0},{2,1}}");
+    let version4 = PLIST_VERSION3.replace("<integer>3</integer>", "<integer>4</integer>");
+    let wrong_size = PLIST_VERSION3.replace("<string>{4,4}</string>", "<string>{8,8}</string>");
+    let duplicate = PLIST_VERSION3.replace("<string>square alias</string>", "<string>bar</string>");
+    let load = |plist: &str| {
+        tex_atlas::load_cocos2d_plist_from_memory(plist.as_bytes(), &sheet_image(), "sheet", Origin::TopLeft)
+            .err()
+            .unwrap()
+            .kind()
+    };
+
+    assert_eq!(load(&bad_rect), ErrorKind::CouldNotLoadCoordinateCharts);
+    assert_eq!(load(&outside), ErrorKind::TextureDoesNotFitInAtlas);
+    assert_eq!(load(&version4), ErrorKind::CouldNotLoadCoordinateCharts);
+    assert_eq!(load(&wrong_size), ErrorKind::InvalidImageData);
+    assert_eq!(load(&duplicate), ErrorKind::DuplicateTextureName);
+    assert_eq!(load("plist"), ErrorKind::CouldNotLoadCoordinateCharts);
+}
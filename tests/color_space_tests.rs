@@ -0,0 +1,137 @@
+mod common;
+
+use common::pixel_atlas;
+use tex_atlas::{
+    AlphaMode,
+    ColorSpace,
+    ColorType,
+    MultiTextureAtlas2D,
+    TextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+use std::io;
+use std::io::{
+    Read,
+    Write,
+};
+
+
+/// Write an atlas out to an in memory atlas file.
+fn write_atlas(atlas: &TextureAtlas2D) -> Vec<u8> {
+    let multi_atlas = MultiTextureAtlas2D::new(vec![atlas.clone()]);
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+
+    cursor.into_inner()
+}
+
+/// Read a file out of an in memory atlas file.
+fn read_zip_entry(atlas_file: &[u8], name: &str) -> Vec<u8> {
+    let mut zip_reader = zip::ZipArchive::new(io::Cursor::new(atlas_file)).unwrap();
+    let mut data = vec![];
+    zip_reader.by_name(name).unwrap().read_to_end(&mut data).unwrap();
+
+    data
+}
+
+/// Determine whether a PNG file contains a chunk of the given type.
+fn has_chunk(png_data: &[u8], chunk_type: &[u8]) -> bool {
+    png_data.windows(chunk_type.len()).any(|window| window == chunk_type)
+}
+
+/// Atlases are sRGB encoded unless stated otherwise.
+#[test]
+fn atlas_is_srgb_by_default() {
+    let atlas = pixel_atlas(ColorType::Rgba8, &[0; 8]);
+    let built_atlas = TextureAtlas2DBuilder::new("atlas", 1, 1)
+        .add_image("pixel", 1, 1, ColorType::Rgba8, &[0; 4])
+        .build()
+        .unwrap();
+
+    assert_eq!(atlas.color_space(), ColorSpace::Srgb);
+    assert_eq!(built_atlas.color_space(), ColorSpace::Srgb);
+}
+
+/// Decoding sRGB applies the inverse sRGB transfer function to the color channels, and
+/// leaves alpha alone. Encoding it again gives back the original pixels.
+#[test]
+fn atlas_converts_srgb_to_linear_and_back() {
+    let atlas = pixel_atlas(ColorType::Rgba8, &[128, 0, 255, 128, 64, 188, 10, 255]);
+    let linear = atlas.convert_color_space(ColorSpace::Linear);
+    let srgb = linear.convert_color_space(ColorSpace::Srgb);
+
+    assert_eq!(linear.color_space(), ColorSpace::Linear);
+    assert_eq!(linear.as_bytes(), &[55, 0, 255, 128, 13, 128, 1, 255]);
+    assert_eq!(srgb.color_space(), ColorSpace::Srgb);
+    assert_eq!(srgb.convert_color_space(ColorSpace::Srgb).as_bytes(), srgb.as_bytes());
+    assert_eq!(&srgb.as_bytes()[..4], &[128, 0, 255, 128]);
+}
+
+/// Converting the color space of a premultiplied atlas converts the straight colors,
+/// rather than the colors multiplied by alpha.
+#[test]
+fn atlas_converts_color_space_of_premultiplied_colors() {
+    let mut atlas = pixel_atlas(ColorType::La8, &[128, 128, 128, 255]);
+    atlas.set_alpha_mode(AlphaMode::Premultiplied);
+    let linear = atlas.convert_color_space(ColorSpace::Linear);
+
+    assert_eq!(linear.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(linear.as_bytes(), &[128, 128, 55, 255]);
+}
+
+/// The color space survives writing the atlas out and reading it back in, and the atlas
+/// image file declares it with the matching PNG chunks.
+#[test]
+fn linear_atlas_written_and_then_read_should_match_color_space() {
+    let mut expected = pixel_atlas(ColorType::Rgb8, &[1, 2, 3, 4, 5, 6]);
+    expected.set_color_space(ColorSpace::Linear);
+    let atlas_file = write_atlas(&expected);
+    let result_multi_atlas = tex_atlas::load_from_memory(&atlas_file).unwrap().multi_atlas;
+    let result = result_multi_atlas.by_page_name("atlas").unwrap();
+    let linear_png = read_zip_entry(&atlas_file, "atlas.png");
+    let srgb_png = read_zip_entry(&write_atlas(&pixel_atlas(ColorType::Rgb8, &[0; 6])), "atlas.png");
+
+    assert_eq!(result.color_space(), ColorSpace::Linear);
+    assert_eq!(result.as_bytes(), expected.as_bytes());
+    assert!(has_chunk(&linear_png, b"gAMA"));
+    assert!(!has_chunk(&linear_png, b"sRGB"));
+    assert!(has_chunk(&srgb_png, b"sRGB"));
+}
+
+/// The coordinate charts only record the color space of linear atlases, so sRGB atlas 
+/// files stay the same as before color spaces existed.
+#[test]
+fn atlas_charts_only_record_linear_color_space() {
+    let srgb = pixel_atlas(ColorType::Rgb8, &[1, 2, 3, 4, 5, 6]);
+    let mut linear = srgb.clone();
+    linear.set_color_space(ColorSpace::Linear);
+    for (atlas, expected) in [(srgb, None), (linear, Some("Linear"))].iter() {
+        let atlas_file = write_atlas(atlas);
+        let charts: serde_json::Value = serde_json::from_slice(&read_zip_entry(&atlas_file, "atlas.json")).unwrap();
+
+        assert_eq!(charts.get("color_space").and_then(|color_space| color_space.as_str()), *expected);
+    }
+}
+
+/// Atlas files whose coordinate charts do not record a color space get the color space
+/// their atlas image files declare.
+#[test]
+fn atlas_without_color_space_in_charts_should_use_png_color_space() {
+    let mut atlas = pixel_atlas(ColorType::Rgb8, &[1, 2, 3, 4, 5, 6]);
+    atlas.set_color_space(ColorSpace::Linear);
+    let atlas_file = write_atlas(&atlas);
+    let mut charts: serde_json::Value = serde_json::from_slice(&read_zip_entry(&atlas_file, "atlas.json")).unwrap();
+    charts.as_object_mut().unwrap().remove("color_space");
+    let png_data = read_zip_entry(&atlas_file, "atlas.png");
+
+    let mut zip_writer = zip::ZipWriter::new(io::Cursor::new(vec![]));
+    let options = zip::write::FileOptions::default();
+    zip_writer.start_file("atlas.json", options).unwrap();
+    zip_writer.write_all(serde_json::to_string(&charts).unwrap().as_bytes()).unwrap();
+    zip_writer.start_file("atlas.png", options).unwrap();
+    zip_writer.write_all(&png_data).unwrap();
+    let old_atlas_file = zip_writer.finish().unwrap().into_inner();
+    let result_multi_atlas = tex_atlas::load_from_memory(&old_atlas_file).unwrap().multi_atlas;
+
+    assert_eq!(result_multi_atlas.by_page_name("atlas").unwrap().color_space(), ColorSpace::Linear);
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use common::pixel_atlas;
+use tex_atlas::{
+    BoundingBoxPixelCoords,
+    ColorType,
+    LuminanceWeights,
+    MultiTextureAtlas2D,
+    OffsetPixelCoords,
+    Origin,
+    TextureAtlas2D,
+};
+use std::io;
+
+
+const COLOR_TYPES: [ColorType; 10] = [
+    ColorType::L8,
+    ColorType::La8,
+    ColorType::Rgb8,
+    ColorType::Rgba8,
+    ColorType::L16,
+    ColorType::La16,
+    ColorType::Rgb16,
+    ColorType::Rgba16,
+    ColorType::Bgr8,
+    ColorType::Bgra8,
+];
+
+/// Generate a deterministic image buffer where neighboring bytes differ, so that swapped
+/// channels or byte orders show up in comparisons.
+fn image_data(length: usize) -> Vec<u8> {
+    let mut state: u32 = 0x9E37_79B9;
+    (0..length).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state >> 24) as u8
+    }).collect()
+}
+
+/// A 4 x 4 atlas with two textures in it, with the given color type.
+fn atlas(color_type: ColorType) -> TextureAtlas2D {
+    let width = 4;
+    let height = 4;
+    let data = image_data(width * height * color_type.bytes_per_pixel());
+    let entries = vec![
+        (0, String::from("top"), BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u: 0, v: 3 }, width: 4, height: 2 }),
+        (1, String::from("bottom"), BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u: 0, v: 1 }, width: 4, height: 2 }),
+    ];
+
+    TextureAtlas2D::new(width, height, color_type, Origin::BottomLeft, entries, String::from("atlas"), data)
+}
+
+/// Writing out an atlas and reading it back in preserves the color type and the pixels
+/// of the atlas image exactly, for every color type.
+#[test]
+fn atlas_written_and_then_read_should_preserve_color_type() {
+    for color_type in COLOR_TYPES.iter() {
+        let expected = atlas(*color_type);
+        let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+        let mut cursor = io::Cursor::new(vec![]);
+        tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+        let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+        let result = result_multi_atlas.by_page_name("atlas").unwrap();
+
+        assert_eq!(result.color_type, *color_type);
+        assert_eq!(result.bytes_per_pixel, color_type.bytes_per_pixel());
+        assert_eq!(result.as_bytes(), expected.as_bytes(), "{:?}", color_type);
+    }
+}
+
+/// The coordinate charts only record the color type of atlases whose atlas image file 
+/// stores the pixels in a different color type.
+#[test]
+fn atlas_charts_only_record_color_types_png_images_cannot_store() {
+    for color_type in COLOR_TYPES.iter() {
+        let multi_atlas = MultiTextureAtlas2D::new(vec![atlas(*color_type)]);
+        let mut cursor = io::Cursor::new(vec![]);
+        tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+        let mut zip_reader = zip::ZipArchive::new(cursor).unwrap();
+        let charts: serde_json::Value = serde_json::from_reader(zip_reader.by_name("atlas.json").unwrap()).unwrap();
+        let expected = match color_type {
+            ColorType::Bgr8 => Some("Bgr8"),
+            ColorType::Bgra8 => Some("Bgra8"),
+            _ => None,
+        };
+
+        assert_eq!(charts.get("color_type").and_then(|color_type| color_type.as_str()), expected);
+    }
+}
+
+/// Converting an 8 bit atlas into any color type and back leaves it unchanged, as long 
+/// as the intermediate color type has every channel of the original.
+#[test]
+fn atlas_converted_and_then_converted_back_should_match() {
+    let round_trips = [
+        (ColorType::Rgba8, ColorType::Rgba16),
+        (ColorType::Rgba8, ColorType::Bgra8),
+        (ColorType::Rgb8, ColorType::Bgr8),
+        (ColorType::Rgb8, ColorType::Rgba8),
+        (ColorType::L8, ColorType::Rgba8),
+        (ColorType::L8, ColorType::L16),
+        (ColorType::La8, ColorType::Rgba16),
+    ];
+    for (color_type, intermediate) in round_trips.iter() {
+        let expected = atlas(*color_type);
+        let result = expected.convert_to(*intermediate).convert_to(*color_type);
+
+        assert_eq!(result.color_type, *color_type);
+        assert_eq!(result.as_bytes(), expected.as_bytes(), "{:?} -> {:?}", color_type, intermediate);
+        assert_eq!(result.by_texture_name("top"), expected.by_texture_name("top"));
+    }
+}
+
+/// Converting between blue-green-red and red-green-blue orderings swaps the channels.
+#[test]
+fn atlas_converts_bgr_to_rgb() {
+    let atlas = pixel_atlas(ColorType::Bgra8, &[10, 20, 30, 40]);
+
+    assert_eq!(atlas.convert_to(ColorType::Rgba8).as_bytes(), &[30, 20, 10, 40]);
+    assert_eq!(atlas.convert_to(ColorType::Rgb8).as_bytes(), &[30, 20, 10]);
+}
+
+/// Narrowing 16 bit samples to 8 bits rounds to the nearest value.
+#[test]
+fn atlas_converts_16_bit_to_8_bit_with_rounding() {
+    let samples: [u16; 4] = [0, 128, 383, 65535];
+    let pixel: Vec<u8> = samples.iter().flat_map(|sample| sample.to_ne_bytes().to_vec()).collect();
+    let atlas = pixel_atlas(ColorType::Rgba16, &pixel);
+
+    assert_eq!(atlas.convert_to(ColorType::Rgba8).as_bytes(), &[0, 0, 1, 255]);
+}
+
+/// Converting color into grayscale weighs the channels by the luminance weights, and 
+/// gray pixels stay the same gray under any weights.
+#[test]
+fn atlas_converts_color_to_luminance_with_weights() {
+    let atlas = pixel_atlas(ColorType::Rgb8, &[255, 0, 0]);
+    let gray = pixel_atlas(ColorType::Rgb8, &[77, 77, 77]);
+    let red_only = LuminanceWeights { red: 1.0, green: 0.0, blue: 0.0 };
+    let unnormalized = LuminanceWeights { red: 2.0, green: 2.0, blue: 2.0 };
+
+    assert_eq!(atlas.convert_to(ColorType::L8).as_bytes(), &[54]);
+    assert_eq!(atlas.convert_to_with_weights(ColorType::L8, LuminanceWeights::REC_601).as_bytes(), &[76]);
+    assert_eq!(atlas.convert_to_with_weights(ColorType::La8, red_only).as_bytes(), &[255, 255]);
+    assert_eq!(gray.convert_to_with_weights(ColorType::L8, unnormalized).as_bytes(), &[77]);
+}
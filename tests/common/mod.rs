@@ -0,0 +1,110 @@
+// Each test crate uses only some of these fixtures.
+#![allow(dead_code)]
+
+use tex_atlas::{
+    BoundingBoxPixelCoords,
+    ColorType,
+    OffsetPixelCoords,
+    Origin,
+    TextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+
+pub const RED: [u8; 4] = [255, 0, 0, 255];
+pub const GREEN: [u8; 4] = [0, 255, 0, 255];
+pub const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+/// An RGBA image of the given dimensions filled with a single pixel.
+pub fn solid_image(width: usize, height: usize, pixel: [u8; 4]) -> Vec<u8> {
+    let mut data = vec![];
+    for _ in 0..(width * height) {
+        data.extend_from_slice(&pixel);
+    }
+
+    data
+}
+
+/// Get the pixel at the top left corner of a texture in an RGBA atlas.
+pub fn top_left_pixel(atlas: &TextureAtlas2D, name: &str) -> [u8; 4] {
+    let bounding_box = atlas.by_texture_name(name).unwrap();
+
+    pixel_at(atlas, bounding_box.top_left.u, bounding_box.top_left.v)
+}
+
+/// Get the pixel at a given column and row of the storage order of an RGBA atlas.
+pub fn pixel_at(atlas: &TextureAtlas2D, col: usize, row: usize) -> [u8; 4] {
+    let offset = (row * atlas.width + col) * 4;
+    let bytes = atlas.as_bytes();
+
+    [bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]
+}
+
+/// An atlas one pixel high holding the given pixels as a single texture.
+pub fn pixel_atlas(color_type: ColorType, pixels: &[u8]) -> TextureAtlas2D {
+    let width = pixels.len() / color_type.bytes_per_pixel();
+    let bounding_box = BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u: 0, v: 0 }, width, height: 1 };
+    let entries = vec![(0, String::from("pixels"), bounding_box)];
+
+    TextureAtlas2D::new(width, 1, color_type, Origin::TopLeft, entries, String::from("atlas"), pixels.to_vec())
+}
+
+/// Create a fresh scratch directory for a test.
+pub fn scratch_directory(test_name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("tex_atlas_{}_{}", test_name, std::process::id()));
+    if path.exists() {
+        fs::remove_dir_all(&path).unwrap();
+    }
+    fs::create_dir_all(&path).unwrap();
+
+    path
+}
+
+/// Write out a solid color RGBA image in the format given by the file extension.
+pub fn write_image(path: &Path, width: u32, height: u32, pixel: [u8; 4]) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let image = image::RgbaImage::from_pixel(width, height, image::Rgba(pixel));
+    image::DynamicImage::ImageRgba8(image).save(path).unwrap();
+}
+
+/// A 4 x 4 sheet image holding a 2 x 2 red square in the top left corner, and a 2 x 1
+/// texture with a green pixel followed by a blue pixel stored rotated clockwise in the
+/// top right corner.
+pub fn sheet_image() -> Vec<u8> {
+    let mut image = image::RgbaImage::new(4, 4);
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)].iter() {
+        image.put_pixel(*x, *y, image::Rgba(RED));
+    }
+    image.put_pixel(3, 0, image::Rgba(GREEN));
+    image.put_pixel(3, 1, image::Rgba(BLUE));
+    let mut png_data = vec![];
+    image::DynamicImage::ImageRgba8(image).write_to(&mut png_data, image::ImageOutputFormat::Png).unwrap();
+
+    png_data
+}
+
+/// A builder for an atlas with a bottom left origin, holding a texture that only fits
+/// rotated, and a trimmed texture with an alias of the given name.
+pub fn exported_atlas_builder(alias_name: &str) -> TextureAtlas2DBuilder {
+    let mut tall = vec![];
+    for i in 0..(2 * 8) {
+        tall.extend_from_slice(&[(i * 10) as u8, 0, 0, 255]);
+    }
+    let mut dot = vec![0; 4 * 4 * 4];
+    dot[(2 * 4 + 1) * 4..(2 * 4 + 2) * 4].copy_from_slice(&BLUE);
+    let mut builder = TextureAtlas2DBuilder::new("sheet", 8, 4);
+    builder
+        .allow_rotation(true)
+        .trim(true)
+        .alias_duplicates(true)
+        .add_image("tall", 2, 8, ColorType::Rgba8, &tall)
+        .add_image("dot", 4, 4, ColorType::Rgba8, &dot)
+        .add_image(alias_name, 4, 4, ColorType::Rgba8, &dot);
+
+    builder
+}
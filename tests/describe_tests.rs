@@ -0,0 +1,82 @@
+use tex_atlas::{
+    BoundingBoxPixelCoords,
+    ColorType,
+    MultiTextureAtlas2D,
+    OffsetPixelCoords,
+    Origin,
+    TextureAtlas2D,
+};
+
+
+/// A 4 x 2 atlas holding two textures side by side, with their indices out of order.
+fn two_texture_atlas(atlas_name: &str) -> TextureAtlas2D {
+    let left = BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u: 0, v: 0 }, width: 1, height: 2 };
+    let right = BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u: 1, v: 0 }, width: 3, height: 2 };
+    let entries = vec![
+        (7, String::from("right"), right),
+        (2, String::from("left"), left),
+    ];
+
+    TextureAtlas2D::new(4, 2, ColorType::Rgba8, Origin::TopLeft, entries, String::from(atlas_name), vec![0; 4 * 2 * 4])
+}
+
+/// Describing a multi texture atlas lists every page in order, and the textures of
+/// each page by index.
+#[test]
+fn describe_lists_pages_and_textures_in_order() {
+    let multi_atlas = MultiTextureAtlas2D::new(vec![two_texture_atlas("first"), two_texture_atlas("second")]);
+    let description = tex_atlas::describe(&multi_atlas);
+    let page = &description.pages[0];
+
+    assert_eq!(description.pages.len(), 2);
+    assert_eq!(page.name, "first");
+    assert_eq!(description.pages[1].name, "second");
+    assert_eq!((page.width, page.height), (4, 2));
+    assert_eq!((page.channel_count, page.bytes_per_pixel), (4, 4));
+    assert_eq!(page.origin, Origin::TopLeft);
+    assert_eq!(page.textures.iter().map(|texture| texture.index).collect::<Vec<usize>>(), vec![2, 7]);
+    assert_eq!(page.textures[1].name, "right");
+    assert_eq!(page.textures[1].bounding_box.width, 3);
+    assert_eq!(page.textures[1].bounding_box_uv.top_left.u, 0.25);
+    assert_eq!(page.textures[1].bounding_box_uv.width, 0.75);
+}
+
+/// The JSON report of a multi texture atlas holds the page and texture fields, and
+/// is the same every time the atlas is described.
+#[test]
+fn describe_to_json_is_stable() {
+    let multi_atlas = MultiTextureAtlas2D::new(vec![two_texture_atlas("atlas")]);
+    let json = tex_atlas::describe(&multi_atlas).to_json();
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let page = &report["pages"][0];
+    let texture = &page["textures"][0];
+
+    assert_eq!(json, tex_atlas::describe(&multi_atlas).to_json());
+    assert_eq!(page["name"], "atlas");
+    assert_eq!(page["width"], 4);
+    assert_eq!(page["height"], 2);
+    assert_eq!(page["channel_count"], 4);
+    assert_eq!(page["bytes_per_pixel"], 4);
+    assert_eq!(page["origin"], "TopLeft");
+    assert_eq!(texture["index"], 2);
+    assert_eq!(texture["name"], "left");
+    assert_eq!(texture["bounding_box"]["width"], 1);
+    assert_eq!(texture["bounding_box_uv"]["height"], 1.0);
+}
+
+/// The text table of a multi texture atlas has a header line for each page and a row
+/// for each texture, with the columns lined up.
+#[test]
+fn describe_to_table_lines_up_columns() {
+    let multi_atlas = MultiTextureAtlas2D::new(vec![two_texture_atlas("atlas")]);
+    let table = tex_atlas::describe(&multi_atlas).to_table();
+    let lines: Vec<&str> = table.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("page atlas: 4 x 2, Rgba8, 4 channels, 4 bytes per pixel, TopLeft"), "{}", table);
+    assert!(lines[1].starts_with("index  name"), "{}", table);
+    assert!(lines[2].starts_with("    2  left "), "{}", table);
+    assert!(lines[3].starts_with("    7  right"), "{}", table);
+    assert!(lines[3].contains("0.250000  0.000000  0.750000   1.000000"), "{}", table);
+    assert_eq!(lines[2].find("0.000000"), lines[3].find("0.250000"));
+}
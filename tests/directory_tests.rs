@@ -0,0 +1,71 @@
+mod common;
+
+use common::{
+    scratch_directory,
+    write_image,
+};
+use tex_atlas::{
+    DirectoryOptions,
+    ErrorKind,
+    TextureAtlas2DBuilder,
+};
+use std::fs;
+use std::path::PathBuf;
+
+
+/// A directory tree of sprites for a character, with a stray text file and a BMP image.
+fn sprite_directory(test_name: &str) -> PathBuf {
+    let directory = scratch_directory(test_name).join("hero");
+    write_image(&directory.join("idle.png"), 4, 4, [255, 0, 0, 255]);
+    write_image(&directory.join("walk").join("0.png"), 4, 4, [0, 255, 0, 255]);
+    write_image(&directory.join("walk").join("1.PNG"), 4, 4, [0, 0, 255, 255]);
+    write_image(&directory.join("shadow.bmp"), 4, 4, [0, 0, 0, 255]);
+    fs::write(directory.join("notes.txt"), "not an image").unwrap();
+
+    directory
+}
+
+/// Loading a directory names each texture after its relative path, and names the atlas
+/// after the directory.
+#[test]
+fn builder_from_directory_names_textures_by_relative_path() {
+    let directory = sprite_directory("relative_path");
+    let mut builder = TextureAtlas2DBuilder::from_directory(&directory).unwrap();
+    let atlas = builder.dimensions(16, 16).build().unwrap();
+    let mut names: Vec<&str> = atlas.texture_names().collect();
+    names.sort_unstable();
+
+    assert_eq!(atlas.atlas_name(), "hero");
+    assert_eq!(names, vec!["idle", "walk/0", "walk/1"]);
+    assert_eq!(atlas.by_index_name(1), Some("walk/0"));
+    fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+}
+
+/// The directory options control the separator, extension stripping, and which image
+/// formats get loaded.
+#[test]
+fn builder_from_directory_with_options() {
+    let directory = sprite_directory("options");
+    let options = DirectoryOptions {
+        separator: String::from("_"),
+        strip_extensions: false,
+        all_image_formats: true,
+    };
+    let mut builder = TextureAtlas2DBuilder::from_directory_with_options(&directory, &options).unwrap();
+    let atlas = builder.dimensions(16, 16).build().unwrap();
+    let mut names: Vec<&str> = atlas.texture_names().collect();
+    names.sort_unstable();
+
+    assert_eq!(names, vec!["idle.png", "shadow.bmp", "walk_0.png", "walk_1.PNG"]);
+    fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+}
+
+/// Loading a directory that does not exist fails.
+#[test]
+fn builder_from_nonexistent_directory_should_fail() {
+    let directory = scratch_directory("nonexistent").join("DoesNotExist");
+    let result = TextureAtlas2DBuilder::from_directory(&directory);
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::CouldNotReadDirectory);
+    fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+}
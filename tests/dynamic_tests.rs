@@ -0,0 +1,110 @@
+mod common;
+
+use common::{
+    solid_image,
+    top_left_pixel,
+};
+use tex_atlas::{
+    ColorType,
+    DynamicTextureAtlas2D,
+    ErrorKind,
+    MultiTextureAtlas2D,
+    Origin,
+    TextureAtlas2DBuilder,
+};
+use std::io;
+
+
+/// A 16 x 16 atlas with a single 8 x 8 red texture in it.
+fn dynamic_atlas(origin: Origin) -> DynamicTextureAtlas2D {
+    let atlas = TextureAtlas2DBuilder::new("atlas", 16, 16)
+        .origin(origin)
+        .add_image("red", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [255, 0, 0, 255]))
+        .build()
+        .unwrap();
+
+    DynamicTextureAtlas2D::new(atlas)
+}
+
+/// Inserting a texture places it in the free space of the atlas without disturbing the
+/// textures already there, and marks its bounding box as dirty.
+#[test]
+fn dynamic_atlas_inserts_into_free_space() {
+    for origin in [Origin::BottomLeft, Origin::TopLeft].iter() {
+        let mut dynamic_atlas = dynamic_atlas(*origin);
+        let bounding_box = dynamic_atlas
+            .insert("green", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 255, 0, 255]))
+            .unwrap();
+        let atlas = dynamic_atlas.atlas();
+
+        assert_ne!(atlas.by_texture_name("red"), Some(bounding_box));
+        assert_eq!(atlas.by_texture_name("green"), Some(bounding_box));
+        assert_eq!(atlas.by_index(1), Some(bounding_box));
+        assert_eq!(top_left_pixel(atlas, "red"), [255, 0, 0, 255]);
+        assert_eq!(top_left_pixel(atlas, "green"), [0, 255, 0, 255]);
+        assert_eq!(dynamic_atlas.take_dirty_rects(), vec![bounding_box]);
+        assert!(dynamic_atlas.dirty_rects().is_empty());
+    }
+}
+
+/// Inserting a texture fails when there is no free space left, or when the name is taken.
+#[test]
+fn dynamic_atlas_rejects_textures_that_do_not_fit() {
+    let mut dynamic_atlas = dynamic_atlas(Origin::BottomLeft);
+    for name in ["green", "blue", "black"].iter() {
+        dynamic_atlas.insert(name, 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 0, 0, 255])).unwrap();
+    }
+    let full = dynamic_atlas.insert("white", 1, 1, ColorType::Rgba8, &[255, 255, 255, 255]);
+    let duplicate = dynamic_atlas.insert("red", 1, 1, ColorType::Rgba8, &[255, 255, 255, 255]);
+
+    assert_eq!(dynamic_atlas.occupancy(), 1.0);
+    assert_eq!(full.unwrap_err().kind(), ErrorKind::TextureDoesNotFitInAtlas);
+    assert_eq!(duplicate.unwrap_err().kind(), ErrorKind::DuplicateTextureName);
+}
+
+/// Removing a texture frees its space for reuse, clears its pixels, and leaves the
+/// indices of the other textures alone.
+#[test]
+fn dynamic_atlas_reuses_removed_space() {
+    let mut dynamic_atlas = dynamic_atlas(Origin::BottomLeft);
+    for name in ["green", "blue", "black"].iter() {
+        dynamic_atlas.insert(name, 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 0, 0, 255])).unwrap();
+    }
+    let black_index = dynamic_atlas.atlas().by_texture_name_index("black");
+    dynamic_atlas.take_dirty_rects();
+    let removed = dynamic_atlas.remove("green").unwrap();
+
+    assert!(dynamic_atlas.remove("green").is_none());
+    assert_eq!(dynamic_atlas.atlas().by_texture_name("green"), None);
+    assert_eq!(dynamic_atlas.atlas().by_texture_name_index("black"), black_index);
+    assert_eq!(dynamic_atlas.take_dirty_rects(), vec![removed]);
+    let offset = (removed.top_left.v * 16 + removed.top_left.u) * 4;
+    assert_eq!(&dynamic_atlas.atlas().as_bytes()[offset..(offset + 4)], &[0, 0, 0, 0]);
+
+    let wide = dynamic_atlas.insert("wide", 8, 4, ColorType::Rgba8, &solid_image(8, 4, [255, 255, 0, 255])).unwrap();
+    assert_eq!(wide.top_left.u, removed.top_left.u);
+    assert_eq!(dynamic_atlas.atlas().by_texture_name_index("wide"), Some(1));
+    assert_eq!(top_left_pixel(dynamic_atlas.atlas(), "wide"), [255, 255, 0, 255]);
+    assert!(dynamic_atlas.insert("square", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 0, 0, 255])).is_err());
+    assert!(dynamic_atlas.insert("strip", 8, 4, ColorType::Rgba8, &solid_image(8, 4, [0, 0, 0, 255])).is_ok());
+}
+
+/// An atlas modified at runtime survives writing it out and reading it back in.
+#[test]
+fn dynamic_atlas_written_and_then_read_should_match() {
+    let mut dynamic_atlas = dynamic_atlas(Origin::BottomLeft);
+    dynamic_atlas.insert("green", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 255, 0, 255])).unwrap();
+    dynamic_atlas.insert("blue", 4, 4, ColorType::Rgba8, &solid_image(4, 4, [0, 0, 255, 255])).unwrap();
+    dynamic_atlas.remove("green");
+    let expected = dynamic_atlas.into_atlas();
+    let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+    let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+    let result = result_multi_atlas.by_page_name("atlas").unwrap();
+
+    assert_eq!(result.texture_count(), 2);
+    assert_eq!(result.by_texture_name("blue"), expected.by_texture_name("blue"));
+    assert_eq!(result.by_texture_name_index("blue"), Some(2));
+    assert_eq!(result.as_bytes(), expected.as_bytes());
+}
@@ -1,8 +1,15 @@
+#![allow(clippy::redundant_field_names)]
+
 use std::fs;
 use std::io;
 use std::path::Path;
 
-use tex_atlas;
+use tex_atlas::{
+    ColorType,
+    MultiTextureAtlas2D,
+    Origin,
+    TextureAtlas2DBuilder,
+};
 
 
 const SAMPLE_DATA: &str = "assets/example.atlas";
@@ -29,6 +36,36 @@ fn atlas_file_should_write_to_storage_successfully() {
     assert!(result.is_ok());
 }
 
+/// An atlas with a top left origin stores its top row first in the atlas image file, and
+/// reading the atlas file back gives the rows in the same order they were written in.
+#[test]
+fn atlas_file_with_top_left_origin_written_and_then_read_should_preserve_pixel_rows() {
+    let mut data = vec![];
+    for row in 0..4 {
+        for _ in 0..4 {
+            data.extend_from_slice(&[(row * 64) as u8, 0, 0, 255]);
+        }
+    }
+    let expected_atlas = TextureAtlas2DBuilder::new("atlas", 4, 4)
+        .origin(Origin::TopLeft)
+        .add_image("rows", 4, 4, ColorType::Rgba8, &data)
+        .build()
+        .unwrap();
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &MultiTextureAtlas2D::new(vec![expected_atlas.clone()])).unwrap();
+    let mut zip_reader = zip::ZipArchive::new(&mut cursor).unwrap();
+    let mut image_data = vec![];
+    io::Read::read_to_end(&mut zip_reader.by_name("atlas.png").unwrap(), &mut image_data).unwrap();
+    let image = image::load_from_memory(&image_data).unwrap().to_rgba8();
+    drop(zip_reader);
+    let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+    let result_atlas = result_multi_atlas.by_page_name("atlas").unwrap();
+
+    assert_eq!(image.as_raw().as_slice(), data.as_slice());
+    assert_eq!(expected_atlas.as_bytes(), data.as_slice());
+    assert_eq!(result_atlas.as_bytes(), data.as_slice());
+}
+
 
 struct ReadWriteTest {
     expected_multi_atlas: tex_atlas::MultiTextureAtlas2D,
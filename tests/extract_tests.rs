@@ -0,0 +1,75 @@
+use tex_atlas::{
+    ColorType,
+    Origin,
+    TextureAtlas2DBuilder,
+};
+
+
+/// Generate a deterministic image buffer where neighboring bytes differ, so that flipped
+/// or rotated images show up in comparisons.
+fn image_data(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+/// Extracting a texture gives back the image that was packed into the atlas, with its
+/// rows ordered from the top down, whatever the origin of the atlas.
+#[test]
+fn atlas_extracts_textures_by_name_and_index() {
+    let red = image_data(4 * 3 * 4);
+    let green: Vec<u8> = image_data(2 * 5 * 4).iter().map(|byte| byte.wrapping_add(100)).collect();
+    for origin in [Origin::BottomLeft, Origin::TopLeft].iter() {
+        let atlas = TextureAtlas2DBuilder::new("atlas", 8, 8)
+            .origin(*origin)
+            .add_image("red", 4, 3, ColorType::Rgba8, &red)
+            .add_image("green", 2, 5, ColorType::Rgba8, &green)
+            .build()
+            .unwrap();
+        let extracted_red = atlas.extract("red").unwrap();
+        let green_index = atlas.by_texture_name_index("green").unwrap();
+        let extracted_green = atlas.extract_by_index(green_index).unwrap();
+
+        assert_eq!((extracted_red.width, extracted_red.height), (4, 3));
+        assert_eq!(extracted_red.color_type, ColorType::Rgba8);
+        assert_eq!(extracted_red.as_bytes(), red.as_slice(), "{:?}", origin);
+        assert_eq!(extracted_green.as_bytes(), green.as_slice(), "{:?}", origin);
+        assert!(atlas.extract("blue").is_none());
+        assert!(atlas.extract_by_index(2).is_none());
+    }
+}
+
+/// Extracting a rotated texture rotates it back upright.
+#[test]
+fn atlas_extracts_rotated_textures_upright() {
+    let sprite = image_data(4 * 16 * 4);
+    for origin in [Origin::BottomLeft, Origin::TopLeft].iter() {
+        let atlas = TextureAtlas2DBuilder::new("atlas", 16, 8)
+            .origin(*origin)
+            .allow_rotation(true)
+            .add_image("sprite", 4, 16, ColorType::Rgba8, &sprite)
+            .build()
+            .unwrap();
+        let extracted = atlas.extract("sprite").unwrap();
+
+        assert_eq!(atlas.by_texture_name_rotated("sprite"), Some(true));
+        assert_eq!((extracted.width, extracted.height), (4, 16));
+        assert_eq!(extracted.as_bytes(), sprite.as_slice(), "{:?}", origin);
+    }
+}
+
+/// Extracted textures convert into image buffers of the underlying image library.
+#[test]
+fn extracted_texture_converts_to_image_buffer() {
+    let atlas = TextureAtlas2DBuilder::new("atlas", 2, 1)
+        .color_type(ColorType::La8)
+        .add_image("pixels", 2, 1, ColorType::La8, &[10, 255, 20, 128])
+        .build()
+        .unwrap();
+    let extracted = atlas.extract("pixels").unwrap();
+    let dynamic_image = extracted.to_dynamic_image();
+    let rgba_image = extracted.to_rgba_image();
+
+    assert_eq!(dynamic_image.color(), image::ColorType::La8);
+    assert_eq!(dynamic_image.as_bytes(), extracted.as_bytes());
+    assert_eq!(rgba_image.dimensions(), (2, 1));
+    assert_eq!(rgba_image.get_pixel(1, 0).0, [20, 20, 20, 128]);
+}
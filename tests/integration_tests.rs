@@ -1,4 +1,5 @@
-use tex_atlas;
+#![allow(clippy::identity_op, clippy::unnecessary_cast, clippy::useless_vec)]
+
 use tex_atlas::{
     ColorType, 
     Origin, 
@@ -0,0 +1,173 @@
+mod common;
+
+use common::{
+    exported_atlas_builder,
+    scratch_directory,
+    BLUE,
+    GREEN,
+    RED,
+};
+use tex_atlas::{
+    AlphaMode,
+    ColorType,
+    ErrorKind,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+};
+use std::fs;
+use std::path::Path;
+
+
+/// Write out a 4 x 4 page image holding a 2 x 2 red square in the top left corner, and
+/// a 2 x 1 texture with a green pixel followed by a blue pixel stored rotated
+/// counterclockwise in the top right corner.
+fn write_page_image(path: &Path) {
+    let mut image = image::RgbaImage::new(4, 4);
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)].iter() {
+        image.put_pixel(*x, *y, image::Rgba(RED));
+    }
+    image.put_pixel(3, 0, image::Rgba(BLUE));
+    image.put_pixel(3, 1, image::Rgba(GREEN));
+    image.save(path).unwrap();
+}
+
+const LEGACY_ATLAS: &str = "
+page.png
+size: 4, 4
+format: RGBA8888
+filter: Nearest, Nearest
+repeat: none
+square
+  rotate: false
+  xy: 0, 0
+  size: 2, 2
+  split: 0, 0, 0, 0
+  pad: 0, 0, 0, 0
+  orig: 4, 5
+  offset: 1, 1
+  index: -1
+bar
+  rotate: true
+  xy: 3, 0
+  size: 2, 1
+  orig: 2, 1
+  offset: 0, 0
+  index: 3
+
+second.png
+size: 4, 4
+format: RGBA8888
+filter: Linear, Linear
+repeat: xy
+square
+  rotate: false
+  xy: 0, 0
+  size: 2, 2
+  orig: 2, 2
+  offset: 0, 0
+  index: -1
+";
+
+/// Importing an atlas file in the older format gives a page for each page image, turns
+/// the offsets measured from the bottom of the original images around, turns rotated
+/// regions upright when extracted, and names indexed regions after their index.
+#[test]
+fn libgdx_imports_legacy_atlas_file() {
+    let directory = scratch_directory("libgdx_imports_legacy_atlas_file");
+    write_page_image(&directory.join("page.png"));
+    write_page_image(&directory.join("second.png"));
+    fs::write(directory.join("sprites.atlas"), LEGACY_ATLAS).unwrap();
+    let multi_atlas = tex_atlas::load_libgdx_atlas_file(directory.join("sprites.atlas"), Origin::TopLeft)
+        .unwrap()
+        .multi_atlas;
+    let atlas = &multi_atlas["page"];
+    let bar = atlas.by_texture_name("bar_3").unwrap();
+    let source = SourceImagePixelCoords { offset: OffsetPixelCoords { u: 1, v: 2 }, width: 4, height: 5 };
+
+    assert_eq!(multi_atlas.page_count(), 2);
+    assert_eq!(multi_atlas["second"].texture_count(), 1);
+    assert_eq!(atlas.by_texture_name_index("square"), Some(0));
+    assert_eq!(atlas.by_texture_name_index("bar_3"), Some(1));
+    assert_eq!(atlas.by_texture_name_source("square"), Some(source));
+    assert_eq!(atlas.by_texture_name_rotated("bar_3"), Some(true));
+    assert_eq!((bar.top_left.u, bar.top_left.v, bar.width, bar.height), (3, 0, 1, 2));
+    assert_eq!(atlas.extract("bar_3").unwrap().as_bytes(), [GREEN, BLUE].concat().as_slice());
+    assert_eq!(atlas.extract("square").unwrap().as_bytes(), [RED; 4].concat().as_slice());
+}
+
+/// Importing an atlas file in the newer format reads the bounds, offsets, rotations in
+/// degrees, and premultiplied alpha of its pages.
+#[test]
+fn libgdx_imports_newer_atlas_file() {
+    let directory = scratch_directory("libgdx_imports_newer_atlas_file");
+    write_page_image(&directory.join("page.png"));
+    let text = "page.png\nsize:4,4\nfilter:Linear,Linear\npma:true\nsquare\nbounds:0,0,2,2\noffsets:1,1,4,5\nbar\nbounds:3,0,2,1\nrotate:90\n";
+    fs::write(directory.join("sprites.atlas"), text).unwrap();
+    let multi_atlas = tex_atlas::load_libgdx_atlas_file(directory.join("sprites.atlas"), Origin::BottomLeft)
+        .unwrap()
+        .multi_atlas;
+    let atlas = &multi_atlas["page"];
+    let square = atlas.by_texture_name("square").unwrap();
+
+    assert_eq!(atlas.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!((square.top_left.u, square.top_left.v), (0, 3));
+    assert_eq!(atlas.by_texture_name_source("square").unwrap().offset, OffsetPixelCoords { u: 1, v: 2 });
+    assert_eq!(atlas.extract("bar").unwrap().as_bytes(), [GREEN, BLUE].concat().as_slice());
+}
+
+/// A multi texture atlas with a bottom left origin, holding a texture that only fits
+/// rotated, a trimmed texture with an alias, and a texture name ending in an index.
+fn exported_multi_atlas() -> tex_atlas::MultiTextureAtlas2D {
+    let mut builder = exported_atlas_builder("dot_copy");
+    builder.add_image("walk_1", 1, 1, ColorType::Rgba8, &GREEN);
+
+    builder.build_multi().unwrap()
+}
+
+/// Exporting writes an atlas file that libGDX reads, and importing it gives back the
+/// textures of the original atlas.
+#[test]
+fn libgdx_export_round_trips() {
+    let directory = scratch_directory("libgdx_export_round_trips");
+    let multi_atlas = exported_multi_atlas();
+    let atlas_file = directory.join("export").join("sprites.atlas");
+    tex_atlas::export_libgdx_atlas(&multi_atlas, &atlas_file).unwrap();
+    let text = fs::read_to_string(&atlas_file).unwrap();
+    let imported = tex_atlas::load_libgdx_atlas_file(&atlas_file, Origin::BottomLeft).unwrap().multi_atlas;
+    let atlas = &multi_atlas["sheet1"];
+    let imported_atlas = &imported["sheet1"];
+
+    assert!(directory.join("export").join("sheet1.png").exists());
+    assert!(text.starts_with("\nsheet1.png\nsize: 8, 4\nformat: RGBA8888\n"), "{}", text);
+    assert!(text.contains("walk\n  rotate: false\n"), "{}", text);
+    assert!(text.contains("  index: 1\n"), "{}", text);
+    assert!(text.contains("tall\n  rotate: true\n"), "{}", text);
+    assert!(text.contains("  orig: 4, 4\n  offset: 1, 1\n"), "{}", text);
+    for name in ["tall", "dot", "dot_copy", "walk_1"].iter() {
+        assert_eq!(imported_atlas.by_texture_name(name), atlas.by_texture_name(name));
+        assert_eq!(imported_atlas.by_texture_name_index(name), atlas.by_texture_name_index(name));
+        assert_eq!(imported_atlas.by_texture_name_source(name), atlas.by_texture_name_source(name));
+        assert_eq!(imported_atlas.by_texture_name_rotated(name), atlas.by_texture_name_rotated(name));
+        assert_eq!(imported_atlas.extract(name).unwrap().as_bytes(), atlas.extract(name).unwrap().as_bytes());
+    }
+}
+
+/// Loading an atlas file tells libGDX atlas files apart from the atlas files of this
+/// crate by their contents.
+#[test]
+fn load_file_detects_libgdx_atlas_files() {
+    let directory = scratch_directory("load_file_detects_libgdx_atlas_files");
+    let multi_atlas = exported_multi_atlas();
+    tex_atlas::export_libgdx_atlas(&multi_atlas, directory.join("libgdx.atlas")).unwrap();
+    tex_atlas::write_to_file(directory.join("native.atlas"), &multi_atlas).unwrap();
+    let broken = directory.join("broken.atlas");
+    fs::write(&broken, "page.png\nsize: 4, 4\nsquare\n  xy: 0, zero\n").unwrap();
+    let libgdx = tex_atlas::load_file(directory.join("libgdx.atlas")).unwrap().multi_atlas;
+    let native = tex_atlas::load_file(directory.join("native.atlas")).unwrap().multi_atlas;
+
+    assert_eq!(libgdx["sheet1"].origin(), Origin::BottomLeft);
+    assert_eq!(libgdx["sheet1"].by_texture_name("dot"), multi_atlas["sheet1"].by_texture_name("dot"));
+    assert_eq!(native["sheet1"].by_texture_name("dot"), multi_atlas["sheet1"].by_texture_name("dot"));
+    assert_eq!(tex_atlas::load_file(&broken).err().unwrap().kind(), ErrorKind::CouldNotLoadCoordinateCharts);
+}
@@ -0,0 +1,139 @@
+use tex_atlas::{
+    BoundingBoxPixelCoords,
+    ColorSpace,
+    ColorType,
+    DynamicTextureAtlas2D,
+    MultiTextureAtlas2D,
+    OffsetPixelCoords,
+    Origin,
+    TextureAtlas2D,
+};
+use std::io;
+
+
+/// Construct a linear atlas with the origin in the top left corner, so that the rows of
+/// the atlas image and its mip levels are stored from the top down.
+fn linear_atlas(
+    width: usize, height: usize, color_type: ColorType,
+    entries: Vec<(usize, String, BoundingBoxPixelCoords)>, data: Vec<u8>) -> TextureAtlas2D {
+
+    let mut atlas = TextureAtlas2D::new(
+        width, height, color_type, Origin::TopLeft, entries, String::from("atlas"), data
+    );
+    atlas.set_color_space(ColorSpace::Linear);
+
+    atlas
+}
+
+fn bounding_box(u: usize, v: usize, width: usize, height: usize) -> BoundingBoxPixelCoords {
+    BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u, v }, width, height }
+}
+
+/// Generating mipmaps gives a full chain of mip levels, halving the dimensions of the
+/// atlas image down to a single pixel.
+#[test]
+fn atlas_generates_full_mipmap_chain() {
+    let entries = vec![(0, String::from("all"), bounding_box(0, 0, 8, 2))];
+    let mut atlas = linear_atlas(8, 2, ColorType::L8, entries, vec![100; 16]);
+
+    assert_eq!(atlas.mip_level_count(), 1);
+    atlas.generate_mipmaps();
+
+    assert_eq!(atlas.mip_level_count(), 4);
+    assert_eq!(atlas.mip_level_dimensions(0), Some((8, 2)));
+    assert_eq!(atlas.mip_level_dimensions(1), Some((4, 1)));
+    assert_eq!(atlas.mip_level_dimensions(2), Some((2, 1)));
+    assert_eq!(atlas.mip_level_dimensions(3), Some((1, 1)));
+    assert_eq!(atlas.mip_level_dimensions(4), None);
+    assert_eq!(atlas.mip_level(3), Some(&[100][..]));
+}
+
+/// Neighboring textures never mix in the mip levels, even when their edges do not line
+/// up with the pixels of the smaller mip level.
+#[test]
+fn atlas_mipmaps_do_not_mix_neighboring_textures() {
+    let entries = vec![
+        (0, String::from("white"), bounding_box(0, 0, 3, 2)),
+        (1, String::from("black"), bounding_box(3, 0, 1, 2)),
+    ];
+    let data = vec![255, 255, 255, 0, 255, 255, 255, 0];
+    let mut atlas = linear_atlas(4, 2, ColorType::L8, entries, data);
+    atlas.generate_mipmaps();
+
+    assert_eq!(atlas.mip_level(1), Some(&[255, 0][..]));
+}
+
+/// The mip levels of sRGB atlases average colors in linear light, and the mip levels
+/// of atlases with straight alpha weigh colors by alpha.
+#[test]
+fn atlas_mipmaps_filter_in_linear_light_weighted_by_alpha() {
+    let entries = vec![(0, String::from("pixels"), bounding_box(0, 0, 2, 1))];
+    let mut srgb_atlas = linear_atlas(2, 1, ColorType::L8, entries.clone(), vec![0, 255]);
+    srgb_atlas.set_color_space(ColorSpace::Srgb);
+    srgb_atlas.generate_mipmaps();
+    let mut linear = linear_atlas(2, 1, ColorType::L8, entries.clone(), vec![0, 255]);
+    linear.generate_mipmaps();
+    let mut alpha_atlas = linear_atlas(2, 1, ColorType::Rgba8, entries, vec![255, 0, 0, 255, 0, 255, 0, 0]);
+    alpha_atlas.generate_mipmaps();
+
+    assert_eq!(srgb_atlas.mip_level(1), Some(&[188][..]));
+    assert_eq!(linear.mip_level(1), Some(&[128][..]));
+    assert_eq!(alpha_atlas.mip_level(1), Some(&[255, 0, 0, 128][..]));
+}
+
+/// A gutter repeats the edges of each texture into the empty space around it in every
+/// mip level.
+#[test]
+fn atlas_mipmaps_surround_textures_with_gutter() {
+    let entries = vec![(0, String::from("white"), bounding_box(0, 0, 2, 2))];
+    let mut data = vec![0; 8 * 8];
+    for row in 0..2 {
+        for column in 0..2 {
+            data[row * 8 + column] = 255;
+        }
+    }
+    let mut atlas = linear_atlas(8, 8, ColorType::L8, entries, data);
+    atlas.generate_mipmaps_with_gutter(1);
+
+    assert_eq!(&atlas.mip_level(1).unwrap()[..4], &[255, 255, 0, 0]);
+    assert_eq!(&atlas.mip_level(1).unwrap()[4..8], &[255, 255, 0, 0]);
+    assert_eq!(&atlas.mip_level(1).unwrap()[8..12], &[0, 0, 0, 0]);
+}
+
+/// The mip levels survive writing the atlas out and reading it back in, without being
+/// mistaken for pages of their own.
+#[test]
+fn atlas_mipmaps_written_and_then_read_should_match() {
+    let entries = vec![
+        (0, String::from("top"), bounding_box(0, 3, 4, 2)),
+        (1, String::from("bottom"), bounding_box(0, 1, 4, 2)),
+    ];
+    let data = (0..64).map(|i| (i * 4) as u8).collect();
+    let mut expected = TextureAtlas2D::new(
+        4, 4, ColorType::Rgba8, Origin::BottomLeft, entries, String::from("atlas"), data
+    );
+    expected.generate_mipmaps();
+    let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+    let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+    let result = result_multi_atlas.by_page_name("atlas").unwrap();
+
+    assert_eq!(result_multi_atlas.page_count(), 1);
+    assert_eq!(result.mip_level_count(), 3);
+    for level in 0..3 {
+        assert_eq!(result.mip_level(level), expected.mip_level(level));
+    }
+}
+
+/// Changing the textures of a dynamic atlas removes the mip levels that no longer match.
+#[test]
+fn dynamic_atlas_clears_stale_mipmaps() {
+    let entries = vec![(0, String::from("all"), bounding_box(0, 0, 2, 2))];
+    let mut atlas = linear_atlas(4, 4, ColorType::L8, entries, vec![255; 16]);
+    atlas.generate_mipmaps();
+    let mut dynamic_atlas = DynamicTextureAtlas2D::new(atlas);
+    dynamic_atlas.insert("other", 1, 1, ColorType::L8, &[0]).unwrap();
+
+    assert_eq!(dynamic_atlas.atlas().mip_level_count(), 1);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{
+    solid_image,
+    top_left_pixel,
+};
+use tex_atlas::{
+    ColorType,
+    ErrorKind,
+    TextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+use std::io;
+
+
+/// Ten 8 x 8 textures of different shades, more than a single 16 x 16 page can hold.
+fn overflowing_builder() -> TextureAtlas2DBuilder {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 16);
+    for i in 0..10 {
+        let shade = (i * 20) as u8;
+        builder.add_image(&format!("texture{}", i), 8, 8, ColorType::Rgba8, &solid_image(8, 8, [shade, shade, shade, 255]));
+    }
+
+    builder
+}
+
+/// Textures that overflow a page spill over onto new pages named after the builder.
+#[test]
+fn builder_overflows_onto_new_pages() {
+    let multi_atlas = overflowing_builder().build_multi().unwrap();
+
+    assert_eq!(multi_atlas.page_count(), 3);
+    for page_name in ["atlas1", "atlas2", "atlas3"].iter() {
+        assert!(multi_atlas.by_page_name(page_name).is_some());
+    }
+    let texture_count: usize = multi_atlas.pages().iter().map(|page| page.texture_count()).sum();
+    assert_eq!(texture_count, 10);
+}
+
+/// Every texture in a multi-page atlas lands on exactly one page, and its bounding box
+/// points at its pixels.
+#[test]
+fn builder_places_each_texture_on_exactly_one_page() {
+    let multi_atlas = overflowing_builder().build_multi().unwrap();
+    for i in 0..10 {
+        let name = format!("texture{}", i);
+        let pages: Vec<&TextureAtlas2D> = multi_atlas.pages()
+            .iter()
+            .filter(|page| page.by_texture_name(&name).is_some())
+            .collect();
+        let shade = (i * 20) as u8;
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(top_left_pixel(pages[0], &name), [shade, shade, shade, 255]);
+    }
+}
+
+/// A single page atlas cannot hold textures that overflow the page.
+#[test]
+fn builder_rejects_overflow_for_single_page_atlases() {
+    let result = overflowing_builder().build();
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TextureDoesNotFitInAtlas);
+}
+
+/// A texture larger than a page cannot be placed on any page.
+#[test]
+fn builder_rejects_textures_larger_than_a_page() {
+    let mut builder = overflowing_builder();
+    builder.add_image("huge", 32, 32, ColorType::Rgba8, &solid_image(32, 32, [0, 0, 0, 0]));
+    let result = builder.build_multi();
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TextureDoesNotFitInAtlas);
+}
+
+/// A multi-page atlas written out and read back in should have the same pages.
+#[test]
+fn built_multi_atlas_written_and_then_read_should_match() {
+    let expected = overflowing_builder().build_multi().unwrap();
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &expected).unwrap();
+    let result = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+
+    assert_eq!(result.page_count(), expected.page_count());
+    for expected_page in expected.pages() {
+        let result_page = result.by_page_name(expected_page.atlas_name()).unwrap();
+
+        assert_eq!(result_page.as_bytes(), expected_page.as_bytes());
+        for name in expected_page.texture_names() {
+            assert_eq!(result_page.by_texture_name(name), expected_page.by_texture_name(name));
+        }
+    }
+}
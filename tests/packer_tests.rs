@@ -0,0 +1,287 @@
+use tex_atlas::packer::{
+    GuillotineHeuristic,
+    GuillotinePacker,
+    GuillotineSplit,
+    MaxRectsHeuristic,
+    MaxRectsPacker,
+    PackedRect,
+    Packer,
+    PackingAlgorithm,
+    ShelfPacker,
+    SkylineHeuristic,
+    SkylinePacker,
+};
+
+
+const MAX_RECTS_HEURISTICS: [MaxRectsHeuristic; 5] = [
+    MaxRectsHeuristic::BestShortSideFit,
+    MaxRectsHeuristic::BestLongSideFit,
+    MaxRectsHeuristic::BestAreaFit,
+    MaxRectsHeuristic::BottomLeft,
+    MaxRectsHeuristic::ContactPoint,
+];
+
+const GUILLOTINE_HEURISTICS: [GuillotineHeuristic; 6] = [
+    GuillotineHeuristic::BestAreaFit,
+    GuillotineHeuristic::BestShortSideFit,
+    GuillotineHeuristic::BestLongSideFit,
+    GuillotineHeuristic::WorstAreaFit,
+    GuillotineHeuristic::WorstShortSideFit,
+    GuillotineHeuristic::WorstLongSideFit,
+];
+
+const GUILLOTINE_SPLITS: [GuillotineSplit; 6] = [
+    GuillotineSplit::ShorterLeftoverAxis,
+    GuillotineSplit::LongerLeftoverAxis,
+    GuillotineSplit::MinimizeArea,
+    GuillotineSplit::MaximizeArea,
+    GuillotineSplit::ShorterAxis,
+    GuillotineSplit::LongerAxis,
+];
+
+/// Generate a deterministic sequence of rectangle dimensions for the packing tests.
+fn rect_sizes(count: usize, max_side: usize) -> Vec<(usize, usize)> {
+    let mut state: u32 = 0x2545_F491;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state as usize % max_side) + 1
+    };
+
+    (0..count).map(|_| (next(), next())).collect()
+}
+
+fn overlaps(rect1: &PackedRect, rect2: &PackedRect) -> bool {
+    rect1.x < rect2.x + rect2.width && rect2.x < rect1.x + rect1.width &&
+    rect1.y < rect2.y + rect2.height && rect2.y < rect1.y + rect1.height
+}
+
+fn pack_all(packer: &mut dyn Packer, sizes: &[(usize, usize)]) -> Vec<PackedRect> {
+    sizes.iter().filter_map(|(width, height)| packer.insert(*width, *height)).collect()
+}
+
+fn assert_valid_packing(rects: &[PackedRect], width: usize, height: usize) {
+    for (i, rect) in rects.iter().enumerate() {
+        assert!(rect.x + rect.width <= width, "{:?}", rect);
+        assert!(rect.y + rect.height <= height, "{:?}", rect);
+        for other in rects[(i + 1)..].iter() {
+            assert!(!overlaps(rect, other), "{:?} overlaps {:?}", rect, other);
+        }
+    }
+}
+
+/// The rectangles placed by a shelf packer never overlap and stay inside the packing region.
+#[test]
+fn shelf_packer_places_disjoint_rectangles() {
+    let mut packer = ShelfPacker::new(256, 256);
+    let rects: Vec<PackedRect> = rect_sizes(200, 32)
+        .iter()
+        .filter_map(|(width, height)| packer.insert(*width, *height))
+        .collect();
+
+    assert!(!rects.is_empty());
+    assert_valid_packing(&rects, 256, 256);
+}
+
+/// A rectangle that does not fit into a shelf packer leaves the current shelf open for
+/// the rectangles that come after it.
+#[test]
+fn shelf_packer_keeps_the_current_shelf_open_after_a_failed_insert() {
+    let mut packer = ShelfPacker::new(16, 16);
+
+    assert_eq!(packer.insert(8, 8), Some(PackedRect::new(0, 0, 8, 8)));
+    assert!(packer.insert(10, 12).is_none());
+    assert_eq!(packer.insert(8, 8), Some(PackedRect::new(8, 0, 8, 8)));
+    assert_eq!(packer.insert(16, 8), Some(PackedRect::new(0, 8, 16, 8)));
+    assert_eq!(packer.occupancy(), 1.0);
+}
+
+/// The rectangles placed by a maximal rectangles packer never overlap and stay inside the
+/// packing region, for every heuristic.
+#[test]
+fn max_rects_packer_places_disjoint_rectangles() {
+    for heuristic in MAX_RECTS_HEURISTICS.iter() {
+        let mut packer = MaxRectsPacker::new(256, 256, *heuristic);
+        let rects: Vec<PackedRect> = rect_sizes(200, 32)
+            .iter()
+            .filter_map(|(width, height)| packer.insert(*width, *height))
+            .collect();
+
+        assert!(!rects.is_empty());
+        assert_valid_packing(&rects, 256, 256);
+    }
+}
+
+/// A maximal rectangles packer fills a region exactly when the rectangles tile it.
+#[test]
+fn max_rects_packer_fills_a_tiled_region() {
+    for heuristic in MAX_RECTS_HEURISTICS.iter() {
+        let mut packer = MaxRectsPacker::new(16, 16, *heuristic);
+
+        assert!(packer.insert(4, 16).is_some());
+        assert!(packer.insert(12, 12).is_some());
+        assert!(packer.insert(12, 4).is_some());
+        assert_eq!(packer.occupancy(), 1.0);
+        assert!(packer.insert(1, 1).is_none());
+    }
+}
+
+/// A maximal rectangles packer packs rectangles that a shelf packer cannot.
+#[test]
+fn max_rects_packer_packs_tighter_than_a_shelf_packer() {
+    let sizes = [(4, 16), (12, 12), (12, 4)];
+    let mut shelf_packer = ShelfPacker::new(16, 16);
+    let mut max_rects_packer = MaxRectsPacker::new(16, 16, MaxRectsHeuristic::BestShortSideFit);
+
+    assert!(!sizes.iter().all(|(width, height)| shelf_packer.insert(*width, *height).is_some()));
+    assert!(sizes.iter().all(|(width, height)| max_rects_packer.insert(*width, *height).is_some()));
+}
+
+/// A rectangle larger than the packing region does not fit.
+#[test]
+fn max_rects_packer_rejects_oversized_rectangles() {
+    let mut packer = MaxRectsPacker::new(16, 16, MaxRectsHeuristic::BestAreaFit);
+
+    assert!(packer.insert(17, 1).is_none());
+    assert!(packer.insert(1, 17).is_none());
+    assert_eq!(packer.occupancy(), 0.0);
+}
+
+/// The rectangles placed by a skyline packer never overlap and stay inside the packing
+/// region, for every heuristic.
+#[test]
+fn skyline_packer_places_disjoint_rectangles() {
+    for heuristic in [SkylineHeuristic::BottomLeft, SkylineHeuristic::MinWaste].iter() {
+        let mut packer = SkylinePacker::new(256, 256, *heuristic);
+        let rects = pack_all(&mut packer, &rect_sizes(200, 32));
+
+        assert!(!rects.is_empty());
+        assert_valid_packing(&rects, 256, 256);
+    }
+}
+
+/// A skyline packer fills a region exactly when the rectangles tile it row by row.
+#[test]
+fn skyline_packer_fills_a_tiled_region() {
+    let mut packer = SkylinePacker::new(16, 16, SkylineHeuristic::BottomLeft);
+    let rects = pack_all(&mut packer, &[(8, 8), (8, 8), (8, 8), (8, 8)]);
+
+    assert_eq!(rects.len(), 4);
+    assert_eq!(packer.occupancy(), 1.0);
+}
+
+/// The rectangles placed by a guillotine packer never overlap and stay inside the packing
+/// region, for every heuristic and split rule, with and without merging.
+#[test]
+fn guillotine_packer_places_disjoint_rectangles() {
+    for heuristic in GUILLOTINE_HEURISTICS.iter() {
+        for split in GUILLOTINE_SPLITS.iter() {
+            for merge in [false, true].iter() {
+                let mut packer = GuillotinePacker::new(256, 256, *heuristic, *split, *merge);
+                let rects = pack_all(&mut packer, &rect_sizes(200, 32));
+
+                assert!(!rects.is_empty());
+                assert_valid_packing(&rects, 256, 256);
+            }
+        }
+    }
+}
+
+/// Merging free rectangles lets a guillotine packer reuse space that would otherwise
+/// stay fragmented.
+#[test]
+fn guillotine_packer_merges_free_rectangles() {
+    let heuristic = GuillotineHeuristic::WorstAreaFit;
+    let split = GuillotineSplit::LongerLeftoverAxis;
+    let mut packer = GuillotinePacker::new(16, 16, heuristic, split, false);
+    let mut merging_packer = GuillotinePacker::new(16, 16, heuristic, split, true);
+    for packer in [&mut packer, &mut merging_packer].iter_mut() {
+        assert!(packer.insert(8, 8).is_some());
+        assert!(packer.insert(8, 8).is_some());
+    }
+
+    assert!(packer.insert(16, 8).is_none());
+    assert!(merging_packer.insert(16, 8).is_some());
+    assert_eq!(merging_packer.occupancy(), 1.0);
+}
+
+/// Every packing algorithm produces a packer for a region of the requested dimensions,
+/// so the algorithms can be compared on the same input.
+#[test]
+fn packing_algorithms_construct_packers_of_the_requested_size() {
+    let algorithms = [
+        PackingAlgorithm::Shelf,
+        PackingAlgorithm::MaxRects(MaxRectsHeuristic::BestAreaFit),
+        PackingAlgorithm::Skyline(SkylineHeuristic::MinWaste),
+        PackingAlgorithm::Guillotine {
+            heuristic: GuillotineHeuristic::BestShortSideFit,
+            split: GuillotineSplit::MinimizeArea,
+            merge: true,
+        },
+    ];
+    for algorithm in algorithms.iter() {
+        let mut packer = algorithm.packer(128, 64);
+
+        assert_eq!(packer.width(), 128);
+        assert_eq!(packer.height(), 64);
+        let rects = pack_all(packer.as_mut(), &rect_sizes(100, 16));
+        assert_valid_packing(&rects, 128, 64);
+        assert!(packer.occupancy() > 0.0);
+    }
+}
+
+/// Every packer rotates a rectangle that only fits into the packing region sideways, when 
+/// rotation is allowed.
+#[test]
+fn packers_rotate_rectangles_that_only_fit_sideways() {
+    let algorithms = [
+        PackingAlgorithm::Shelf,
+        PackingAlgorithm::MaxRects(MaxRectsHeuristic::BestShortSideFit),
+        PackingAlgorithm::Skyline(SkylineHeuristic::MinWaste),
+        PackingAlgorithm::Guillotine {
+            heuristic: GuillotineHeuristic::BestAreaFit,
+            split: GuillotineSplit::ShorterLeftoverAxis,
+            merge: false,
+        },
+    ];
+    for algorithm in algorithms.iter() {
+        let mut packer = algorithm.packer(16, 4);
+
+        assert!(packer.insert(4, 16).is_none());
+        let rect = packer.insert_rotatable(4, 16).unwrap();
+        assert!(rect.rotated);
+        assert_eq!((rect.width, rect.height), (16, 4));
+    }
+}
+
+/// A shelf packer that only fits a rectangle sideways on the current shelf places it 
+/// there, without opening a new shelf for the upright orientation first, whether or not
+/// the upright orientation fits on a new shelf.
+#[test]
+fn shelf_packer_rotates_rectangles_onto_the_current_shelf() {
+    for height in [10, 20].iter() {
+        let mut packer = ShelfPacker::new(16, *height);
+
+        assert!(packer.insert(12, 8).is_some());
+        let rect = packer.insert_rotatable(8, 4).unwrap();
+        assert!(rect.rotated);
+        assert_eq!((rect.x, rect.y, rect.width, rect.height), (12, 0, 4, 8));
+        assert_eq!(packer.insert(16, 2), Some(PackedRect::new(0, 8, 16, 2)));
+    }
+}
+
+/// A maximal rectangles packer reuses the space of a removed rectangle, including space
+/// that spans the removed rectangle and the free space next to it.
+#[test]
+fn max_rects_packer_reuses_removed_rectangles() {
+    let mut packer = MaxRectsPacker::new(16, 16, MaxRectsHeuristic::BestShortSideFit);
+    packer.occupy(PackedRect::new(0, 0, 8, 16));
+    let rect = packer.insert(8, 8).unwrap();
+
+    assert!(packer.insert(16, 8).is_none());
+    assert!(packer.remove(&rect));
+    assert!(!packer.remove(&rect));
+    assert!(packer.insert(8, 16).is_some());
+    assert_eq!(packer.occupancy(), 1.0);
+}
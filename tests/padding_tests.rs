@@ -0,0 +1,99 @@
+use tex_atlas::{
+    ColorType,
+    Origin,
+    TextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+
+
+/// Get the luminance at a given column and row of an L8 atlas.
+fn luminance_at(atlas: &TextureAtlas2D, u: usize, v: usize) -> u8 {
+    atlas.as_bytes()[v * atlas.width + u]
+}
+
+/// Four 6 x 6 textures of different shades packed with the given spacing options.
+fn padded_builder(padding: usize, border_padding: usize, extrude: usize) -> TextureAtlas2DBuilder {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 32, 32);
+    builder
+        .color_type(ColorType::L8)
+        .origin(Origin::TopLeft)
+        .padding(padding)
+        .border_padding(border_padding)
+        .extrude(extrude);
+    for i in 0..4 {
+        let shade = 50 * (i + 1) as u8;
+        builder.add_image(&format!("texture{}", i), 6, 6, ColorType::L8, &[shade; 36]);
+    }
+
+    builder
+}
+
+/// Neighboring textures are separated by at least the padding.
+#[test]
+fn builder_separates_textures_by_the_padding() {
+    let atlas = padded_builder(3, 0, 0).build().unwrap();
+    let boxes: Vec<_> = (0..4).map(|i| atlas.by_index(i).unwrap()).collect();
+    for (i, box1) in boxes.iter().enumerate() {
+        for box2 in boxes[(i + 1)..].iter() {
+            let separated_horizontally = 
+                box1.top_left.u + box1.width + 3 <= box2.top_left.u || 
+                box2.top_left.u + box2.width + 3 <= box1.top_left.u;
+            let separated_vertically = 
+                box1.top_left.v + box1.height + 3 <= box2.top_left.v || 
+                box2.top_left.v + box2.height + 3 <= box1.top_left.v;
+
+            assert!(separated_horizontally || separated_vertically, "{:?} {:?}", box1, box2);
+        }
+    }
+}
+
+/// Every texture stays at least the border padding away from the edges of the atlas image.
+#[test]
+fn builder_keeps_textures_inside_the_border_padding() {
+    let atlas = padded_builder(0, 5, 0).build().unwrap();
+    for i in 0..4 {
+        let bounding_box = atlas.by_index(i).unwrap();
+
+        assert!(bounding_box.top_left.u >= 5);
+        assert!(bounding_box.top_left.v >= 5);
+        assert!(bounding_box.top_left.u + bounding_box.width <= 32 - 5);
+        assert!(bounding_box.top_left.v + bounding_box.height <= 32 - 5);
+    }
+}
+
+/// Extrusion duplicates the edge pixels of each texture into the space around it, while the 
+/// bounding box still covers only the original texture.
+#[test]
+fn builder_extrudes_texture_edges() {
+    let atlas = padded_builder(0, 0, 2).build().unwrap();
+    for i in 0..4 {
+        let bounding_box = atlas.by_index(i).unwrap();
+        let shade = 50 * (i + 1) as u8;
+        let u = bounding_box.top_left.u;
+        let v = bounding_box.top_left.v;
+
+        assert_eq!(bounding_box.width, 6);
+        assert_eq!(bounding_box.height, 6);
+        assert!(u >= 2 && v >= 2);
+        // The corners of the extruded region.
+        assert_eq!(luminance_at(&atlas, u - 2, v - 2), shade);
+        assert_eq!(luminance_at(&atlas, u + 7, v + 7), shade);
+        // The middle of each edge of the extruded region.
+        assert_eq!(luminance_at(&atlas, u - 1, v + 3), shade);
+        assert_eq!(luminance_at(&atlas, u + 6, v + 3), shade);
+        assert_eq!(luminance_at(&atlas, u + 3, v - 1), shade);
+        assert_eq!(luminance_at(&atlas, u + 3, v + 6), shade);
+    }
+}
+
+/// Padding and extrusion take up space in the atlas, so textures that fit without them 
+/// may not fit with them.
+#[test]
+fn builder_accounts_for_padding_when_packing() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 16);
+    builder.add_image("texture", 16, 16, ColorType::L8, &[0; 256]);
+
+    assert!(builder.build().is_ok());
+    assert!(builder.border_padding(1).build().is_err());
+    assert!(builder.border_padding(0).extrude(1).build().is_err());
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::{
+    pixel_at,
+    solid_image,
+};
+use tex_atlas::{
+    ColorType,
+    ErrorKind,
+    MultiTextureAtlas2D,
+    Origin,
+    TextureAtlas2DBuilder,
+};
+use std::io;
+
+
+/// A tall sprite with a red top left pixel and a blue bottom right pixel, that only fits 
+/// into a wide atlas when it is rotated.
+fn rotating_builder(origin: Origin) -> TextureAtlas2DBuilder {
+    let mut sprite = solid_image(4, 16, [255, 255, 255, 255]);
+    sprite[0..4].copy_from_slice(&[255, 0, 0, 255]);
+    let last = sprite.len() - 4;
+    sprite[last..].copy_from_slice(&[0, 0, 255, 255]);
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 8);
+    builder
+        .origin(origin)
+        .allow_rotation(true)
+        .add_image("sprite", 4, 16, ColorType::Rgba8, &sprite);
+
+    builder
+}
+
+/// A texture that only fits into the atlas sideways gets rotated, and its bounding box 
+/// covers the rotated image.
+#[test]
+fn builder_rotates_textures_that_only_fit_sideways() {
+    let atlas = rotating_builder(Origin::BottomLeft).build().unwrap();
+    let bounding_box = atlas.by_texture_name("sprite").unwrap();
+
+    assert_eq!(atlas.by_texture_name_rotated("sprite"), Some(true));
+    assert_eq!(atlas.by_index_rotated(0), Some(true));
+    assert_eq!((bounding_box.width, bounding_box.height), (16, 4));
+}
+
+/// A texture that does not fit upright does not fit at all without rotation.
+#[test]
+fn builder_does_not_rotate_by_default() {
+    let result = rotating_builder(Origin::BottomLeft).allow_rotation(false).build();
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TextureDoesNotFitInAtlas);
+}
+
+/// The corners of a rotated texture are labeled by where they appear when the texture is
+/// upright, so each corner points at the matching corner pixel of the original image.
+#[test]
+fn builder_rotated_texture_corners_point_at_upright_corners() {
+    for origin in [Origin::BottomLeft, Origin::TopLeft].iter() {
+        let atlas = rotating_builder(*origin).build().unwrap();
+        let bounding_box = atlas.by_texture_name("sprite").unwrap();
+        let corners = atlas.by_texture_name_corners("sprite").unwrap();
+        let corners_uv = atlas.by_texture_name_corners_uv("sprite").unwrap();
+        let top_left = corners.top_left;
+        let bottom_right = corners.bottom_right;
+        let bottom_row = match origin {
+            Origin::BottomLeft => bottom_right.v,
+            Origin::TopLeft => bottom_right.v - 1,
+        };
+
+        assert_eq!(top_left.u, bounding_box.top_left.u + bounding_box.width);
+        assert_eq!(top_left.v, bounding_box.top_left.v);
+        assert_eq!(bottom_right.u, bounding_box.top_left.u);
+        assert_eq!(pixel_at(&atlas, top_left.u - 1, top_left.v), [255, 0, 0, 255]);
+        assert_eq!(pixel_at(&atlas, bottom_right.u, bottom_row), [0, 0, 255, 255]);
+        assert_eq!(corners_uv.top_left.u, top_left.u as f32 / atlas.width as f32);
+        assert_eq!(corners_uv.bottom_right.v, bottom_right.v as f32 / atlas.height as f32);
+    }
+}
+
+/// The rotation of each texture survives writing the atlas out and reading it back in.
+#[test]
+fn rotated_atlas_written_and_then_read_should_match_rotations() {
+    let expected = rotating_builder(Origin::BottomLeft).build().unwrap();
+    let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+    let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+    let result = result_multi_atlas.by_page_name("atlas").unwrap();
+
+    assert_eq!(result.by_texture_name_rotated("sprite"), Some(true));
+    assert_eq!(result.by_texture_name_corners("sprite"), expected.by_texture_name_corners("sprite"));
+}
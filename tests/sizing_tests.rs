@@ -0,0 +1,83 @@
+mod common;
+
+use common::{
+    solid_image,
+    top_left_pixel,
+};
+use tex_atlas::{
+    ColorType,
+    ErrorKind,
+    SizingPolicy,
+    TextureAtlas2DBuilder,
+    TextureAtlas2DWarning,
+};
+use std::io;
+
+
+/// Three small textures packed with a sizing policy into a page with awkward 
+/// maximum dimensions.
+fn sizing_builder(sizing_policy: SizingPolicy) -> TextureAtlas2DBuilder {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 100, 60);
+    builder
+        .sizing_policy(sizing_policy)
+        .add_image("red", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [255, 0, 0, 255]))
+        .add_image("green", 8, 8, ColorType::Rgba8, &solid_image(8, 8, [0, 255, 0, 255]))
+        .add_image("blue", 5, 3, ColorType::Rgba8, &solid_image(5, 3, [0, 0, 255, 255]));
+
+    builder
+}
+
+/// Each sizing policy shrinks the page to the smallest size satisfying it that holds 
+/// every texture.
+#[test]
+fn builder_sizes_pages_by_policy() {
+    let cases = [
+        (SizingPolicy::Fixed, (100, 60)),
+        (SizingPolicy::ExactFit, (8, 19)),
+        (SizingPolicy::MultipleOf(4), (8, 20)),
+        (SizingPolicy::PowerOfTwo, (16, 16)),
+        (SizingPolicy::SquarePowerOfTwo, (16, 16)),
+    ];
+    for (sizing_policy, expected) in cases.iter() {
+        let atlas = sizing_builder(*sizing_policy).build().unwrap();
+
+        assert_eq!((atlas.width, atlas.height), *expected, "{:?}", sizing_policy);
+        assert_eq!(atlas.texture_count(), 3);
+        assert_eq!(top_left_pixel(&atlas, "blue"), [0, 0, 255, 255]);
+    }
+}
+
+/// A multiple of pixels larger than the maximum dimensions of the pages leaves no page
+/// size to pack into.
+#[test]
+fn builder_rejects_multiples_larger_than_the_page() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 100, 60);
+    builder.sizing_policy(SizingPolicy::MultipleOf(64));
+
+    assert_eq!(builder.build().unwrap_err().kind(), ErrorKind::InvalidSizingPolicy);
+    assert_eq!(builder.build_multi().unwrap_err().kind(), ErrorKind::InvalidSizingPolicy);
+    assert!(builder.sizing_policy(SizingPolicy::MultipleOf(60)).build().is_ok());
+}
+
+/// The pages of a multi texture atlas packed with a power of two sizing policy never 
+/// trigger the power of two warning when they are read back in.
+#[test]
+fn power_of_two_atlas_written_and_then_read_should_not_warn() {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 20, 12);
+    builder.sizing_policy(SizingPolicy::PowerOfTwo);
+    for i in 0..5 {
+        let shade = 50 * i as u8;
+        builder.add_image(&format!("texture{}", i), 6, 6, ColorType::Rgba8, &solid_image(6, 6, [shade, shade, shade, 255]));
+    }
+    let multi_atlas = builder.build_multi().unwrap();
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+    let result = tex_atlas::from_reader(&mut cursor, "").unwrap();
+
+    assert!(multi_atlas.page_count() > 1);
+    for page in multi_atlas.pages() {
+        assert!(page.width.is_power_of_two() && page.height.is_power_of_two());
+        assert!(page.width <= 20 && page.height <= 12);
+    }
+    assert!(result.warnings.iter().all(|warning| *warning == TextureAtlas2DWarning::NoWarnings));
+}
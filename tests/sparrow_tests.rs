@@ -0,0 +1,90 @@
+mod common;
+
+use common::{
+    exported_atlas_builder,
+    scratch_directory,
+    sheet_image,
+    BLUE,
+    GREEN,
+    RED,
+};
+use tex_atlas::{
+    ErrorKind,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+};
+use std::fs;
+
+
+const ATLAS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<TextureAtlas imagePath="sheet.png" width="4" height="4">
+    <SubTexture name="square &amp; co" x="0" y="0" width="2" height="2" frameX="-1" frameY="-2" frameWidth="4" frameHeight="5"/>
+    <SubTexture name="bar" x="3" y="0" width="1" height="2" rotated="true"/>
+    <SubTexture name="bar_copy" x="3" y="0" width="1" height="2" rotated="true"/>
+</TextureAtlas>
+"#;
+
+/// Importing a Sparrow atlas file indexes the textures in the order of the atlas file,
+/// keeps their trimming and rotation, and turns repeated rectangles into aliases.
+#[test]
+fn sparrow_imports_atlas_file() {
+    let atlas = tex_atlas::load_sparrow_xml_from_memory(
+        ATLAS_XML.as_bytes(), &sheet_image(), "sheet", Origin::BottomLeft
+    ).unwrap().atlas;
+    let square = atlas.by_texture_name("square & co").unwrap();
+    let source = SourceImagePixelCoords { offset: OffsetPixelCoords { u: 1, v: 2 }, width: 4, height: 5 };
+
+    assert_eq!(atlas.texture_count(), 2);
+    assert_eq!(atlas.by_texture_name_index("square & co"), Some(0));
+    assert_eq!(atlas.by_texture_name_index("bar_copy"), Some(1));
+    assert_eq!((square.top_left.u, square.top_left.v, square.width, square.height), (0, 3, 2, 2));
+    assert_eq!(atlas.by_texture_name_source("square & co"), Some(source));
+    assert_eq!(atlas.by_texture_name_rotated("bar"), Some(true));
+    assert_eq!(atlas.extract("bar_copy").unwrap().as_bytes(), [GREEN, BLUE].concat().as_slice());
+    assert_eq!(atlas.extract("square & co").unwrap().as_bytes(), [RED; 4].concat().as_slice());
+}
+
+/// Exporting writes a Sparrow atlas file and its atlas image, and importing them gives
+/// back the textures of the original atlas.
+#[test]
+fn sparrow_export_round_trips() {
+    let directory = scratch_directory("sparrow_export_round_trips");
+    let atlas = exported_atlas_builder("dot <copy>").build().unwrap();
+    let xml_file = directory.join("export").join("sprites.xml");
+    tex_atlas::export_sparrow_xml(&atlas, &xml_file).unwrap();
+    let text = fs::read_to_string(&xml_file).unwrap();
+    let imported = tex_atlas::load_sparrow_xml_file(&xml_file, Origin::BottomLeft).unwrap().atlas;
+
+    assert!(directory.join("export").join("sprites.png").exists());
+    assert!(text.contains(r#"<TextureAtlas imagePath="sprites.png" width="8" height="4">"#), "{}", text);
+    assert!(text.contains(r#"name="dot &lt;copy&gt;""#), "{}", text);
+    assert!(text.contains(r#"frameX="-1" frameY="-2" frameWidth="4" frameHeight="4""#), "{}", text);
+    assert!(text.contains(r#"rotated="true""#), "{}", text);
+    assert_eq!(imported.atlas_name(), "sprites");
+    for name in ["tall", "dot", "dot <copy>"].iter() {
+        assert_eq!(imported.by_texture_name(name), atlas.by_texture_name(name));
+        assert_eq!(imported.by_texture_name_index(name), atlas.by_texture_name_index(name));
+        assert_eq!(imported.by_texture_name_source(name), atlas.by_texture_name_source(name));
+        assert_eq!(imported.by_texture_name_rotated(name), atlas.by_texture_name_rotated(name));
+        assert_eq!(imported.extract(name).unwrap().as_bytes(), atlas.extract(name).unwrap().as_bytes());
+    }
+}
+
+/// Importing fails for malformed atlas files and for textures outside of the atlas image.
+#[test]
+fn sparrow_rejects_invalid_atlas_files() {
+    let missing_attribute = ATLAS_XML.replace(r#"name="bar" x="3""#, r#"name="bar""#);
+    let outside = ATLAS_XML.replace(r#"name="bar" x="3""#, r#"name="bar" x="4""#);
+    let not_xml = "TextureAtlas";
+    let load = |xml: &str| {
+        tex_atlas::load_sparrow_xml_from_memory(xml.as_bytes(), &sheet_image(), "sheet", Origin::TopLeft)
+            .err()
+            .unwrap()
+            .kind()
+    };
+
+    assert_eq!(load(&missing_attribute), ErrorKind::CouldNotLoadCoordinateCharts);
+    assert_eq!(load(&outside), ErrorKind::TextureDoesNotFitInAtlas);
+    assert_eq!(load(not_xml), ErrorKind::CouldNotLoadCoordinateCharts);
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use common::{
+    exported_atlas_builder,
+    scratch_directory,
+    sheet_image,
+    BLUE,
+    GREEN,
+    RED,
+};
+use tex_atlas::{
+    ErrorKind,
+    OffsetPixelCoords,
+    Origin,
+    SourceImagePixelCoords,
+};
+use std::fs;
+
+
+const HASH_SHEET: &str = r#"{
+    "frames": {
+        "square.png": {
+            "frame": {"x": 0, "y": 0, "w": 2, "h": 2},
+            "rotated": false,
+            "trimmed": true,
+            "spriteSourceSize": {"x": 1, "y": 2, "w": 2, "h": 2},
+            "sourceSize": {"w": 4, "h": 5}
+        },
+        "bar.png": {
+            "frame": {"x": 3, "y": 0, "w": 2, "h": 1},
+            "rotated": true,
+            "trimmed": false,
+            "spriteSourceSize": {"x": 0, "y": 0, "w": 2, "h": 1},
+            "sourceSize": {"w": 2, "h": 1}
+        }
+    },
+    "meta": {"image": "sheet.png", "format": "RGBA8888", "size": {"w": 4, "h": 4}, "scale": "1"}
+}"#;
+
+/// Importing a sheet in the hash format indexes the frames in the order of the sheet
+/// file, and keeps the trimming and rotation of each frame.
+#[test]
+fn texturepacker_imports_hash_sheet() {
+    let result = tex_atlas::load_texturepacker_from_memory(
+        HASH_SHEET.as_bytes(), &sheet_image(), "sheet", Origin::TopLeft
+    ).unwrap();
+    let atlas = result.atlas;
+    let bar = atlas.by_texture_name("bar.png").unwrap();
+    let source = SourceImagePixelCoords { offset: OffsetPixelCoords { u: 1, v: 2 }, width: 4, height: 5 };
+
+    assert!(result.warnings == tex_atlas::TextureAtlas2DWarning::NoWarnings);
+    assert_eq!(atlas.atlas_name(), "sheet");
+    assert_eq!(atlas.by_texture_name_index("square.png"), Some(0));
+    assert_eq!(atlas.by_texture_name_index("bar.png"), Some(1));
+    assert_eq!(atlas.by_texture_name_source("square.png"), Some(source));
+    assert_eq!(atlas.by_texture_name_rotated("bar.png"), Some(true));
+    assert_eq!((bar.top_left.u, bar.top_left.v, bar.width, bar.height), (3, 0, 1, 2));
+    assert_eq!(atlas.extract("bar.png").unwrap().as_bytes(), [GREEN, BLUE].concat().as_slice());
+    assert_eq!(atlas.extract("square.png").unwrap().as_bytes(), [RED; 4].concat().as_slice());
+}
+
+/// Importing a sheet in the array format with a bottom left origin flips the pixel
+/// coordinates over, and turns frames covering the same rectangle into aliases.
+#[test]
+fn texturepacker_imports_array_sheet_with_bottom_left_origin() {
+    let sheet = r#"{
+        "frames": [
+            {"filename": "square", "frame": {"x": 0, "y": 0, "w": 2, "h": 2}, "rotated": false, "trimmed": false},
+            {"filename": "square_copy", "frame": {"x": 0, "y": 0, "w": 2, "h": 2}, "rotated": false, "trimmed": false}
+        ],
+        "meta": {"image": "sheet.png"}
+    }"#;
+    let atlas = tex_atlas::load_texturepacker_from_memory(
+        sheet.as_bytes(), &sheet_image(), "sheet", Origin::BottomLeft
+    ).unwrap().atlas;
+    let square = atlas.by_texture_name("square").unwrap();
+
+    assert_eq!(atlas.texture_count(), 1);
+    assert_eq!(atlas.by_texture_name_index("square_copy"), Some(0));
+    assert_eq!(atlas.by_index_aliases(0), Some(&[String::from("square_copy")][..]));
+    assert_eq!((square.top_left.u, square.top_left.v), (0, 3));
+    assert_eq!(atlas.extract("square_copy").unwrap().as_bytes(), [RED; 4].concat().as_slice());
+}
+
+/// Loading a multipack sheet file loads every related sheet file as a page.
+#[test]
+fn texturepacker_loads_multipack_sheets() {
+    let directory = scratch_directory("texturepacker_loads_multipack_sheets");
+    let first = HASH_SHEET.replace(
+        r#""scale": "1""#, r#""scale": "1", "related_multi_packs": ["sheet-1.json"]"#
+    );
+    let second = HASH_SHEET.replace(r#""image": "sheet.png""#, r#""image": "sheet-1.png""#);
+    fs::write(directory.join("sheet-0.json"), first).unwrap();
+    fs::write(directory.join("sheet-1.json"), second).unwrap();
+    fs::write(directory.join("sheet.png"), sheet_image()).unwrap();
+    fs::write(directory.join("sheet-1.png"), sheet_image()).unwrap();
+    let multi_atlas = tex_atlas::load_texturepacker_file(directory.join("sheet-0.json"), Origin::TopLeft)
+        .unwrap()
+        .multi_atlas;
+
+    assert_eq!(multi_atlas.page_count(), 2);
+    assert_eq!(multi_atlas.pages()[0].atlas_name(), "sheet-0");
+    assert_eq!(multi_atlas.pages()[1].atlas_name(), "sheet-1");
+    assert_eq!(multi_atlas["sheet-1"].texture_count(), 2);
+}
+
+/// The image file and the related sheet files of each sheet file of a multipack sheet
+/// are found relative to that sheet file, and each sheet file is loaded once.
+#[test]
+fn texturepacker_loads_multipack_sheets_relative_to_each_sheet() {
+    let directory = scratch_directory("texturepacker_loads_multipack_sheets_relative_to_each_sheet");
+    let pages_directory = directory.join("pages");
+    fs::create_dir_all(&pages_directory).unwrap();
+    let first = HASH_SHEET.replace(
+        r#""scale": "1""#, r#""scale": "1", "related_multi_packs": ["pages/sheet-1.json"]"#
+    );
+    let second = HASH_SHEET
+        .replace(r#""image": "sheet.png""#, r#""image": "sheet-1.png""#)
+        .replace(r#""scale": "1""#, r#""scale": "1", "related_multi_packs": ["sheet-2.json", "../sheet-0.json"]"#);
+    let third = HASH_SHEET.replace(r#""image": "sheet.png""#, r#""image": "sheet-2.png""#);
+    fs::write(directory.join("sheet-0.json"), first).unwrap();
+    fs::write(pages_directory.join("sheet-1.json"), second).unwrap();
+    fs::write(pages_directory.join("sheet-2.json"), third).unwrap();
+    fs::write(directory.join("sheet.png"), sheet_image()).unwrap();
+    fs::write(pages_directory.join("sheet-1.png"), sheet_image()).unwrap();
+    fs::write(pages_directory.join("sheet-2.png"), sheet_image()).unwrap();
+    let multi_atlas = tex_atlas::load_texturepacker_file(directory.join("sheet-0.json"), Origin::TopLeft)
+        .unwrap()
+        .multi_atlas;
+    let page_names: Vec<&str> = multi_atlas.pages().iter().map(|page| page.atlas_name()).collect();
+
+    assert_eq!(page_names, ["sheet-0", "sheet-1", "sheet-2"]);
+    assert_eq!(multi_atlas["sheet-2"].texture_count(), 2);
+}
+
+/// Importing fails for frames that lie outside of the sheet image, and for sheets
+/// missing their image file.
+#[test]
+fn texturepacker_rejects_invalid_sheets() {
+    let directory = scratch_directory("texturepacker_rejects_invalid_sheets");
+    let outside = HASH_SHEET.replace(r#""x": 3, "y": 0, "w": 2, "h": 1"#, r#""x": 3, "y": 0, "w": 5, "h": 1"#);
+    fs::write(directory.join("sheet.json"), HASH_SHEET).unwrap();
+    let outside_result = tex_atlas::load_texturepacker_from_memory(
+        outside.as_bytes(), &sheet_image(), "sheet", Origin::TopLeft
+    );
+    let missing_result = tex_atlas::load_texturepacker_file(directory.join("sheet.json"), Origin::TopLeft);
+
+    assert_eq!(outside_result.unwrap_err().kind(), ErrorKind::TextureDoesNotFitInAtlas);
+    assert_eq!(missing_result.err().unwrap().kind(), ErrorKind::MissingImageBuffer);
+}
+
+/// Exporting writes a sheet file in the hash format for each page, with the pixel
+/// coordinates measured from the top left corner of the sheet image, the frames in
+/// index order, and a frame for each alias.
+#[test]
+fn texturepacker_exports_hash_sheets() {
+    let directory = scratch_directory("texturepacker_exports_hash_sheets");
+    let multi_atlas = exported_atlas_builder("dot_copy").build_multi().unwrap();
+    tex_atlas::export_texturepacker_json(&multi_atlas, &directory).unwrap();
+    let atlas = multi_atlas.by_page_name("sheet1").unwrap();
+    let tall_index = atlas.by_texture_name_index("tall").unwrap();
+    let tall = atlas.by_texture_name("tall").unwrap();
+    let sheet_text = fs::read_to_string(directory.join("sheet1.json")).unwrap();
+    let sheet: serde_json::Value = serde_json::from_str(&sheet_text).unwrap();
+    let tall_frame = &sheet["frames"]["tall"];
+    let dot_frame = &sheet["frames"]["dot"];
+
+    assert_eq!(multi_atlas.page_count(), 1);
+    assert!(directory.join("sheet1.png").exists());
+    assert_eq!(sheet["meta"]["image"], "sheet1.png");
+    assert_eq!(sheet["meta"]["size"]["w"], 8);
+    assert_eq!(tall_frame["rotated"], true);
+    assert_eq!(tall_frame["frame"]["x"], tall.top_left.u);
+    assert_eq!(tall_frame["frame"]["y"], 4 - 1 - tall.top_left.v);
+    assert_eq!((&tall_frame["frame"]["w"], &tall_frame["frame"]["h"]), (&2.into(), &8.into()));
+    assert_eq!(dot_frame["trimmed"], true);
+    assert_eq!(dot_frame["spriteSourceSize"], serde_json::json!({"x": 1, "y": 2, "w": 1, "h": 1}));
+    assert_eq!(dot_frame["sourceSize"], serde_json::json!({"w": 4, "h": 4}));
+    assert_eq!(sheet["frames"]["dot_copy"], *dot_frame);
+    assert!(sheet_text.find("\"dot\"").unwrap() < sheet_text.find("\"dot_copy\"").unwrap());
+    assert_eq!(sheet_text.find("\"tall\"").unwrap() < sheet_text.find("\"dot\"").unwrap(), tall_index == 0);
+}
+
+/// Importing an exported sheet gives back the textures of the original atlas.
+#[test]
+fn texturepacker_export_round_trips() {
+    let directory = scratch_directory("texturepacker_export_round_trips");
+    let multi_atlas = exported_atlas_builder("dot_copy").build_multi().unwrap();
+    tex_atlas::export_texturepacker_json(&multi_atlas, &directory).unwrap();
+    let imported = tex_atlas::load_texturepacker_file(directory.join("sheet1.json"), Origin::BottomLeft)
+        .unwrap()
+        .multi_atlas;
+    let atlas = &multi_atlas["sheet1"];
+    let imported_atlas = &imported["sheet1"];
+
+    assert_eq!(imported.page_count(), 1);
+    for name in ["tall", "dot", "dot_copy"].iter() {
+        assert_eq!(imported_atlas.by_texture_name(name), atlas.by_texture_name(name));
+        assert_eq!(imported_atlas.by_texture_name_index(name), atlas.by_texture_name_index(name));
+        assert_eq!(imported_atlas.by_texture_name_source(name), atlas.by_texture_name_source(name));
+        assert_eq!(imported_atlas.by_texture_name_rotated(name), atlas.by_texture_name_rotated(name));
+        assert_eq!(imported_atlas.extract(name).unwrap().as_bytes(), atlas.extract(name).unwrap().as_bytes());
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{
+    solid_image,
+    top_left_pixel,
+};
+use tex_atlas::{
+    ColorType,
+    MultiTextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+use std::io;
+
+
+/// An 8x8 transparent image with an opaque 3x2 block whose top left corner is at (2, 4).
+fn sprite_with_transparent_border() -> Vec<u8> {
+    let mut data = solid_image(8, 8, [0, 0, 0, 0]);
+    for y in 4..6 {
+        for x in 2..5 {
+            let offset = (y * 8 + x) * 4;
+            data[offset..(offset + 4)].copy_from_slice(&[255, 255, 255, 255]);
+        }
+    }
+
+    data
+}
+
+/// A sprite with a transparent border, a fully transparent image, and an opaque image,
+/// packed with trimming enabled.
+fn trimming_builder() -> TextureAtlas2DBuilder {
+    let mut builder = TextureAtlas2DBuilder::new("atlas", 16, 16);
+    builder
+        .trim(true)
+        .add_image("sprite", 8, 8, ColorType::Rgba8, &sprite_with_transparent_border())
+        .add_image("empty", 4, 4, ColorType::Rgba8, &solid_image(4, 4, [0, 0, 0, 0]))
+        .add_image("opaque", 2, 2, ColorType::Rgb8, &[1; 12]);
+
+    builder
+}
+
+/// Trimming shrinks each texture down to its non-transparent pixels, and records the
+/// original image dimensions and the position of the trimmed texture inside it.
+#[test]
+fn builder_trims_transparent_borders() {
+    let atlas = trimming_builder().build().unwrap();
+    let bounding_box = atlas.by_texture_name("sprite").unwrap();
+    let source = atlas.by_texture_name_source("sprite").unwrap();
+
+    assert_eq!((bounding_box.width, bounding_box.height), (3, 2));
+    assert_eq!((source.width, source.height), (8, 8));
+    assert_eq!((source.offset.u, source.offset.v), (2, 4));
+    assert_eq!(top_left_pixel(&atlas, "sprite"), [255, 255, 255, 255]);
+}
+
+/// Trimming leaves a single pixel of a fully transparent image, and leaves images 
+/// without transparency alone.
+#[test]
+fn builder_trims_empty_and_opaque_textures() {
+    let atlas = trimming_builder().build().unwrap();
+    let empty = atlas.by_texture_name("empty").unwrap();
+    let empty_source = atlas.by_texture_name_source("empty").unwrap();
+    let opaque = atlas.by_texture_name("opaque").unwrap();
+    let opaque_source = atlas.by_index_source(2).unwrap();
+
+    assert_eq!((empty.width, empty.height), (1, 1));
+    assert_eq!((empty_source.width, empty_source.height), (4, 4));
+    assert_eq!((opaque.width, opaque.height), (2, 2));
+    assert_eq!((opaque_source.width, opaque_source.height), (2, 2));
+    assert_eq!((opaque_source.offset.u, opaque_source.offset.v), (0, 0));
+}
+
+/// Without trimming, every texture covers its whole original image.
+#[test]
+fn builder_does_not_trim_by_default() {
+    let atlas = trimming_builder().trim(false).build().unwrap();
+    let bounding_box = atlas.by_texture_name("sprite").unwrap();
+    let source = atlas.by_texture_name_source("sprite").unwrap();
+
+    assert_eq!((bounding_box.width, bounding_box.height), (8, 8));
+    assert_eq!((source.width, source.height), (8, 8));
+    assert_eq!((source.offset.u, source.offset.v), (0, 0));
+}
+
+/// The original image dimensions and trim offsets survive writing the atlas out and 
+/// reading it back in.
+#[test]
+fn trimmed_atlas_written_and_then_read_should_match_sources() {
+    let expected = trimming_builder().build().unwrap();
+    let multi_atlas = MultiTextureAtlas2D::new(vec![expected.clone()]);
+    let mut cursor = io::Cursor::new(vec![]);
+    tex_atlas::to_writer(&mut cursor, &multi_atlas).unwrap();
+    let result_multi_atlas = tex_atlas::from_reader(&mut cursor, "").unwrap().multi_atlas;
+    let result = result_multi_atlas.by_page_name("atlas").unwrap();
+    for name in expected.texture_names() {
+        assert_eq!(result.by_texture_name_source(name), expected.by_texture_name_source(name));
+    }
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{
+    scratch_directory,
+    solid_image,
+};
+use tex_atlas::{
+    BoundingBoxPixelCoords,
+    ColorType,
+    ErrorKind,
+    MultiTextureAtlas2D,
+    OffsetPixelCoords,
+    Origin,
+    TextureAtlas2D,
+    TextureAtlas2DBuilder,
+};
+use std::fs;
+
+
+/// A 1 x 1 atlas holding a single texture with the given name.
+fn pixel_atlas(atlas_name: &str, texture_name: &str) -> TextureAtlas2D {
+    let bounding_box = BoundingBoxPixelCoords { top_left: OffsetPixelCoords { u: 0, v: 0 }, width: 1, height: 1 };
+    let entries = vec![(0, String::from(texture_name), bounding_box)];
+
+    TextureAtlas2D::new(1, 1, ColorType::Rgba8, Origin::TopLeft, entries, String::from(atlas_name), vec![0; 4])
+}
+
+/// Unpacking a multi texture atlas writes every texture of every page out as an image
+/// file named after the texture, and a manifest listing the textures of each page.
+#[test]
+fn multi_atlas_unpacks_to_directory() {
+    let directory = scratch_directory("multi_atlas_unpacks_to_directory");
+    let mut gradient = vec![];
+    for i in 0..(3 * 2) {
+        gradient.extend_from_slice(&[(i * 40) as u8, 0, 0, 255]);
+    }
+    let multi_atlas = TextureAtlas2DBuilder::new("atlas", 4, 4)
+        .add_image("hero/idle", 3, 2, ColorType::Rgba8, &gradient)
+        .add_image("hero/walk", 4, 4, ColorType::Rgba8, &solid_image(4, 4, [0, 255, 0, 255]))
+        .build_multi()
+        .unwrap();
+    tex_atlas::unpack_to_directory(&multi_atlas, &directory).unwrap();
+    let idle = image::open(directory.join("hero").join("idle.png")).unwrap().into_rgba8();
+    let walk = image::open(directory.join("hero").join("walk.png")).unwrap().into_rgba8();
+    let manifest: serde_json::Value = serde_json::from_slice(
+        &fs::read(directory.join("manifest.json")).unwrap()
+    ).unwrap();
+    let pages = manifest["pages"].as_array().unwrap();
+
+    assert_eq!(multi_atlas.page_count(), 2);
+    assert_eq!(idle.dimensions(), (3, 2));
+    assert_eq!(idle.into_raw(), gradient);
+    assert_eq!(walk.get_pixel(3, 3).0, [0, 255, 0, 255]);
+    assert_eq!(pages.len(), 2);
+    for page in pages.iter() {
+        let atlas = multi_atlas.by_page_name(page["name"].as_str().unwrap()).unwrap();
+        let texture = &page["textures"][0];
+        let name = texture["name"].as_str().unwrap();
+        assert_eq!(Some(texture["index"].as_u64().unwrap() as usize), atlas.by_texture_name_index(name));
+        assert_eq!(texture["file"].as_str().unwrap(), format!("{}.png", name));
+    }
+}
+
+/// Unpacking fails on texture names that would escape the directory, and on textures
+/// in different pages that would overwrite each other.
+#[test]
+fn multi_atlas_unpacking_rejects_unusable_names() {
+    let directory = scratch_directory("multi_atlas_unpacking_rejects_unusable_names");
+    let escaping = MultiTextureAtlas2D::new(vec![pixel_atlas("atlas", "../escaped")]);
+    let duplicates = MultiTextureAtlas2D::new(vec![
+        pixel_atlas("atlas1", "sprite"),
+        pixel_atlas("atlas2", "sprite"),
+    ]);
+    let escaping_result = tex_atlas::unpack_to_directory(&escaping, directory.join("escaping"));
+    let duplicates_result = tex_atlas::unpack_to_directory(&duplicates, directory.join("duplicates"));
+
+    assert_eq!(escaping_result.unwrap_err().kind(), ErrorKind::InvalidTextureName);
+    assert!(!directory.join("escaped.png").exists());
+    assert_eq!(duplicates_result.unwrap_err().kind(), ErrorKind::DuplicateTextureName);
+}